@@ -2,6 +2,7 @@ use opentelemetry::api::{
     Context, CorrelationContextExt, Gauge, GaugeHandle, Key, Measure, MeasureHandle, Meter,
     MetricOptions, TraceContextExt, Tracer,
 };
+use opentelemetry::exporter::metrics::prometheus;
 use opentelemetry::{global, sdk};
 
 fn init_tracer() -> thrift::Result<()> {
@@ -30,9 +31,16 @@ fn init_tracer() -> thrift::Result<()> {
     Ok(())
 }
 
+fn init_meter() -> sdk::Controller {
+    let exporter = prometheus::PrometheusExporter::new(prometheus::default_registry().clone())
+        .expect("exporter is the only one registered with the default registry");
+    sdk::Controller::builder(exporter).build()
+}
+
 fn main() -> thrift::Result<()> {
     init_tracer()?;
-    let meter = sdk::Meter::new("ex_com_basic");
+    let controller = init_meter();
+    let meter = controller.meter("ex_com_basic");
 
     let foo_key = Key::new("ex.com/foo");
     let bar_key = Key::new("ex.com/bar");
@@ -86,5 +94,7 @@ fn main() -> thrift::Result<()> {
         });
     });
 
+    controller.collect();
+
     Ok(())
 }
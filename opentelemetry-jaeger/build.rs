@@ -0,0 +1,5 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    #[cfg(feature = "collector_grpc")]
+    tonic_build::configure().compile(&["proto/api_v2/collector.proto"], &["proto/api_v2"])?;
+    Ok(())
+}
@@ -1,18 +1,26 @@
 //! # UDP Jaeger Agent Client
 use crate::thrift::{agent, jaeger, zipkincore};
 use crate::transport::TUdpChannel;
+use agent::TAgentSyncClient;
 use std::fmt;
 use std::net::ToSocketAddrs;
 use thrift::protocol;
-use thrift::protocol::{TCompactInputProtocol, TCompactOutputProtocol};
+use thrift::protocol::{TCompactInputProtocol, TCompactOutputProtocol, TOutputProtocol};
 use thrift::transport::{ReadHalf, TIoChannel, WriteHalf};
 
+/// Upper bound of the bytes the `emitBatch` message adds to the encoded
+/// process and spans: the message header, the argument struct and the span
+/// list header.
+const EMIT_BATCH_OVERHEAD: usize = 70;
+
 /// `AgentSyncClientUDP` implements the `TAgentSyncClient` interface over UDP
 pub(crate) struct AgentSyncClientUDP {
     client: agent::AgentSyncClient<
         TCompactInputProtocol<ReadHalf<TUdpChannel>>,
         TCompactOutputProtocol<WriteHalf<TUdpChannel>>,
     >,
+    max_packet_size: usize,
+    dropped_spans: u64,
 }
 
 impl fmt::Debug for AgentSyncClientUDP {
@@ -31,16 +39,68 @@ impl AgentSyncClientUDP {
         max_packet_size: Option<usize>,
     ) -> thrift::Result<Self> {
         let transport = TUdpChannel::new(host_port, max_packet_size)?;
+        let max_packet_size = transport.max_packet_size();
         let (read, write) = transport.split()?;
         let client = agent::AgentSyncClient::new(
             protocol::TCompactInputProtocol::new(read),
             protocol::TCompactOutputProtocol::new(write),
         );
 
-        Ok(AgentSyncClientUDP { client })
+        Ok(AgentSyncClientUDP {
+            client,
+            max_packet_size,
+            dropped_spans: 0,
+        })
+    }
+
+    /// Emits the batch in as many packets as needed to stay below the max
+    /// packet size. Spans that don't fit in a packet on their own are dropped
+    /// and counted in `dropped_spans`.
+    pub(crate) fn emit_batch_split(&mut self, batch: jaeger::Batch) -> thrift::Result<()> {
+        let process_len = encoded_len(|o_prot| batch.process.write_to_out_protocol(o_prot))?;
+        let max_spans_len = self
+            .max_packet_size
+            .saturating_sub(EMIT_BATCH_OVERHEAD + process_len);
+
+        let mut spans = Vec::new();
+        let mut spans_len = 0;
+        for span in batch.spans {
+            let span_len = encoded_len(|o_prot| span.write_to_out_protocol(o_prot))?;
+            if span_len > max_spans_len {
+                self.dropped_spans += 1;
+                continue;
+            }
+            if spans_len + span_len > max_spans_len {
+                let full = std::mem::take(&mut spans);
+                self.emit_batch(jaeger::Batch::new(batch.process.clone(), full))?;
+                spans_len = 0;
+            }
+            spans.push(span);
+            spans_len += span_len;
+        }
+
+        if spans.is_empty() {
+            return Ok(());
+        }
+        self.emit_batch(jaeger::Batch::new(batch.process, spans))
+    }
+
+    /// Number of spans dropped for being larger than the max packet size.
+    pub(crate) fn dropped_spans(&self) -> u64 {
+        self.dropped_spans
     }
 }
 
+/// The length of a value in the compact protocol.
+fn encoded_len<F>(write: F) -> thrift::Result<usize>
+where
+    F: FnOnce(&mut dyn TOutputProtocol) -> thrift::Result<()>,
+{
+    let mut buf = Vec::new();
+    write(&mut TCompactOutputProtocol::new(&mut buf))?;
+    Ok(buf.len())
+}
+
 impl agent::TAgentSyncClient for AgentSyncClientUDP {
     /// Emit zipkin batch (Deprecated)
     fn emit_zipkin_batch(&mut self, spans: Vec<zipkincore::Span>) -> thrift::Result<()> {
@@ -52,3 +112,72 @@ impl agent::TAgentSyncClient for AgentSyncClientUDP {
         self.client.emit_batch(batch)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::UdpSocket;
+    use std::time::Duration;
+    use thrift::protocol::TInputProtocol;
+
+    fn span(span_id: i64, operation_name: &str) -> jaeger::Span {
+        jaeger::Span::new(
+            1,
+            0,
+            span_id,
+            0,
+            operation_name.to_string(),
+            None,
+            0,
+            0,
+            0,
+            None,
+            None,
+        )
+    }
+
+    /// Decodes the batches of the `emitBatch` packets received by the socket.
+    fn receive_batches(socket: &UdpSocket, max_packet_size: usize) -> Vec<jaeger::Batch> {
+        let mut batches = Vec::new();
+        let mut buf = vec![0; 65535];
+        while let Ok(len) = socket.recv(&mut buf) {
+            assert!(len <= max_packet_size);
+            let mut i_prot = TCompactInputProtocol::new(&buf[..len]);
+            i_prot.read_message_begin().unwrap();
+            i_prot.read_struct_begin().unwrap();
+            i_prot.read_field_begin().unwrap();
+            batches.push(jaeger::Batch::read_from_in_protocol(&mut i_prot).unwrap());
+        }
+        batches
+    }
+
+    #[test]
+    fn splits_batches_and_drops_oversized_spans() {
+        let agent = UdpSocket::bind("127.0.0.1:0").unwrap();
+        agent
+            .set_read_timeout(Some(Duration::from_millis(200)))
+            .unwrap();
+        let max_packet_size = 500;
+        let mut client =
+            AgentSyncClientUDP::new(agent.local_addr().unwrap(), Some(max_packet_size)).unwrap();
+
+        let mut spans = (0..40).map(|i| span(i, "operation")).collect::<Vec<_>>();
+        spans.insert(20, span(100, &"x".repeat(max_packet_size)));
+        let process = jaeger::Process::new("service".to_string(), None);
+        client
+            .emit_batch_split(jaeger::Batch::new(process, spans))
+            .unwrap();
+
+        let batches = receive_batches(&agent, max_packet_size);
+        assert!(batches.len() > 1);
+        let span_ids = batches
+            .iter()
+            .flat_map(|batch| batch.spans.iter().map(|span| span.span_id))
+            .collect::<Vec<_>>();
+        assert_eq!(span_ids, (0..40).collect::<Vec<_>>());
+        assert!(batches
+            .iter()
+            .all(|batch| batch.process.service_name == "service"));
+        assert_eq!(client.dropped_spans(), 1);
+    }
+}
@@ -0,0 +1,429 @@
+//! # gRPC Jaeger Collector Client
+use crate::proto::api_v2::{
+    collector_service_client::CollectorServiceClient, Batch, KeyValue, Log, PostSpansRequest,
+    Process, Span, SpanRef, SpanRefType, ValueType,
+};
+use crate::thrift::jaeger;
+use futures::channel::oneshot;
+use opentelemetry::exporter::trace;
+use opentelemetry::{api, sdk};
+use std::fmt;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use tonic::metadata::{MetadataKey, MetadataMap, MetadataValue};
+use tonic::transport::{Channel, ClientTlsConfig, Endpoint};
+
+/// `CollectorSyncClientGrpc` posts spans to the `jaeger.api_v2.CollectorService`
+///
+/// Requests are driven on the client's own runtime so spans can be exported
+/// from both synchronous and asynchronous span processors.
+pub(crate) struct CollectorSyncClientGrpc {
+    client: CollectorServiceClient<Channel>,
+    metadata: MetadataMap,
+    timeout: Duration,
+    runtime: tokio::runtime::Handle,
+    // Dropping the sender stops the runtime thread.
+    _shutdown: oneshot::Sender<()>,
+}
+
+impl fmt::Debug for CollectorSyncClientGrpc {
+    /// Debug info
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("CollectorSyncClientGrpc")
+            .field("metadata", &self.metadata)
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
+impl CollectorSyncClientGrpc {
+    /// Create a new gRPC collector client, the connection is established on
+    /// first upload.
+    pub(crate) fn new(
+        endpoint: String,
+        tls_config: Option<ClientTlsConfig>,
+        metadata: Vec<(String, String)>,
+        timeout: Duration,
+    ) -> thrift::Result<Self> {
+        let mut endpoint = Endpoint::from_shared(endpoint).map_err(|err| {
+            thrift::Error::from(format!("invalid collector gRPC endpoint: {}", err))
+        })?;
+        if let Some(tls_config) = tls_config {
+            endpoint = endpoint.tls_config(tls_config).map_err(|err| {
+                thrift::Error::from(format!("invalid collector gRPC TLS config: {}", err))
+            })?;
+        }
+
+        let mut metadata_map = MetadataMap::with_capacity(metadata.len());
+        for (key, value) in metadata {
+            let invalid = || thrift::Error::from(format!("invalid gRPC metadata `{}`", key));
+            let parsed_key = MetadataKey::from_bytes(key.as_bytes()).map_err(|_| invalid())?;
+            let parsed_value = MetadataValue::from_str(&value).map_err(|_| invalid())?;
+            metadata_map.append(parsed_key, parsed_value);
+        }
+
+        let mut runtime = tokio::runtime::Builder::new()
+            .threaded_scheduler()
+            .core_threads(1)
+            .thread_name("opentelemetry-jaeger")
+            .enable_all()
+            .build()?;
+        let handle = runtime.handle().clone();
+        let channel = handle.enter(|| endpoint.connect_lazy()).map_err(|err| {
+            thrift::Error::from(format!("invalid collector gRPC channel: {}", err))
+        })?;
+        // The runtime is owned by its own thread, so the client can be
+        // dropped from within an asynchronous context.
+        let (shutdown, shutdown_rx) = oneshot::channel();
+        thread::Builder::new()
+            .name("opentelemetry-jaeger".to_string())
+            .spawn(move || {
+                let _ = runtime.block_on(shutdown_rx);
+            })?;
+
+        Ok(CollectorSyncClientGrpc {
+            client: CollectorServiceClient::new(channel),
+            metadata: metadata_map,
+            timeout,
+            runtime: handle,
+            _shutdown: shutdown,
+        })
+    }
+
+    /// Post a batch of spans to the collector, blocking until the request
+    /// completes or times out.
+    pub(crate) fn post_spans(&self, batch: Batch) -> trace::ExportResult {
+        let mut request = tonic::Request::new(PostSpansRequest { batch: Some(batch) });
+        *request.metadata_mut() = self.metadata.clone();
+        let mut client = self.client.clone();
+        let timeout = self.timeout;
+        let handle = self.runtime.spawn(async move {
+            match tokio::time::timeout(timeout, client.post_spans(request)).await {
+                Ok(Ok(_)) => trace::ExportResult::Success,
+                Ok(Err(status)) => grpc_result(status.code()),
+                Err(_) => trace::ExportResult::FailedRetryable,
+            }
+        });
+
+        futures::executor::block_on(handle).unwrap_or(trace::ExportResult::FailedNotRetryable)
+    }
+}
+
+/// Map gRPC status codes to export results, transient failures are retryable.
+fn grpc_result(code: tonic::Code) -> trace::ExportResult {
+    match code {
+        tonic::Code::Ok => trace::ExportResult::Success,
+        // tonic reports connection failures as `Unknown`
+        tonic::Code::Unknown
+        | tonic::Code::Cancelled
+        | tonic::Code::DeadlineExceeded
+        | tonic::Code::ResourceExhausted
+        | tonic::Code::Aborted
+        | tonic::Code::OutOfRange
+        | tonic::Code::Unavailable
+        | tonic::Code::DataLoss => trace::ExportResult::FailedRetryable,
+        _ => trace::ExportResult::FailedNotRetryable,
+    }
+}
+
+/// Convert spans to an `api_v2` batch for exporting.
+pub(crate) fn build_batch(process: &jaeger::Process, spans: Vec<Arc<trace::SpanData>>) -> Batch {
+    Batch {
+        spans: spans.into_iter().map(convert_span).collect(),
+        process: Some(Process {
+            service_name: process.service_name.clone(),
+            tags: process
+                .tags
+                .iter()
+                .flatten()
+                .cloned()
+                .map(convert_tag)
+                .collect(),
+        }),
+    }
+}
+
+fn convert_span(span_data: Arc<trace::SpanData>) -> Span {
+    let trace_id = trace_id_bytes(span_data.span_context.trace_id());
+    let mut references = Vec::with_capacity(span_data.links.len() + 1);
+    // The parent is the first `CHILD_OF` reference in the api_v2 model
+    if span_data.parent_span_id != api::SpanId::invalid() {
+        references.push(SpanRef {
+            trace_id: trace_id.clone(),
+            span_id: span_id_bytes(span_data.parent_span_id),
+            ref_type: SpanRefType::ChildOf as i32,
+        });
+    }
+    references.extend(span_data.links.iter().map(|link| SpanRef {
+        trace_id: trace_id_bytes(link.span_context().trace_id()),
+        span_id: span_id_bytes(link.span_context().span_id()),
+        ref_type: SpanRefType::FollowsFrom as i32,
+    }));
+
+    Span {
+        trace_id,
+        span_id: span_id_bytes(span_data.span_context.span_id()),
+        operation_name: span_data.name.clone(),
+        references,
+        flags: u32::from(span_data.span_context.trace_flags()),
+        start_time: Some(span_data.start_time.into()),
+        duration: Some(
+            span_data
+                .end_time
+                .duration_since(span_data.start_time)
+                .unwrap_or_default()
+                .into(),
+        ),
+        tags: crate::build_tags(&span_data)
+            .into_iter()
+            .map(convert_key_value)
+            .collect(),
+        logs: convert_events(&span_data.message_events),
+        process: None,
+        process_id: String::new(),
+        warnings: Vec::new(),
+    }
+}
+
+fn convert_events(events: &sdk::EvictedQueue<api::Event>) -> Vec<Log> {
+    events
+        .iter()
+        .map(|event| Log {
+            timestamp: Some(event.timestamp.into()),
+            fields: event
+                .attributes
+                .iter()
+                .cloned()
+                .chain(Some(api::Key::new("name").string(event.name.clone())))
+                .map(convert_key_value)
+                .collect(),
+        })
+        .collect()
+}
+
+fn convert_key_value(kv: api::KeyValue) -> KeyValue {
+    let mut key_value = KeyValue {
+        key: kv.key.into(),
+        ..Default::default()
+    };
+    match kv.value {
+        api::Value::String(s) => key_value.v_str = s,
+        api::Value::F64(f) => {
+            key_value.v_type = ValueType::Float64 as i32;
+            key_value.v_float64 = f;
+        }
+        api::Value::Bool(b) => {
+            key_value.v_type = ValueType::Bool as i32;
+            key_value.v_bool = b;
+        }
+        api::Value::I64(i) => {
+            key_value.v_type = ValueType::Int64 as i32;
+            key_value.v_int64 = i;
+        }
+        api::Value::Bytes(b) => {
+            key_value.v_type = ValueType::Binary as i32;
+            key_value.v_binary = b;
+        }
+        // Like the thrift model, api_v2 only has signed integers
+        api::Value::U64(u) => key_value.v_str = u.to_string(),
+        v @ api::Value::Array(_) => key_value.v_str = v.into(),
+    }
+
+    key_value
+}
+
+fn convert_tag(tag: jaeger::Tag) -> KeyValue {
+    let mut key_value = KeyValue {
+        key: tag.key,
+        v_str: tag.v_str.unwrap_or_default(),
+        v_bool: tag.v_bool.unwrap_or_default(),
+        v_int64: tag.v_long.unwrap_or_default(),
+        v_float64: tag
+            .v_double
+            .map(|double| double.into_inner())
+            .unwrap_or_default(),
+        v_binary: tag.v_binary.unwrap_or_default(),
+        ..Default::default()
+    };
+    key_value.v_type = match tag.v_type {
+        jaeger::TagType::String => ValueType::String,
+        jaeger::TagType::Double => ValueType::Float64,
+        jaeger::TagType::Bool => ValueType::Bool,
+        jaeger::TagType::Long => ValueType::Int64,
+        jaeger::TagType::Binary => ValueType::Binary,
+    } as i32;
+
+    key_value
+}
+
+fn trace_id_bytes(trace_id: api::TraceId) -> Vec<u8> {
+    trace_id.to_u128().to_be_bytes().to_vec()
+}
+
+fn span_id_bytes(span_id: api::SpanId) -> Vec<u8> {
+    span_id.to_u64().to_be_bytes().to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::api_v2::collector_service_server::{
+        CollectorService, CollectorServiceServer,
+    };
+    use crate::proto::api_v2::PostSpansResponse;
+    use opentelemetry::exporter::trace::SpanExporter;
+    use std::sync::Mutex;
+    use std::time::SystemTime;
+
+    /// Stand-in collector recording every request it receives.
+    #[derive(Debug, Default, Clone)]
+    struct Collector {
+        requests: Arc<Mutex<Vec<(MetadataMap, PostSpansRequest)>>>,
+    }
+
+    #[tonic::async_trait]
+    impl CollectorService for Collector {
+        async fn post_spans(
+            &self,
+            request: tonic::Request<PostSpansRequest>,
+        ) -> Result<tonic::Response<PostSpansResponse>, tonic::Status> {
+            let metadata = request.metadata().clone();
+            self.requests
+                .lock()
+                .unwrap()
+                .push((metadata, request.into_inner()));
+            Ok(tonic::Response::new(PostSpansResponse {}))
+        }
+    }
+
+    fn span_data(span_id: u64, parent_span_id: u64, name: &str) -> Arc<trace::SpanData> {
+        let mut attributes = sdk::EvictedHashMap::new(32);
+        attributes.insert(api::Key::new("http.status_code").i64(200));
+        let mut message_events = sdk::EvictedQueue::new(32);
+        message_events.extend(Some(api::Event::with_name("started".to_string())));
+        Arc::new(trace::SpanData {
+            span_context: api::SpanContext::new(
+                api::TraceId::from_u128(0x0102_0304_0506_0708_090a_0b0c_0d0e_0f10),
+                api::SpanId::from_u64(span_id),
+                api::TRACE_FLAG_SAMPLED,
+                false,
+                api::TraceState::default(),
+            ),
+            parent_span_id: api::SpanId::from_u64(parent_span_id),
+            span_kind: api::SpanKind::Server,
+            start_time: SystemTime::UNIX_EPOCH + Duration::from_millis(1500),
+            end_time: SystemTime::UNIX_EPOCH + Duration::from_millis(2000),
+            attributes,
+            message_events,
+            ..crate::tests::span_data(name, Arc::new(sdk::Resource::default()))
+        })
+    }
+
+    #[test]
+    fn can_be_dropped_inside_a_runtime() {
+        let mut runtime = tokio::runtime::Runtime::new().unwrap();
+        let exporter = crate::Exporter::builder()
+            .with_collector_grpc_endpoint("http://localhost:14250")
+            .init()
+            .unwrap();
+        let task = runtime.spawn(async move { drop(exporter) });
+
+        runtime.block_on(task).unwrap();
+    }
+
+    #[test]
+    fn posts_spans_to_collector() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let collector = Collector::default();
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let service = CollectorServiceServer::new(collector.clone());
+        runtime.spawn(async move {
+            let mut listener = tokio::net::TcpListener::from_std(listener).unwrap();
+            tonic::transport::Server::builder()
+                .add_service(service)
+                .serve_with_incoming(listener.incoming())
+                .await
+                .unwrap();
+        });
+
+        let exporter = crate::Exporter::builder()
+            .with_collector_grpc_endpoint(format!("http://{}", addr))
+            .with_collector_grpc_metadata("x-tenant", "tenant-a")
+            .with_process(crate::Process {
+                service_name: "grpc-service".to_string(),
+                tags: vec![api::Key::new("hostname").string("host-a")],
+            })
+            .init()
+            .unwrap();
+
+        assert_eq!(
+            exporter.export(vec![span_data(2, 1, "child"), span_data(1, 0, "root")]),
+            trace::ExportResult::Success
+        );
+        let requests = collector.requests.lock().unwrap().clone();
+        assert_eq!(requests.len(), 1);
+        let (metadata, request) = &requests[0];
+        assert_eq!(metadata.get("x-tenant").unwrap(), "tenant-a");
+
+        let batch = request.batch.as_ref().unwrap();
+        let process = batch.process.as_ref().unwrap();
+        assert_eq!(process.service_name, "grpc-service");
+        assert_eq!(process.tags[0].key, "hostname");
+        assert_eq!(process.tags[0].v_str, "host-a");
+
+        let child = &batch.spans[0];
+        assert_eq!(child.operation_name, "child");
+        assert_eq!(child.trace_id, (1..=16).collect::<Vec<u8>>());
+        assert_eq!(child.span_id, vec![0, 0, 0, 0, 0, 0, 0, 2]);
+        assert_eq!(child.references.len(), 1);
+        assert_eq!(child.references[0].span_id, vec![0, 0, 0, 0, 0, 0, 0, 1]);
+        assert_eq!(child.references[0].ref_type, SpanRefType::ChildOf as i32);
+        assert_eq!(child.flags, 1);
+        let start_time = child.start_time.as_ref().unwrap();
+        assert_eq!((start_time.seconds, start_time.nanos), (1, 500_000_000));
+        let duration = child.duration.as_ref().unwrap();
+        assert_eq!((duration.seconds, duration.nanos), (0, 500_000_000));
+        let status_code = child
+            .tags
+            .iter()
+            .find(|tag| tag.key == "http.status_code")
+            .unwrap();
+        assert_eq!(status_code.v_type, ValueType::Int64 as i32);
+        assert_eq!(status_code.v_int64, 200);
+        assert_eq!(child.logs[0].fields[0].key, "name");
+        assert_eq!(child.logs[0].fields[0].v_str, "started");
+        assert!(batch.spans[1].references.is_empty());
+    }
+
+    #[test]
+    fn unreachable_collector_is_retryable() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+        let exporter = crate::Exporter::builder()
+            .with_collector_grpc_endpoint(format!("http://{}", addr))
+            .with_collector_grpc_timeout(Duration::from_secs(1))
+            .init()
+            .unwrap();
+
+        assert_eq!(
+            exporter.export(vec![span_data(1, 0, "lost")]),
+            trace::ExportResult::FailedRetryable
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_configuration() {
+        assert!(crate::Exporter::builder()
+            .with_collector_grpc_endpoint("not a uri")
+            .init()
+            .is_err());
+        assert!(crate::Exporter::builder()
+            .with_collector_grpc_endpoint("http://localhost:14250")
+            .with_collector_grpc_metadata("invalid key", "value")
+            .init()
+            .is_err());
+    }
+}
@@ -89,20 +89,65 @@
 //! }
 //! ```
 //!
+//! ### Jaeger gRPC Collector
+//!
+//! With the optional `collector_grpc` feature, spans are posted to the
+//! `jaeger.api_v2.CollectorService` of a Jaeger collector over gRPC, by
+//! default listening on port `14250`. TLS, request metadata and a timeout
+//! can be configured on the builder.
+//!
+//! Each export blocks the calling thread until the collector responds or the
+//! timeout elapses, see [batching with blocking exporters] for running the
+//! batch worker off the executor threads.
+//!
+//! ```rust,ignore
+//! // Note that this requires the `collector_grpc` feature.
+//!
+//! let exporter = opentelemetry_jaeger::Exporter::builder()
+//!     .with_collector_grpc_endpoint("http://localhost:14250")
+//!     .with_collector_grpc_metadata("x-tenant", "my-tenant")
+//!     .with_collector_grpc_timeout(std::time::Duration::from_secs(5))
+//!     .with_process(opentelemetry_jaeger::Process {
+//!         service_name: "trace-demo".to_string(),
+//!         tags: Vec::new(),
+//!     })
+//!     .init()?;
+//! ```
+//!
+//! ### Jaeger Remote Sampling
+//!
+//! With the optional `remote_sampler` feature, the [`JaegerRemoteSampler`]
+//! polls the sampling strategy of a service from a Jaeger agent, so sampling
+//! rates can be changed centrally. It supports the `probabilistic`,
+//! `rateLimiting` and per-operation strategies.
+//!
 //! [Jaeger Docs]: https://www.jaegertracing.io/docs/
 //! [`with_collector_endpoint`]: struct.Builder.html#with_collector_endpoint
+//! [`JaegerRemoteSampler`]: struct.JaegerRemoteSampler.html
+//! [batching with blocking exporters]: https://docs.rs/opentelemetry/0.6.0/opentelemetry/sdk/trace/span_processor/index.html#batching-with-blocking-exporters
 #![deny(missing_docs, unreachable_pub, missing_debug_implementations)]
 #![cfg_attr(test, deny(warnings))]
 mod agent;
 #[cfg(feature = "collector_client")]
 mod collector;
+#[cfg(feature = "collector_grpc")]
+mod collector_grpc;
+#[cfg(feature = "collector_grpc")]
+#[allow(clippy::all, unreachable_pub, dead_code, missing_docs)]
+mod proto;
+#[cfg(feature = "remote_sampler")]
+mod sampler;
 #[allow(clippy::all, unreachable_pub, dead_code)]
 mod thrift;
 pub(crate) mod transport;
 mod uploader;
 
 use self::thrift::jaeger;
+use opentelemetry::sdk::env::{self, EnvError};
+use opentelemetry::semantic_conventions as semcov;
 use opentelemetry::{api, exporter::trace, sdk};
+#[cfg(feature = "remote_sampler")]
+pub use sampler::{JaegerRemoteSampler, RemoteSamplerBuilder};
 use std::sync::{Arc, Mutex};
 use std::{
     net,
@@ -113,6 +158,26 @@ use std::{
 static DEFAULT_SERVICE_NAME: &str = "OpenTelemetry";
 /// Default agent endpoint if none is provided
 static DEFAULT_AGENT_ENDPOINT: &str = "127.0.0.1:6831";
+/// Default agent host if only the port is configured
+static DEFAULT_AGENT_HOST: &str = "127.0.0.1";
+/// Default agent port if only the host is configured
+const DEFAULT_AGENT_PORT: u16 = 6831;
+
+/// Environment variable with the agent host.
+const OTEL_EXPORTER_JAEGER_AGENT_HOST: &str = "OTEL_EXPORTER_JAEGER_AGENT_HOST";
+/// Environment variable with the agent port.
+const OTEL_EXPORTER_JAEGER_AGENT_PORT: &str = "OTEL_EXPORTER_JAEGER_AGENT_PORT";
+/// Environment variable with the collector endpoint.
+const OTEL_EXPORTER_JAEGER_ENDPOINT: &str = "OTEL_EXPORTER_JAEGER_ENDPOINT";
+/// Environment variable with the collector username.
+const OTEL_EXPORTER_JAEGER_USER: &str = "OTEL_EXPORTER_JAEGER_USER";
+/// Environment variable with the collector password.
+const OTEL_EXPORTER_JAEGER_PASSWORD: &str = "OTEL_EXPORTER_JAEGER_PASSWORD";
+/// Environment variable with the service name.
+const OTEL_SERVICE_NAME: &str = "OTEL_SERVICE_NAME";
+/// Default timeout for a single export to the gRPC collector.
+#[cfg(feature = "collector_grpc")]
+const DEFAULT_COLLECTOR_GRPC_TIMEOUT: Duration = Duration::from_secs(10);
 
 /// Jaeger span exporter
 #[derive(Debug)]
@@ -122,6 +187,10 @@ pub struct Exporter {
 }
 
 /// Jaeger process configuration
+///
+/// The attributes of the span resource are added to the configured process:
+/// `service.name` replaces the service name and all other attributes are
+/// reported as process tags.
 #[derive(Debug, Default)]
 pub struct Process {
     /// Jaeger service name
@@ -145,6 +214,16 @@ impl Exporter {
         Builder::default()
     }
 
+    /// Number of spans dropped because they alone exceeded the max packet
+    /// size of the agent transport. Larger batches are split into multiple
+    /// packets instead.
+    pub fn dropped_span_count(&self) -> u64 {
+        self.uploader
+            .lock()
+            .map(|uploader| uploader.dropped_spans())
+            .unwrap_or(0)
+    }
+
     /// Default `Exporter` with initialized uploader.
     pub fn init_default() -> Result<Self, ::thrift::Error> {
         Exporter::builder()
@@ -155,14 +234,30 @@ impl Exporter {
 
 impl trace::SpanExporter for Exporter {
     /// Export spans to Jaeger
+    ///
+    /// Spans are uploaded in one batch per resource, with the resource
+    /// attributes reported as the batch process.
+    ///
+    /// A retry re-exports the whole batch, so the export is only reported as
+    /// retryable when no resource batch was uploaded and none of them failed
+    /// with a non-retryable error. Once any resource batch has been uploaded,
+    /// failures of the others are reported as non-retryable to avoid
+    /// duplicating spans.
     fn export(&self, batch: Vec<Arc<trace::SpanData>>) -> trace::ExportResult {
-        match self.uploader.lock() {
-            Ok(mut uploader) => {
-                let jaeger_spans = batch.into_iter().map(Into::into).collect();
-                uploader.upload(jaeger::Batch::new(self.process.clone(), jaeger_spans))
-            }
-            Err(_) => trace::ExportResult::FailedNotRetryable,
-        }
+        let mut uploader = match self.uploader.lock() {
+            Ok(uploader) => uploader,
+            Err(_) => return trace::ExportResult::FailedNotRetryable,
+        };
+
+        let results = group_by_resource(batch)
+            .into_iter()
+            .map(|(resource, spans)| {
+                let process = build_process(&self.process, &resource);
+                uploader.upload(&process, spans)
+            })
+            .collect::<Vec<_>>();
+
+        merge_results(&results)
     }
 
     /// Ignored for now.
@@ -179,6 +274,14 @@ pub struct Builder<T: net::ToSocketAddrs> {
     collector_username: Option<String>,
     #[cfg(feature = "collector_client")]
     collector_password: Option<String>,
+    #[cfg(feature = "collector_grpc")]
+    collector_grpc_endpoint: Option<String>,
+    #[cfg(feature = "collector_grpc")]
+    collector_grpc_tls_config: Option<tonic::transport::ClientTlsConfig>,
+    #[cfg(feature = "collector_grpc")]
+    collector_grpc_metadata: Vec<(String, String)>,
+    #[cfg(feature = "collector_grpc")]
+    collector_grpc_timeout: Duration,
     process: Process,
 }
 
@@ -193,6 +296,14 @@ impl<T: net::ToSocketAddrs> Default for Builder<T> {
             collector_username: None,
             #[cfg(feature = "collector_client")]
             collector_password: None,
+            #[cfg(feature = "collector_grpc")]
+            collector_grpc_endpoint: None,
+            #[cfg(feature = "collector_grpc")]
+            collector_grpc_tls_config: None,
+            #[cfg(feature = "collector_grpc")]
+            collector_grpc_metadata: Vec::new(),
+            #[cfg(feature = "collector_grpc")]
+            collector_grpc_timeout: DEFAULT_COLLECTOR_GRPC_TIMEOUT,
             process: Process {
                 service_name: DEFAULT_SERVICE_NAME.to_string(),
                 tags: Vec::new(),
@@ -237,6 +348,54 @@ impl<T: net::ToSocketAddrs> Builder<T> {
         }
     }
 
+    /// Assign the endpoint of the collector gRPC service, e.g.
+    /// `http://localhost:14250`.
+    ///
+    /// Exports to the gRPC collector block the calling thread for up to the
+    /// configured timeout, see the [crate documentation](index.html#jaeger-grpc-collector).
+    #[cfg(feature = "collector_grpc")]
+    pub fn with_collector_grpc_endpoint<S: Into<String>>(self, endpoint: S) -> Self {
+        Builder {
+            collector_grpc_endpoint: Some(endpoint.into()),
+            ..self
+        }
+    }
+
+    /// Assign the TLS config used to connect to the collector gRPC service,
+    /// the endpoint must use the `https` scheme.
+    #[cfg(feature = "collector_grpc")]
+    pub fn with_collector_grpc_tls_config(
+        self,
+        tls_config: tonic::transport::ClientTlsConfig,
+    ) -> Self {
+        Builder {
+            collector_grpc_tls_config: Some(tls_config),
+            ..self
+        }
+    }
+
+    /// Add metadata sent with every request to the collector gRPC service.
+    #[cfg(feature = "collector_grpc")]
+    pub fn with_collector_grpc_metadata<K: Into<String>, V: Into<String>>(
+        mut self,
+        key: K,
+        value: V,
+    ) -> Self {
+        self.collector_grpc_metadata
+            .push((key.into(), value.into()));
+        self
+    }
+
+    /// Assign the maximum time a single export to the collector gRPC service
+    /// may take, defaults to 10 seconds.
+    #[cfg(feature = "collector_grpc")]
+    pub fn with_collector_grpc_timeout(self, timeout: Duration) -> Self {
+        Builder {
+            collector_grpc_timeout: timeout,
+            ..self
+        }
+    }
+
     /// Assign the exporter process config.
     pub fn with_process(self, process: Process) -> Self {
         Builder { process, ..self }
@@ -252,7 +411,7 @@ impl<T: net::ToSocketAddrs> Builder<T> {
         })
     }
 
-    #[cfg(not(feature = "collector_client"))]
+    #[cfg(not(any(feature = "collector_client", feature = "collector_grpc")))]
     fn init_uploader(self) -> ::thrift::Result<(Process, uploader::BatchUploader)> {
         let agent = if let Some(endpoint) = self.agent_endpoint {
             agent::AgentSyncClientUDP::new(endpoint, None)?
@@ -266,27 +425,169 @@ impl<T: net::ToSocketAddrs> Builder<T> {
         Ok((self.process, uploader::BatchUploader::Agent(agent)))
     }
 
-    #[cfg(feature = "collector_client")]
+    #[cfg(any(feature = "collector_client", feature = "collector_grpc"))]
     fn init_uploader(self) -> ::thrift::Result<(Process, uploader::BatchUploader)> {
-        if self.agent_endpoint.is_some() {
-            let agent = agent::AgentSyncClientUDP::new(self.agent_endpoint.unwrap(), None)?;
-            Ok((self.process, uploader::BatchUploader::Agent(agent)))
-        } else if self.collector_endpoint.is_some() {
-            let collector = collector::CollectorSyncClientHttp::new(
-                self.collector_endpoint.unwrap(),
-                self.collector_username,
-                self.collector_password,
-            )?;
-            Ok((
-                self.process,
-                uploader::BatchUploader::Collector(Box::new(collector)),
-            ))
+        if let Some(agent_endpoint) = self.agent_endpoint {
+            let agent = agent::AgentSyncClientUDP::new(agent_endpoint, None)?;
+            return Ok((self.process, uploader::BatchUploader::Agent(agent)));
+        }
+
+        #[cfg(feature = "collector_client")]
+        {
+            if let Some(collector_endpoint) = self.collector_endpoint {
+                let collector = collector::CollectorSyncClientHttp::new(
+                    collector_endpoint,
+                    self.collector_username,
+                    self.collector_password,
+                )?;
+                return Ok((
+                    self.process,
+                    uploader::BatchUploader::Collector(Box::new(collector)),
+                ));
+            }
+        }
+
+        #[cfg(feature = "collector_grpc")]
+        {
+            if let Some(collector_grpc_endpoint) = self.collector_grpc_endpoint {
+                let collector = collector_grpc::CollectorSyncClientGrpc::new(
+                    collector_grpc_endpoint,
+                    self.collector_grpc_tls_config,
+                    self.collector_grpc_metadata,
+                    self.collector_grpc_timeout,
+                )?;
+                return Ok((
+                    self.process,
+                    uploader::BatchUploader::CollectorGrpc(Box::new(collector)),
+                ));
+            }
+        }
+
+        Err(::thrift::Error::from(
+            "Collector endpoint or agent endpoint must be set",
+        ))
+    }
+}
+
+impl Builder<String> {
+    /// Create a builder configured from the standard environment variables.
+    /// Settings assigned on the returned builder take precedence.
+    ///
+    /// The following variables are supported:
+    ///
+    /// - `OTEL_EXPORTER_JAEGER_AGENT_HOST` and `OTEL_EXPORTER_JAEGER_AGENT_PORT`
+    /// - `OTEL_EXPORTER_JAEGER_ENDPOINT`, `OTEL_EXPORTER_JAEGER_USER` and
+    ///   `OTEL_EXPORTER_JAEGER_PASSWORD`, which require the `collector_client`
+    ///   feature
+    /// - `OTEL_SERVICE_NAME`
+    pub fn from_env() -> Result<Self, EnvError> {
+        let mut builder = Builder::default();
+
+        let host = env::var(OTEL_EXPORTER_JAEGER_AGENT_HOST)?;
+        let port = env::parse::<u16>(OTEL_EXPORTER_JAEGER_AGENT_PORT)?;
+        if host.is_some() || port.is_some() {
+            let host = host.unwrap_or_else(|| DEFAULT_AGENT_HOST.to_string());
+            let port = port.unwrap_or(DEFAULT_AGENT_PORT);
+            builder.agent_endpoint = Some(if host.contains(':') && !host.starts_with('[') {
+                format!("[{}]:{}", host, port)
+            } else {
+                format!("{}:{}", host, port)
+            });
+        }
+
+        for name in &[
+            OTEL_EXPORTER_JAEGER_ENDPOINT,
+            OTEL_EXPORTER_JAEGER_USER,
+            OTEL_EXPORTER_JAEGER_PASSWORD,
+        ] {
+            if let Some(value) = env::var(name)? {
+                builder = builder.with_collector_env(name, value)?;
+            }
+        }
+
+        if let Some(service_name) = env::var(OTEL_SERVICE_NAME)? {
+            builder.process.service_name = service_name;
+        }
+
+        Ok(builder)
+    }
+
+    #[cfg(feature = "collector_client")]
+    fn with_collector_env(self, name: &str, value: String) -> Result<Self, EnvError> {
+        Ok(match name {
+            OTEL_EXPORTER_JAEGER_ENDPOINT => self.with_collector_endpoint(value),
+            OTEL_EXPORTER_JAEGER_USER => self.with_collector_username(value),
+            _ => self.with_collector_password(value),
+        })
+    }
+
+    #[cfg(not(feature = "collector_client"))]
+    fn with_collector_env(self, name: &str, value: String) -> Result<Self, EnvError> {
+        let value = if name == OTEL_EXPORTER_JAEGER_PASSWORD {
+            "<redacted>".to_string()
         } else {
-            Err(::thrift::Error::from(
-                "Collector endpoint or agent endpoint must be set",
-            ))
+            value
+        };
+        Err(EnvError::invalid_value(
+            name,
+            &value,
+            "the collector_client feature is not enabled",
+        ))
+    }
+}
+
+/// Group spans by resource, keeping the order in which resources were seen.
+fn group_by_resource(
+    batch: Vec<Arc<trace::SpanData>>,
+) -> Vec<(Arc<sdk::Resource>, Vec<Arc<trace::SpanData>>)> {
+    let mut groups: Vec<(Arc<sdk::Resource>, Vec<Arc<trace::SpanData>>)> = Vec::new();
+    for span in batch {
+        // Spans of a provider share the same resource
+        match groups.iter_mut().find(|(resource, _)| {
+            Arc::ptr_eq(resource, &span.resource) || **resource == *span.resource
+        }) {
+            Some((_, spans)) => spans.push(span),
+            None => groups.push((span.resource.clone(), vec![span])),
         }
     }
+
+    groups
+}
+
+/// Combine the results of the per-resource uploads of one export.
+fn merge_results(results: &[trace::ExportResult]) -> trace::ExportResult {
+    let succeeded = results
+        .iter()
+        .filter(|result| **result == trace::ExportResult::Success)
+        .count();
+    if succeeded == results.len() {
+        trace::ExportResult::Success
+    } else if succeeded == 0
+        && results
+            .iter()
+            .all(|result| *result == trace::ExportResult::FailedRetryable)
+    {
+        trace::ExportResult::FailedRetryable
+    } else {
+        trace::ExportResult::FailedNotRetryable
+    }
+}
+
+/// Build the process of a batch from the configured process and the resource
+/// of its spans.
+fn build_process(process: &jaeger::Process, resource: &sdk::Resource) -> jaeger::Process {
+    let mut process = process.clone();
+    let mut tags = process.tags.take().unwrap_or_default();
+    for (key, value) in resource.iter() {
+        if *key == semcov::resource::SERVICE_NAME {
+            process.service_name = value.clone().into();
+        } else {
+            tags.push(api::KeyValue::new(key.clone(), value.clone()).into());
+        }
+    }
+    process.tags = Some(tags);
+
+    process
 }
 
 #[rustfmt::skip]
@@ -349,7 +650,7 @@ impl Into<jaeger::Span> for Arc<trace::SpanData> {
                 .duration_since(self.start_time)
                 .unwrap_or_else(|_| Duration::from_secs(0))
                 .as_micros() as i64,
-            tags: build_tags(&self),
+            tags: Some(build_tags(&self).into_iter().map(Into::into).collect()),
             logs: events_to_logs(&self.message_events),
         }
     }
@@ -381,57 +682,38 @@ fn links_to_references(links: &sdk::EvictedQueue<api::Link>) -> Option<Vec<jaege
     }
 }
 
-fn build_tags(span_data: &Arc<trace::SpanData>) -> Option<Vec<jaeger::Tag>> {
+fn build_tags(span_data: &trace::SpanData) -> Vec<api::KeyValue> {
     let mut user_overrides = UserOverrides::default();
     // TODO determine if namespacing is required to avoid collisions with set attributes
     let mut tags = span_data
         .attributes
         .iter()
         .map(|(k, v)| {
-            user_overrides.record_attr(k.as_str());
-            api::KeyValue::new(k.clone(), v.clone()).into()
+            user_overrides.record_attr(k);
+            api::KeyValue::new(k.clone(), v.clone())
         })
-        .chain(
-            span_data
-                .resource
-                .iter()
-                .map(|(k, v)| api::KeyValue::new(k.clone(), v.clone()).into()),
-        )
         .collect::<Vec<_>>();
 
     // Ensure error status is set
     if span_data.status_code != api::StatusCode::OK && !user_overrides.error {
-        tags.push(api::Key::new(ERROR).bool(true).into())
+        tags.push(semcov::trace::ERROR.bool(true))
     }
 
     if !user_overrides.span_kind {
-        tags.push(
-            api::Key::new(SPAN_KIND)
-                .string(span_data.span_kind.to_string())
-                .into(),
-        );
+        tags.push(semcov::trace::SPAN_KIND.string(span_data.span_kind.to_string()));
     }
 
     if !user_overrides.status_code {
-        tags.push(api::KeyValue::new(STATUS_CODE, span_data.status_code.clone() as i64).into());
+        tags.push(semcov::trace::STATUS_CODE.i64(span_data.status_code.clone() as i64));
     }
 
     if !user_overrides.status_message {
-        tags.push(
-            api::Key::new(STATUS_MESSAGE)
-                .string(span_data.status_message.clone())
-                .into(),
-        );
+        tags.push(semcov::trace::STATUS_MESSAGE.string(span_data.status_message.clone()));
     }
 
-    Some(tags)
+    tags
 }
 
-const ERROR: &str = "error";
-const SPAN_KIND: &str = "span.kind";
-const STATUS_CODE: &str = "status.code";
-const STATUS_MESSAGE: &str = "status.message";
-
 #[derive(Default)]
 struct UserOverrides {
     error: bool,
@@ -441,13 +723,15 @@ struct UserOverrides {
 }
 
 impl UserOverrides {
-    fn record_attr(&mut self, attr: &str) {
-        match attr {
-            ERROR => self.error = true,
-            SPAN_KIND => self.span_kind = true,
-            STATUS_CODE => self.status_code = true,
-            STATUS_MESSAGE => self.status_message = true,
-            _ => (),
+    fn record_attr(&mut self, attr: &api::Key) {
+        if *attr == semcov::trace::ERROR {
+            self.error = true;
+        } else if *attr == semcov::trace::SPAN_KIND {
+            self.span_kind = true;
+        } else if *attr == semcov::trace::STATUS_CODE {
+            self.status_code = true;
+        } else if *attr == semcov::trace::STATUS_MESSAGE {
+            self.status_message = true;
         }
     }
 }
@@ -459,3 +743,119 @@ fn events_to_logs(events: &sdk::EvictedQueue<api::Event>) -> Option<Vec<jaeger::
         Some(events.iter().cloned().map(Into::into).collect())
     }
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// A sampled span with the given name and resource, shared by the tests
+    /// of the crate.
+    pub(crate) fn span_data(name: &str, resource: Arc<sdk::Resource>) -> trace::SpanData {
+        trace::SpanData {
+            span_context: api::SpanContext::new(
+                api::TraceId::from_u128(1),
+                api::SpanId::from_u64(1),
+                api::TRACE_FLAG_SAMPLED,
+                false,
+                api::TraceState::default(),
+            ),
+            parent_span_id: api::SpanId::invalid(),
+            span_kind: api::SpanKind::Internal,
+            name: name.to_string(),
+            start_time: SystemTime::now(),
+            end_time: SystemTime::now(),
+            attributes: sdk::EvictedHashMap::new(32),
+            message_events: sdk::EvictedQueue::new(32),
+            links: sdk::EvictedQueue::new(32),
+            status_code: api::StatusCode::OK,
+            status_message: String::new(),
+            resource,
+        }
+    }
+
+    #[test]
+    fn groups_spans_by_resource() {
+        let first = Arc::new(sdk::Resource::new(vec![
+            semcov::resource::SERVICE_NAME.string("first")
+        ]));
+        let second = Arc::new(sdk::Resource::new(vec![
+            semcov::resource::SERVICE_NAME.string("second")
+        ]));
+        let equal = Arc::new(sdk::Resource::new(vec![
+            semcov::resource::SERVICE_NAME.string("first")
+        ]));
+
+        let groups = group_by_resource(vec![
+            Arc::new(span_data("a", first.clone())),
+            Arc::new(span_data("b", second)),
+            Arc::new(span_data("c", first)),
+            Arc::new(span_data("d", equal)),
+        ]);
+        let names = groups
+            .iter()
+            .map(|(_, spans)| spans.iter().map(|span| span.name.as_str()).collect())
+            .collect::<Vec<Vec<_>>>();
+        assert_eq!(names, vec![vec!["a", "c", "d"], vec!["b"]]);
+    }
+
+    #[test]
+    fn merges_resource_batch_results() {
+        use trace::ExportResult::*;
+
+        assert_eq!(merge_results(&[]), Success);
+        assert_eq!(merge_results(&[Success, Success]), Success);
+        assert_eq!(
+            merge_results(&[FailedRetryable, FailedRetryable]),
+            FailedRetryable
+        );
+        assert_eq!(
+            merge_results(&[Success, FailedRetryable]),
+            FailedNotRetryable
+        );
+        assert_eq!(
+            merge_results(&[FailedNotRetryable, FailedRetryable]),
+            FailedNotRetryable
+        );
+    }
+
+    #[test]
+    fn builds_process_from_resource() {
+        let configured: jaeger::Process = Process {
+            service_name: "configured".to_string(),
+            tags: vec![api::Key::new("exporter").string("jaeger")],
+        }
+        .into();
+        let resource = sdk::Resource::new(vec![
+            semcov::resource::SERVICE_NAME.string("from-resource"),
+            semcov::resource::HOST_NAME.string("host-a"),
+        ]);
+
+        let process = build_process(&configured, &resource);
+        assert_eq!(process.service_name, "from-resource");
+        let tags = process
+            .tags
+            .unwrap()
+            .into_iter()
+            .map(|tag| (tag.key, tag.v_str.unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tags,
+            vec![
+                ("exporter".to_string(), "jaeger".to_string()),
+                ("host.name".to_string(), "host-a".to_string()),
+            ]
+        );
+
+        let process = build_process(&configured, &sdk::Resource::default());
+        assert_eq!(process.service_name, "configured");
+    }
+
+    #[test]
+    fn resource_is_not_added_to_span_tags() {
+        let resource = Arc::new(sdk::Resource::new(vec![
+            semcov::resource::HOST_NAME.string("host-a")
+        ]));
+        let span: jaeger::Span = Arc::new(span_data("span", resource)).into();
+        assert!(span.tags.unwrap().iter().all(|tag| tag.key != "host.name"));
+    }
+}
@@ -0,0 +1,10 @@
+//! Generated Jaeger `api_v2` protobuf types.
+//!
+//! Compiled from the vendored [jaeger-idl] definitions by `build.rs`.
+//!
+//! [jaeger-idl]: https://github.com/jaegertracing/jaeger-idl
+
+/// Version 2 of the Jaeger model and collector service.
+pub(crate) mod api_v2 {
+    tonic::include_proto!("jaeger.api_v2");
+}
@@ -0,0 +1,541 @@
+//! # Jaeger Remote Sampler
+//!
+//! Polls the sampling strategy of a service from a Jaeger agent or collector
+//! and samples root spans according to it.
+use opentelemetry::api::{self, Sampler as _};
+use opentelemetry::sdk;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+use std::sync::{mpsc, Arc, Mutex, RwLock};
+use std::thread;
+use std::time::Duration;
+
+/// Default sampling endpoint of the Jaeger agent.
+static DEFAULT_SAMPLING_ENDPOINT: &str = "http://localhost:5778/sampling";
+/// Default interval between two strategy requests.
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(60);
+/// Shortest allowed interval between two strategy requests.
+const MIN_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// Timeout of a single strategy request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A sampler applying the sampling strategy configured for a service in
+/// Jaeger.
+///
+/// The strategy is requested from the sampling endpoint when the sampler is
+/// built and then at every poll interval by a background thread, which stops
+/// when the sampler is dropped. The default sampler is used until a valid
+/// strategy was received, afterwards the last valid strategy is kept while
+/// the endpoint is unreachable or returns an invalid strategy.
+///
+/// Like Jaeger clients, the strategy only applies to root spans, child spans
+/// follow the sampling decision of their parent.
+///
+/// ```no_run
+/// use opentelemetry::sdk;
+/// use opentelemetry_jaeger::JaegerRemoteSampler;
+///
+/// let sampler = JaegerRemoteSampler::builder("my-service")
+///     .with_endpoint("http://localhost:5778/sampling")
+///     .with_default_sampler(sdk::Sampler::Probability(0.01))
+///     .build()
+///     .expect("failed to start remote sampler");
+///
+/// let provider = sdk::Provider::builder()
+///     .with_config(sdk::Config {
+///         default_sampler: Box::new(sampler),
+///         ..Default::default()
+///     })
+///     .build();
+/// ```
+pub struct JaegerRemoteSampler {
+    strategy: Arc<RwLock<Strategy>>,
+    // Dropping the sender stops the polling thread.
+    _shutdown: Mutex<mpsc::Sender<()>>,
+}
+
+impl fmt::Debug for JaegerRemoteSampler {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("JaegerRemoteSampler")
+            .field("strategy", &self.strategy)
+            .finish()
+    }
+}
+
+impl JaegerRemoteSampler {
+    /// Create a builder for a remote sampler of the given service.
+    pub fn builder<T: Into<String>>(service_name: T) -> RemoteSamplerBuilder {
+        RemoteSamplerBuilder {
+            service_name: service_name.into(),
+            endpoint: DEFAULT_SAMPLING_ENDPOINT.to_string(),
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            default_sampler: sdk::Sampler::Probability(0.001),
+        }
+    }
+}
+
+impl api::Sampler for JaegerRemoteSampler {
+    fn should_sample(
+        &self,
+        parent_context: Option<&api::SpanContext>,
+        trace_id: api::TraceId,
+        name: &str,
+        span_kind: &api::SpanKind,
+        attributes: &[api::KeyValue],
+        links: &[api::Link],
+    ) -> api::SamplingResult {
+        if let Some(parent_context) = parent_context {
+            let decision = if parent_context.is_sampled() {
+                api::SamplingDecision::RecordAndSampled
+            } else {
+                api::SamplingDecision::NotRecord
+            };
+            return api::SamplingResult {
+                decision,
+                attributes: Vec::new(),
+            };
+        }
+
+        let strategy = self
+            .strategy
+            .read()
+            .expect("JaegerRemoteSampler RwLock poisoned");
+        match &*strategy {
+            Strategy::Default(sampler) | Strategy::Sampler(_, sampler) => {
+                sampler.should_sample(None, trace_id, name, span_kind, attributes, links)
+            }
+            Strategy::PerOperation {
+                operations,
+                default,
+                ..
+            } => operations
+                .get(name)
+                .unwrap_or(default)
+                .should_sample(trace_id, name, span_kind, attributes, links),
+        }
+    }
+}
+
+/// Builder for a `JaegerRemoteSampler`.
+#[derive(Debug)]
+pub struct RemoteSamplerBuilder {
+    service_name: String,
+    endpoint: String,
+    poll_interval: Duration,
+    default_sampler: sdk::Sampler,
+}
+
+impl RemoteSamplerBuilder {
+    /// Assign the sampling endpoint, defaults to the agent's
+    /// `http://localhost:5778/sampling`.
+    pub fn with_endpoint<T: Into<String>>(self, endpoint: T) -> Self {
+        RemoteSamplerBuilder {
+            endpoint: endpoint.into(),
+            ..self
+        }
+    }
+
+    /// Assign the interval between two strategy requests, defaults to one
+    /// minute. Intervals below one second are rounded up to one second.
+    pub fn with_poll_interval(self, poll_interval: Duration) -> Self {
+        RemoteSamplerBuilder {
+            poll_interval: poll_interval.max(MIN_POLL_INTERVAL),
+            ..self
+        }
+    }
+
+    /// Assign the sampler used while no strategy is available, defaults to
+    /// sampling 0.1% of traces.
+    pub fn with_default_sampler(self, default_sampler: sdk::Sampler) -> Self {
+        RemoteSamplerBuilder {
+            default_sampler,
+            ..self
+        }
+    }
+
+    /// Build the sampler and start polling the sampling endpoint.
+    pub fn build(self) -> io::Result<JaegerRemoteSampler> {
+        let strategy = Arc::new(RwLock::new(Strategy::Default(self.default_sampler.clone())));
+        let (shutdown, shutdown_rx) = mpsc::channel();
+
+        let poller_strategy = strategy.clone();
+        thread::Builder::new()
+            .name("opentelemetry-jaeger-remote-sampler".to_string())
+            .spawn(move || loop {
+                // Failed requests keep the current strategy
+                if let Some(response) = fetch_strategy(&self.endpoint, &self.service_name) {
+                    let next = poller_strategy
+                        .read()
+                        .expect("JaegerRemoteSampler RwLock poisoned")
+                        .updated(response);
+                    if let Some(next) = next {
+                        *poller_strategy
+                            .write()
+                            .expect("JaegerRemoteSampler RwLock poisoned") = next;
+                    }
+                }
+
+                match shutdown_rx.recv_timeout(self.poll_interval) {
+                    Err(mpsc::RecvTimeoutError::Timeout) => continue,
+                    _ => break,
+                }
+            })?;
+
+        Ok(JaegerRemoteSampler {
+            strategy,
+            _shutdown: Mutex::new(shutdown),
+        })
+    }
+}
+
+/// Requests the sampling strategy of the service, returning `None` if the
+/// endpoint is unreachable or the response is invalid.
+fn fetch_strategy(endpoint: &str, service_name: &str) -> Option<StrategyResponse> {
+    let response = ureq::get(endpoint)
+        .query("service", service_name)
+        .timeout(REQUEST_TIMEOUT)
+        .call();
+    if !response.ok() {
+        return None;
+    }
+
+    let body = response.into_string().ok()?;
+    serde_json::from_str(&body).ok()
+}
+
+/// The samplers of the current strategy.
+#[derive(Debug)]
+enum Strategy {
+    /// No strategy available, the default sampler is used.
+    Default(sdk::Sampler),
+    /// A single sampler for all operations.
+    Sampler(StrategyResponse, sdk::Sampler),
+    /// A sampler per operation.
+    PerOperation {
+        response: StrategyResponse,
+        operations: HashMap<String, OperationSampler>,
+        default: OperationSampler,
+    },
+}
+
+impl Strategy {
+    /// Creates the samplers of a strategy, `None` if it contains none.
+    fn from_response(response: StrategyResponse) -> Option<Self> {
+        if let Some(operation_sampling) = &response.operation_sampling {
+            let lower_bound = operation_sampling.default_lower_bound_traces_per_second;
+            let operations = operation_sampling
+                .per_operation_strategies
+                .iter()
+                .map(|strategy| {
+                    (
+                        strategy.operation.clone(),
+                        OperationSampler::new(
+                            strategy.probabilistic_sampling.sampling_rate,
+                            lower_bound,
+                        ),
+                    )
+                })
+                .collect();
+            let default =
+                OperationSampler::new(operation_sampling.default_sampling_probability, lower_bound);
+
+            return Some(Strategy::PerOperation {
+                response,
+                operations,
+                default,
+            });
+        }
+
+        let sampler = match (
+            &response.probabilistic_sampling,
+            &response.rate_limiting_sampling,
+        ) {
+            (probabilistic, Some(rate_limiting))
+                if probabilistic.is_none() || response.is_rate_limiting() =>
+            {
+                rate_limiting.sampler()
+            }
+            (Some(probabilistic), _) => sdk::Sampler::Probability(probabilistic.sampling_rate),
+            (None, _) => return None,
+        };
+
+        Some(Strategy::Sampler(response, sampler))
+    }
+
+    /// The strategy replacing this one after receiving the given response,
+    /// `None` if the current one should be kept because the response is
+    /// unchanged or contains no sampler.
+    fn updated(&self, response: StrategyResponse) -> Option<Self> {
+        // Keep the current samplers, and their rate limits, if the strategy
+        // didn't change.
+        if self.response() == Some(&response) {
+            None
+        } else {
+            Strategy::from_response(response)
+        }
+    }
+
+    fn response(&self) -> Option<&StrategyResponse> {
+        match self {
+            Strategy::Default(_) => None,
+            Strategy::Sampler(response, _) | Strategy::PerOperation { response, .. } => {
+                Some(response)
+            }
+        }
+    }
+}
+
+/// Samples an operation probabilistically, but at least at a lower bound
+/// rate.
+#[derive(Debug)]
+struct OperationSampler {
+    probabilistic: sdk::Sampler,
+    lower_bound: sdk::Sampler,
+}
+
+impl OperationSampler {
+    fn new(sampling_rate: f64, lower_bound: f64) -> Self {
+        OperationSampler {
+            probabilistic: sdk::Sampler::Probability(sampling_rate),
+            lower_bound: sdk::Sampler::RateLimiting(sdk::RateLimiter::new(lower_bound, 1)),
+        }
+    }
+
+    fn should_sample(
+        &self,
+        trace_id: api::TraceId,
+        name: &str,
+        span_kind: &api::SpanKind,
+        attributes: &[api::KeyValue],
+        links: &[api::Link],
+    ) -> api::SamplingResult {
+        let result = self
+            .probabilistic
+            .should_sample(None, trace_id, name, span_kind, attributes, links);
+        if result.decision == api::SamplingDecision::RecordAndSampled {
+            return result;
+        }
+
+        self.lower_bound
+            .should_sample(None, trace_id, name, span_kind, attributes, links)
+    }
+}
+
+/// Sampling strategy as returned by the Jaeger sampling endpoint.
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct StrategyResponse {
+    /// Either the strategy name or its numeric value in the Thrift IDL.
+    #[serde(default)]
+    strategy_type: Option<serde_json::Value>,
+    #[serde(default)]
+    probabilistic_sampling: Option<ProbabilisticStrategy>,
+    #[serde(default)]
+    rate_limiting_sampling: Option<RateLimitingStrategy>,
+    #[serde(default)]
+    operation_sampling: Option<PerOperationStrategy>,
+}
+
+impl StrategyResponse {
+    fn is_rate_limiting(&self) -> bool {
+        match &self.strategy_type {
+            Some(serde_json::Value::String(name)) => name == "RATE_LIMITING",
+            Some(serde_json::Value::Number(value)) => value.as_u64() == Some(1),
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct ProbabilisticStrategy {
+    sampling_rate: f64,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct RateLimitingStrategy {
+    max_traces_per_second: f64,
+}
+
+impl RateLimitingStrategy {
+    fn sampler(&self) -> sdk::Sampler {
+        // Like Jaeger clients, allow up to a second worth of traces at once.
+        let burst = self.max_traces_per_second.ceil().max(1.0) as u32;
+        sdk::Sampler::RateLimiting(sdk::RateLimiter::new(self.max_traces_per_second, burst))
+    }
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct PerOperationStrategy {
+    default_sampling_probability: f64,
+    #[serde(default)]
+    default_lower_bound_traces_per_second: f64,
+    #[serde(default)]
+    per_operation_strategies: Vec<OperationStrategy>,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct OperationStrategy {
+    operation: String,
+    probabilistic_sampling: ProbabilisticStrategy,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::time::Instant;
+
+    fn sampled(sampler: &JaegerRemoteSampler, name: &str) -> bool {
+        sampler
+            .should_sample(
+                None,
+                api::TraceId::from_u128(u128::from(u64::MAX)),
+                name,
+                &api::SpanKind::Server,
+                &[],
+                &[],
+            )
+            .decision
+            == api::SamplingDecision::RecordAndSampled
+    }
+
+    /// Serves the given body to every request, returning the endpoint.
+    fn serve(body: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}/sampling", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    let read = stream.read(&mut buf).unwrap();
+                    if read == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buf[..read]);
+                }
+                assert!(String::from_utf8_lossy(&request).contains("service=test-service"));
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+        });
+
+        endpoint
+    }
+
+    fn wait_for<F: Fn() -> bool>(condition: F) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !condition() {
+            assert!(Instant::now() < deadline, "timed out waiting for strategy");
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn applies_remote_strategy() {
+        let endpoint = serve(
+            r#"{"strategyType":"PROBABILISTIC","probabilisticSampling":{"samplingRate":1.0}}"#,
+        );
+        let sampler = JaegerRemoteSampler::builder("test-service")
+            .with_endpoint(endpoint)
+            .with_default_sampler(sdk::Sampler::AlwaysOff)
+            .build()
+            .unwrap();
+
+        wait_for(|| sampled(&sampler, "op"));
+    }
+
+    #[test]
+    fn applies_per_operation_strategies() {
+        let endpoint = serve(
+            r#"{
+                "strategyType": 0,
+                "probabilisticSampling": {"samplingRate": 0.0},
+                "operationSampling": {
+                    "defaultSamplingProbability": 0.0,
+                    "defaultLowerBoundTracesPerSecond": 0.0,
+                    "perOperationStrategies": [
+                        {"operation": "checkout", "probabilisticSampling": {"samplingRate": 1.0}}
+                    ]
+                }
+            }"#,
+        );
+        let sampler = JaegerRemoteSampler::builder("test-service")
+            .with_endpoint(endpoint)
+            .with_default_sampler(sdk::Sampler::AlwaysOff)
+            .build()
+            .unwrap();
+
+        wait_for(|| sampled(&sampler, "checkout"));
+        assert!(!sampled(&sampler, "health"));
+    }
+
+    #[test]
+    fn falls_back_to_default_sampler() {
+        // Bind and drop a listener to get a port nothing listens on.
+        let addr = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let sampler = JaegerRemoteSampler::builder("test-service")
+            .with_endpoint(format!("http://{}/sampling", addr))
+            .with_default_sampler(sdk::Sampler::AlwaysOn)
+            .build()
+            .unwrap();
+
+        assert!(sampled(&sampler, "op"));
+    }
+
+    #[test]
+    fn keeps_last_strategy_on_invalid_response() {
+        let parse = |body| serde_json::from_str::<StrategyResponse>(body).unwrap();
+        let valid = r#"{"probabilisticSampling":{"samplingRate":0.5}}"#;
+        let strategy = Strategy::Default(sdk::Sampler::AlwaysOff)
+            .updated(parse(valid))
+            .unwrap();
+
+        assert!(strategy.updated(parse(valid)).is_none());
+        assert!(strategy
+            .updated(parse(r#"{"strategyType":"PROBABILISTIC"}"#))
+            .is_none());
+        assert!(strategy
+            .updated(parse(r#"{"probabilisticSampling":{"samplingRate":1.0}}"#))
+            .is_some());
+    }
+
+    #[test]
+    fn clamps_poll_interval() {
+        let builder =
+            JaegerRemoteSampler::builder("test-service").with_poll_interval(Duration::from_secs(0));
+
+        assert_eq!(builder.poll_interval, MIN_POLL_INTERVAL);
+    }
+
+    #[test]
+    fn parses_rate_limiting_strategy() {
+        let response: StrategyResponse = serde_json::from_str(
+            r#"{"strategyType":"RATE_LIMITING","rateLimitingSampling":{"maxTracesPerSecond":2}}"#,
+        )
+        .unwrap();
+
+        match Strategy::from_response(response).unwrap() {
+            Strategy::Sampler(_, sdk::Sampler::RateLimiting(limiter)) => {
+                assert_eq!(limiter.traces_per_second(), 2.0)
+            }
+            strategy => panic!("unexpected strategy {:?}", strategy),
+        }
+    }
+}
@@ -38,6 +38,11 @@ impl TUdpChannel {
             write_buffer: Default::default(),
         })
     }
+
+    /// The max size of a packet in bytes.
+    pub(crate) fn max_packet_size(&self) -> usize {
+        self.max_packet_size
+    }
 }
 
 impl std::io::Write for TUdpChannel {
@@ -47,12 +52,14 @@ impl std::io::Write for TUdpChannel {
             .lock()
             .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err.to_string()))?;
         if write_buffer.len() + buf.len() > self.max_packet_size {
+            let len = write_buffer.len() + buf.len();
+            // Discard the partial message so the next one starts clean
+            write_buffer.clear();
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidInput,
                 format!(
                     "input ({} bytes) is larger than max packet size ({} bytes)",
-                    write_buffer.len() + buf.len(),
-                    self.max_packet_size
+                    len, self.max_packet_size
                 ),
             ));
         }
@@ -1,8 +1,11 @@
 //! # Jaeger Span Uploader
-use crate::{agent, jaeger, thrift::agent::TAgentSyncClient};
+#[cfg(feature = "collector_grpc")]
+use crate::collector_grpc;
+use crate::{agent, jaeger};
 #[cfg(feature = "collector_client")]
 use crate::{collector, thrift::jaeger::TCollectorSyncClient};
 use opentelemetry::exporter::trace;
+use std::sync::Arc;
 
 /// Uploads a batch of spans to Jaeger
 #[derive(Debug)]
@@ -12,23 +15,54 @@ pub(crate) enum BatchUploader {
     /// Collector sync client
     #[cfg(feature = "collector_client")]
     Collector(Box<collector::CollectorSyncClientHttp>),
+    /// Collector gRPC client
+    #[cfg(feature = "collector_grpc")]
+    CollectorGrpc(Box<collector_grpc::CollectorSyncClientGrpc>),
 }
 
 impl BatchUploader {
     /// Emit a jaeger batch for the given uploader
-    pub(crate) fn upload(&mut self, batch: jaeger::Batch) -> trace::ExportResult {
+    pub(crate) fn upload(
+        &mut self,
+        process: &jaeger::Process,
+        spans: Vec<Arc<trace::SpanData>>,
+    ) -> trace::ExportResult {
         match self {
-            BatchUploader::Agent(client) => match client.emit_batch(batch) {
-                Ok(_) => trace::ExportResult::Success,
-                // TODO determine if the error is retryable
-                Err(_) => trace::ExportResult::FailedNotRetryable,
-            },
+            BatchUploader::Agent(client) => {
+                match client.emit_batch_split(thrift_batch(process, spans)) {
+                    Ok(_) => trace::ExportResult::Success,
+                    // TODO determine if the error is retryable
+                    Err(_) => trace::ExportResult::FailedNotRetryable,
+                }
+            }
             #[cfg(feature = "collector_client")]
-            BatchUploader::Collector(collector) => match collector.submit_batches(vec![batch]) {
-                Ok(_) => trace::ExportResult::Success,
-                // TODO determine if the error is retryable
-                Err(_) => trace::ExportResult::FailedNotRetryable,
-            },
+            BatchUploader::Collector(collector) => {
+                match collector.submit_batches(vec![thrift_batch(process, spans)]) {
+                    Ok(_) => trace::ExportResult::Success,
+                    // TODO determine if the error is retryable
+                    Err(_) => trace::ExportResult::FailedNotRetryable,
+                }
+            }
+            #[cfg(feature = "collector_grpc")]
+            BatchUploader::CollectorGrpc(collector) => {
+                collector.post_spans(collector_grpc::build_batch(process, spans))
+            }
         }
     }
+
+    /// Number of spans dropped for being too large to upload.
+    pub(crate) fn dropped_spans(&self) -> u64 {
+        match self {
+            BatchUploader::Agent(client) => client.dropped_spans(),
+            #[cfg(feature = "collector_client")]
+            BatchUploader::Collector(_) => 0,
+            #[cfg(feature = "collector_grpc")]
+            BatchUploader::CollectorGrpc(_) => 0,
+        }
+    }
+}
+
+/// Convert spans to a thrift batch for exporting.
+fn thrift_batch(process: &jaeger::Process, spans: Vec<Arc<trace::SpanData>>) -> jaeger::Batch {
+    jaeger::Batch::new(process.clone(), spans.into_iter().map(Into::into).collect())
 }
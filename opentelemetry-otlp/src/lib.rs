@@ -0,0 +1,429 @@
+//! # OpenTelemetry OTLP Exporter
+//!
+//! Collects OpenTelemetry spans and reports them to an OpenTelemetry
+//! collector using the [OpenTelemetry Protocol] (OTLP). Spans can be
+//! sent either over gRPC or as binary protobuf over HTTP.
+//!
+//! ### OTLP Exporter Example
+//!
+//! This example expects an OpenTelemetry collector listening for OTLP gRPC
+//! requests on `localhost:55680`.
+//!
+//! ```rust,no_run
+//! use opentelemetry::{global, sdk};
+//!
+//! fn init_tracer() -> Result<(), opentelemetry_otlp::Error> {
+//!     let exporter = opentelemetry_otlp::Exporter::builder()
+//!         .with_endpoint("http://localhost:55680")
+//!         .init()?;
+//!     let provider = sdk::Provider::builder()
+//!         .with_simple_exporter(exporter)
+//!         .build();
+//!     global::set_provider(provider);
+//!
+//!     Ok(())
+//! }
+//!
+//! fn main() -> Result<(), opentelemetry_otlp::Error> {
+//!     init_tracer()?;
+//!     // Use configured tracer
+//!     Ok(())
+//! }
+//! ```
+//!
+//! ### HTTP Example
+//!
+//! Collectors that accept OTLP over HTTP can be targeted by selecting the
+//! [`Protocol::HttpBinary`] protocol. The endpoint is the full URL spans are
+//! posted to.
+//!
+//! ```rust,no_run
+//! use opentelemetry_otlp::Protocol;
+//!
+//! let exporter = opentelemetry_otlp::Exporter::builder()
+//!     .with_endpoint("http://localhost:55681/v1/trace")
+//!     .with_protocol(Protocol::HttpBinary)
+//!     .init()
+//!     .expect("valid exporter configuration");
+//! ```
+//!
+//! ### Blocking Exports
+//!
+//! `SpanExporter::export` is synchronous, so each export blocks the calling
+//! thread until the collector responds or the export timeout elapses. When
+//! batching on a tokio runtime, run the batch worker on the blocking thread
+//! pool as shown in [batching with blocking exporters].
+//!
+//! [OpenTelemetry Protocol]: https://github.com/open-telemetry/opentelemetry-specification/blob/master/specification/protocol/otlp.md
+//! [`Protocol::HttpBinary`]: enum.Protocol.html#variant.HttpBinary
+//! [batching with blocking exporters]: https://docs.rs/opentelemetry/0.6.0/opentelemetry/sdk/trace/span_processor/index.html#batching-with-blocking-exporters
+#![deny(missing_docs, unreachable_pub, missing_debug_implementations)]
+#![cfg_attr(test, deny(warnings))]
+#[allow(clippy::all, missing_docs, unreachable_pub)]
+pub mod proto;
+mod transform;
+mod uploader;
+
+use futures::channel::oneshot;
+use opentelemetry::exporter::trace;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// Default collector endpoint for the gRPC protocol.
+static DEFAULT_GRPC_ENDPOINT: &str = "http://localhost:55680";
+/// Default collector endpoint for the HTTP protocol.
+static DEFAULT_HTTP_ENDPOINT: &str = "http://localhost:55681/v1/trace";
+/// Default timeout for a single export.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Transport protocol used to send spans to the collector.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Protocol {
+    /// Protobuf messages over gRPC.
+    Grpc,
+    /// Binary protobuf messages posted over HTTP.
+    HttpBinary,
+}
+
+impl Default for Protocol {
+    fn default() -> Self {
+        Protocol::Grpc
+    }
+}
+
+/// Errors that can occur while building an OTLP `Exporter`.
+#[derive(Debug)]
+pub enum Error {
+    /// The configured endpoint is not a valid URI.
+    InvalidEndpoint(http::uri::InvalidUri),
+    /// The background runtime used to drive requests could not be started.
+    Runtime(std::io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidEndpoint(err) => write!(f, "invalid OTLP endpoint: {}", err),
+            Error::Runtime(err) => write!(f, "could not start OTLP exporter runtime: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// OTLP span exporter
+pub struct Exporter {
+    uploader: uploader::Uploader,
+    runtime: tokio::runtime::Handle,
+    // Dropping the sender stops the runtime thread.
+    _shutdown: oneshot::Sender<()>,
+    timeout: Duration,
+    is_shutdown: AtomicBool,
+}
+
+impl fmt::Debug for Exporter {
+    /// Debug info
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Exporter")
+            .field("uploader", &self.uploader)
+            .field("timeout", &self.timeout)
+            .field("is_shutdown", &self.is_shutdown)
+            .finish()
+    }
+}
+
+impl Exporter {
+    /// Create a new exporter builder.
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+}
+
+impl trace::SpanExporter for Exporter {
+    /// Export spans to an OTLP collector.
+    ///
+    /// Requests are driven on the exporter's own runtime so this can be
+    /// called from both synchronous and asynchronous span processors. The
+    /// calling thread is blocked until the request completes or times out.
+    fn export(&self, batch: Vec<Arc<trace::SpanData>>) -> trace::ExportResult {
+        if self.is_shutdown.load(Ordering::SeqCst) {
+            return trace::ExportResult::FailedNotRetryable;
+        }
+
+        let request = transform::build_request(batch);
+        let upload = self.uploader.clone().upload(request);
+        let timeout = self.timeout;
+        let handle = self.runtime.spawn(async move {
+            tokio::time::timeout(timeout, upload)
+                .await
+                .unwrap_or(trace::ExportResult::FailedRetryable)
+        });
+
+        futures::executor::block_on(handle).unwrap_or(trace::ExportResult::FailedNotRetryable)
+    }
+
+    /// Marks the exporter as shut down, subsequent exports will fail.
+    fn shutdown(&self) {
+        self.is_shutdown.store(true, Ordering::SeqCst);
+    }
+}
+
+/// OTLP exporter builder
+///
+/// Exporters built here block the thread calling `export` for up to the
+/// configured timeout, see the [crate documentation](index.html#blocking-exports).
+#[derive(Debug, Default)]
+pub struct Builder {
+    endpoint: Option<String>,
+    protocol: Protocol,
+    timeout: Option<Duration>,
+}
+
+impl Builder {
+    /// Assign the collector endpoint.
+    ///
+    /// For `Protocol::Grpc` this is the address of the collector, for
+    /// `Protocol::HttpBinary` it is the full URL spans are posted to.
+    pub fn with_endpoint<S: Into<String>>(self, endpoint: S) -> Self {
+        Builder {
+            endpoint: Some(endpoint.into()),
+            ..self
+        }
+    }
+
+    /// Assign the transport protocol, defaults to `Protocol::Grpc`.
+    pub fn with_protocol(self, protocol: Protocol) -> Self {
+        Builder { protocol, ..self }
+    }
+
+    /// Assign the maximum time a single export may take, defaults to 10 seconds.
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        Builder {
+            timeout: Some(timeout),
+            ..self
+        }
+    }
+
+    /// Create a new exporter from the builder
+    pub fn init(self) -> Result<Exporter, Error> {
+        let uploader = match self.protocol {
+            Protocol::Grpc => {
+                let endpoint = self
+                    .endpoint
+                    .unwrap_or_else(|| DEFAULT_GRPC_ENDPOINT.to_string());
+                // Validate eagerly, the connection itself is established on first export.
+                endpoint
+                    .parse::<http::Uri>()
+                    .map_err(Error::InvalidEndpoint)?;
+                uploader::Uploader::grpc(endpoint)
+            }
+            Protocol::HttpBinary => {
+                let endpoint = self
+                    .endpoint
+                    .as_deref()
+                    .unwrap_or(DEFAULT_HTTP_ENDPOINT)
+                    .parse::<http::Uri>()
+                    .map_err(Error::InvalidEndpoint)?;
+                uploader::Uploader::http(endpoint)
+            }
+        };
+
+        let mut runtime = tokio::runtime::Builder::new()
+            .threaded_scheduler()
+            .core_threads(1)
+            .thread_name("opentelemetry-otlp")
+            .enable_all()
+            .build()
+            .map_err(Error::Runtime)?;
+        let handle = runtime.handle().clone();
+        // The runtime is owned by its own thread, so the exporter can be
+        // dropped from within an asynchronous context.
+        let (shutdown, shutdown_rx) = oneshot::channel();
+        thread::Builder::new()
+            .name("opentelemetry-otlp".to_string())
+            .spawn(move || {
+                let _ = runtime.block_on(shutdown_rx);
+            })
+            .map_err(Error::Runtime)?;
+
+        Ok(Exporter {
+            uploader,
+            runtime: handle,
+            _shutdown: shutdown,
+            timeout: self.timeout.unwrap_or(DEFAULT_TIMEOUT),
+            is_shutdown: AtomicBool::new(false),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::collector::trace::v1::{
+        trace_service_server::{TraceService, TraceServiceServer},
+        ExportTraceServiceRequest, ExportTraceServiceResponse,
+    };
+    use opentelemetry::exporter::trace::SpanExporter;
+    use opentelemetry::sdk;
+    use prost::Message;
+    use std::sync::Mutex;
+
+    /// Stand-in collector recording every request it receives.
+    #[derive(Debug, Default, Clone)]
+    struct Collector {
+        requests: Arc<Mutex<Vec<ExportTraceServiceRequest>>>,
+    }
+
+    #[tonic::async_trait]
+    impl TraceService for Collector {
+        async fn export(
+            &self,
+            request: tonic::Request<ExportTraceServiceRequest>,
+        ) -> Result<tonic::Response<ExportTraceServiceResponse>, tonic::Status> {
+            self.requests.lock().unwrap().push(request.into_inner());
+            Ok(tonic::Response::new(ExportTraceServiceResponse {}))
+        }
+    }
+
+    fn span_data(name: &str) -> Arc<trace::SpanData> {
+        Arc::new(trace::SpanData {
+            name: name.to_string(),
+            ..transform::tests::span_data(Arc::new(sdk::Resource::default()), 0)
+        })
+    }
+
+    fn local_listener() -> (std::net::TcpListener, std::net::SocketAddr) {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        (listener, addr)
+    }
+
+    #[test]
+    fn exports_over_grpc() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let collector = Collector::default();
+        let (listener, addr) = local_listener();
+        let service = TraceServiceServer::new(collector.clone());
+        runtime.spawn(async move {
+            let mut listener = tokio::net::TcpListener::from_std(listener).unwrap();
+            tonic::transport::Server::builder()
+                .add_service(service)
+                .serve_with_incoming(listener.incoming())
+                .await
+                .unwrap();
+        });
+
+        let exporter = Exporter::builder()
+            .with_endpoint(format!("http://{}", addr))
+            .init()
+            .unwrap();
+
+        assert_eq!(
+            exporter.export(vec![span_data("first"), span_data("second")]),
+            trace::ExportResult::Success
+        );
+        let requests = collector.requests.lock().unwrap().clone();
+        assert_eq!(requests.len(), 1);
+        let spans = &requests[0].resource_spans[0].instrumentation_library_spans[0].spans;
+        assert_eq!(
+            spans
+                .iter()
+                .map(|span| span.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["first", "second"]
+        );
+
+        exporter.shutdown();
+        assert_eq!(
+            exporter.export(vec![span_data("after shutdown")]),
+            trace::ExportResult::FailedNotRetryable
+        );
+    }
+
+    #[test]
+    fn can_be_dropped_inside_a_runtime() {
+        let mut runtime = tokio::runtime::Runtime::new().unwrap();
+        let exporter = Exporter::builder().init().unwrap();
+
+        let task = runtime.spawn(async move { drop(exporter) });
+
+        runtime.block_on(task).unwrap();
+    }
+
+    #[test]
+    fn exports_over_http() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let requests: Arc<Mutex<Vec<ExportTraceServiceRequest>>> = Default::default();
+        let (listener, addr) = local_listener();
+        let server_requests = requests.clone();
+        runtime.spawn(async move {
+            let make_service = hyper::service::make_service_fn(move |_| {
+                let requests = server_requests.clone();
+                async move {
+                    Ok::<_, hyper::Error>(hyper::service::service_fn(
+                        move |request: hyper::Request<hyper::Body>| {
+                            let requests = requests.clone();
+                            async move {
+                                assert_eq!(request.uri().path(), "/v1/trace");
+                                let body = hyper::body::to_bytes(request.into_body()).await?;
+                                let export = ExportTraceServiceRequest::decode(body).unwrap();
+                                requests.lock().unwrap().push(export);
+                                Ok::<_, hyper::Error>(hyper::Response::new(hyper::Body::empty()))
+                            }
+                        },
+                    ))
+                }
+            });
+            hyper::Server::from_tcp(listener)
+                .unwrap()
+                .serve(make_service)
+                .await
+                .unwrap();
+        });
+
+        let exporter = Exporter::builder()
+            .with_endpoint(format!("http://{}/v1/trace", addr))
+            .with_protocol(Protocol::HttpBinary)
+            .init()
+            .unwrap();
+
+        assert_eq!(
+            exporter.export(vec![span_data("http span")]),
+            trace::ExportResult::Success
+        );
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(
+            requests[0].resource_spans[0].instrumentation_library_spans[0].spans[0].name,
+            "http span"
+        );
+    }
+
+    #[test]
+    fn unreachable_collector_is_retryable() {
+        let (listener, addr) = local_listener();
+        drop(listener);
+        let exporter = Exporter::builder()
+            .with_endpoint(format!("http://{}/v1/trace", addr))
+            .with_protocol(Protocol::HttpBinary)
+            .with_timeout(Duration::from_secs(1))
+            .init()
+            .unwrap();
+
+        assert_eq!(
+            exporter.export(vec![span_data("lost")]),
+            trace::ExportResult::FailedRetryable
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_endpoint() {
+        assert!(Exporter::builder()
+            .with_endpoint("not a uri")
+            .init()
+            .is_err());
+    }
+}
@@ -0,0 +1,40 @@
+//! Generated OTLP protobuf types.
+//!
+//! Compiled from the vendored [opentelemetry-proto] definitions by `build.rs`.
+//!
+//! [opentelemetry-proto]: https://github.com/open-telemetry/opentelemetry-proto
+
+/// Types shared by all OTLP signals.
+pub mod common {
+    /// Version 1 of the common types.
+    pub mod v1 {
+        tonic::include_proto!("opentelemetry.proto.common.v1");
+    }
+}
+
+/// Resource definitions.
+pub mod resource {
+    /// Version 1 of the resource types.
+    pub mod v1 {
+        tonic::include_proto!("opentelemetry.proto.resource.v1");
+    }
+}
+
+/// Trace data definitions.
+pub mod trace {
+    /// Version 1 of the trace types.
+    pub mod v1 {
+        tonic::include_proto!("opentelemetry.proto.trace.v1");
+    }
+}
+
+/// Collector service definitions.
+pub mod collector {
+    /// Trace collector service.
+    pub mod trace {
+        /// Version 1 of the trace collector service.
+        pub mod v1 {
+            tonic::include_proto!("opentelemetry.proto.collector.trace.v1");
+        }
+    }
+}
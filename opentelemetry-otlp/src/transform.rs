@@ -0,0 +1,286 @@
+//! # OTLP Span Transformation
+//!
+//! Converts `SpanData` produced by the SDK into the OTLP protobuf model.
+use crate::proto::collector::trace::v1::ExportTraceServiceRequest;
+use crate::proto::common::v1::{any_value, AnyValue, ArrayValue, KeyValue};
+use crate::proto::resource::v1::Resource;
+use crate::proto::trace::v1::{span, InstrumentationLibrarySpans, ResourceSpans, Span, Status};
+use opentelemetry::exporter::trace;
+use opentelemetry::{api, sdk};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Build an export request from a batch of spans, grouping spans that share
+/// the same `Resource` into a single `ResourceSpans` entry.
+pub(crate) fn build_request(batch: Vec<Arc<trace::SpanData>>) -> ExportTraceServiceRequest {
+    let mut groups: Vec<(Arc<sdk::Resource>, Vec<Span>)> = Vec::new();
+
+    for span_data in batch {
+        let span = to_span(&span_data);
+        // Spans of a provider share the same resource
+        match groups.iter_mut().find(|(resource, _)| {
+            Arc::ptr_eq(resource, &span_data.resource) || **resource == *span_data.resource
+        }) {
+            Some((_, spans)) => spans.push(span),
+            None => groups.push((span_data.resource.clone(), vec![span])),
+        }
+    }
+
+    ExportTraceServiceRequest {
+        resource_spans: groups
+            .into_iter()
+            .map(|(resource, spans)| ResourceSpans {
+                resource: Some(to_resource(&resource)),
+                instrumentation_library_spans: vec![InstrumentationLibrarySpans {
+                    instrumentation_library: None,
+                    spans,
+                }],
+            })
+            .collect(),
+    }
+}
+
+fn to_resource(resource: &sdk::Resource) -> Resource {
+    Resource {
+        attributes: resource
+            .iter()
+            .map(|(key, value)| to_key_value(key, value))
+            .collect(),
+        dropped_attributes_count: 0,
+    }
+}
+
+fn to_span(span_data: &trace::SpanData) -> Span {
+    let parent_span_id = if span_data.parent_span_id == api::SpanId::invalid() {
+        Vec::new()
+    } else {
+        span_id_bytes(span_data.parent_span_id)
+    };
+
+    Span {
+        trace_id: trace_id_bytes(span_data.span_context.trace_id()),
+        span_id: span_id_bytes(span_data.span_context.span_id()),
+        trace_state: span_data.span_context.trace_state().header(),
+        parent_span_id,
+        name: span_data.name.clone(),
+        kind: to_span_kind(&span_data.span_kind) as i32,
+        start_time_unix_nano: to_nanos(span_data.start_time),
+        end_time_unix_nano: to_nanos(span_data.end_time),
+        attributes: span_data
+            .attributes
+            .iter()
+            .map(|(key, value)| to_key_value(key, value))
+            .collect(),
+        dropped_attributes_count: span_data.attributes.dropped_count(),
+        events: span_data
+            .message_events
+            .iter()
+            .map(|event| span::Event {
+                time_unix_nano: to_nanos(event.timestamp),
+                name: event.name.clone(),
+                attributes: event.attributes.iter().map(from_key_value).collect(),
+                dropped_attributes_count: 0,
+            })
+            .collect(),
+        dropped_events_count: span_data.message_events.dropped_count(),
+        links: span_data
+            .links
+            .iter()
+            .map(|link| span::Link {
+                trace_id: trace_id_bytes(link.span_context().trace_id()),
+                span_id: span_id_bytes(link.span_context().span_id()),
+                trace_state: link.span_context().trace_state().header(),
+                attributes: link.attributes().iter().map(from_key_value).collect(),
+                dropped_attributes_count: 0,
+            })
+            .collect(),
+        dropped_links_count: span_data.links.dropped_count(),
+        status: Some(Status {
+            // `api::StatusCode` shares its numbering with the OTLP status codes.
+            code: span_data.status_code.clone() as i32,
+            message: span_data.status_message.clone(),
+        }),
+    }
+}
+
+fn to_span_kind(span_kind: &api::SpanKind) -> span::SpanKind {
+    match span_kind {
+        api::SpanKind::Client => span::SpanKind::Client,
+        api::SpanKind::Server => span::SpanKind::Server,
+        api::SpanKind::Producer => span::SpanKind::Producer,
+        api::SpanKind::Consumer => span::SpanKind::Consumer,
+        api::SpanKind::Internal => span::SpanKind::Internal,
+    }
+}
+
+fn from_key_value(kv: &api::KeyValue) -> KeyValue {
+    to_key_value(&kv.key, &kv.value)
+}
+
+fn to_key_value(key: &api::Key, value: &api::Value) -> KeyValue {
+    KeyValue {
+        key: key.as_str().to_string(),
+        value: Some(to_any_value(value)),
+    }
+}
+
+fn to_any_value(value: &api::Value) -> AnyValue {
+    let value = match value {
+        api::Value::Bool(b) => any_value::Value::BoolValue(*b),
+        api::Value::I64(i) => any_value::Value::IntValue(*i),
+        // OTLP only has signed integers, fall back to a string if the value does not fit.
+        api::Value::U64(u) if *u <= i64::MAX as u64 => any_value::Value::IntValue(*u as i64),
+        api::Value::U64(u) => any_value::Value::StringValue(u.to_string()),
+        api::Value::F64(f) => any_value::Value::DoubleValue(*f),
+        api::Value::String(s) => any_value::Value::StringValue(s.clone()),
+        api::Value::Bytes(b) => any_value::Value::BytesValue(b.clone()),
+        api::Value::Array(values) => any_value::Value::ArrayValue(ArrayValue {
+            values: values.iter().map(to_any_value).collect(),
+        }),
+    };
+
+    AnyValue { value: Some(value) }
+}
+
+fn trace_id_bytes(trace_id: api::TraceId) -> Vec<u8> {
+    trace_id.to_u128().to_be_bytes().to_vec()
+}
+
+fn span_id_bytes(span_id: api::SpanId) -> Vec<u8> {
+    span_id.to_u64().to_be_bytes().to_vec()
+}
+
+fn to_nanos(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_nanos() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    /// A span with attributes, events and links, shared by the tests of the
+    /// crate.
+    pub(crate) fn span_data(resource: Arc<sdk::Resource>, parent: u64) -> trace::SpanData {
+        let mut attributes = sdk::EvictedHashMap::new(1);
+        attributes.insert(api::KeyValue::new("first", "dropped"));
+        attributes.insert(api::KeyValue::new("http.status_code", 200i64));
+        let mut message_events = sdk::EvictedQueue::new(2);
+        message_events.append_vec(&mut vec![api::Event::new(
+            "exception".to_string(),
+            UNIX_EPOCH + Duration::from_secs(2),
+            vec![api::KeyValue::new("retry", true)],
+        )]);
+        let mut links = sdk::EvictedQueue::new(2);
+        links.append_vec(&mut vec![api::Link::new(
+            api::SpanContext::new(
+                api::TraceId::from_u128(3),
+                api::SpanId::from_u64(4),
+                0,
+                true,
+                api::TraceState::default(),
+            ),
+            Vec::new(),
+        )]);
+
+        trace::SpanData {
+            span_context: api::SpanContext::new(
+                api::TraceId::from_u128(0x0102_0304_0506_0708_090a_0b0c_0d0e_0f10),
+                api::SpanId::from_u64(0x0102_0304_0506_0708),
+                api::TRACE_FLAG_SAMPLED,
+                false,
+                "vendor=value".parse().unwrap(),
+            ),
+            parent_span_id: api::SpanId::from_u64(parent),
+            span_kind: api::SpanKind::Server,
+            name: "GET /".to_string(),
+            start_time: UNIX_EPOCH + Duration::from_secs(1),
+            end_time: UNIX_EPOCH + Duration::from_secs(3),
+            attributes,
+            message_events,
+            links,
+            status_code: api::StatusCode::Unavailable,
+            status_message: "upstream down".to_string(),
+            resource,
+        }
+    }
+
+    #[test]
+    fn converts_span_data() {
+        let resource = Arc::new(sdk::Resource::new(vec![api::KeyValue::new(
+            "service.name",
+            "checkout",
+        )]));
+        let request = build_request(vec![Arc::new(span_data(resource, 0))]);
+
+        assert_eq!(request.resource_spans.len(), 1);
+        let resource_spans = &request.resource_spans[0];
+        assert_eq!(
+            resource_spans.resource.as_ref().unwrap().attributes,
+            vec![KeyValue {
+                key: "service.name".to_string(),
+                value: Some(AnyValue {
+                    value: Some(any_value::Value::StringValue("checkout".to_string()))
+                }),
+            }]
+        );
+
+        let span = &resource_spans.instrumentation_library_spans[0].spans[0];
+        assert_eq!(
+            span.trace_id,
+            (1..=16).collect::<Vec<u8>>(),
+            "trace ids are big endian"
+        );
+        assert_eq!(span.span_id, (1..=8).collect::<Vec<u8>>());
+        assert_eq!(span.trace_state, "vendor=value");
+        assert!(span.parent_span_id.is_empty());
+        assert_eq!(span.kind, span::SpanKind::Server as i32);
+        assert_eq!(span.start_time_unix_nano, 1_000_000_000);
+        assert_eq!(span.end_time_unix_nano, 3_000_000_000);
+        assert_eq!(span.attributes.len(), 1);
+        assert_eq!(span.attributes[0].key, "http.status_code");
+        assert_eq!(span.dropped_attributes_count, 1);
+        assert_eq!(span.events[0].name, "exception");
+        assert_eq!(span.events[0].time_unix_nano, 2_000_000_000);
+        assert_eq!(span.links[0].span_id, 4u64.to_be_bytes().to_vec());
+        let status = span.status.as_ref().unwrap();
+        assert_eq!(status.code, 14);
+        assert_eq!(status.message, "upstream down");
+    }
+
+    #[test]
+    fn groups_spans_by_resource() {
+        let first = Arc::new(sdk::Resource::new(vec![api::KeyValue::new("a", "1")]));
+        let second = Arc::new(sdk::Resource::new(vec![api::KeyValue::new("b", "2")]));
+        let request = build_request(vec![
+            Arc::new(span_data(first.clone(), 1)),
+            Arc::new(span_data(second, 1)),
+            Arc::new(span_data(first, 1)),
+        ]);
+
+        assert_eq!(request.resource_spans.len(), 2);
+        assert_eq!(
+            request.resource_spans[0].instrumentation_library_spans[0]
+                .spans
+                .len(),
+            2
+        );
+        assert_eq!(
+            request.resource_spans[0].instrumentation_library_spans[0].spans[0].parent_span_id,
+            1u64.to_be_bytes().to_vec()
+        );
+    }
+
+    #[test]
+    fn groups_spans_by_equal_resources() {
+        let resource = || Arc::new(sdk::Resource::new(vec![api::KeyValue::new("a", "1")]));
+        let request = build_request(vec![
+            Arc::new(span_data(resource(), 1)),
+            Arc::new(span_data(resource(), 1)),
+        ]);
+
+        assert_eq!(request.resource_spans.len(), 1);
+    }
+}
@@ -0,0 +1,115 @@
+//! # OTLP Span Uploader
+use crate::proto::collector::trace::v1::{
+    trace_service_client::TraceServiceClient, ExportTraceServiceRequest,
+};
+use opentelemetry::exporter::trace;
+use prost::Message;
+use std::sync::{Arc, Mutex};
+use tonic::transport::Channel;
+
+/// Content type used for OTLP binary protobuf requests over HTTP.
+const PROTOBUF_CONTENT_TYPE: &str = "application/x-protobuf";
+
+/// Sends export requests to an OTLP collector.
+#[derive(Debug, Clone)]
+pub(crate) enum Uploader {
+    /// gRPC client, connected lazily on first upload.
+    Grpc {
+        endpoint: String,
+        client: Arc<Mutex<Option<TraceServiceClient<Channel>>>>,
+    },
+    /// HTTP client posting binary protobuf requests.
+    Http {
+        endpoint: http::Uri,
+        client: hyper::Client<hyper::client::HttpConnector>,
+    },
+}
+
+impl Uploader {
+    /// Create a new gRPC uploader for the given collector endpoint.
+    pub(crate) fn grpc(endpoint: String) -> Self {
+        Uploader::Grpc {
+            endpoint,
+            client: Default::default(),
+        }
+    }
+
+    /// Create a new HTTP uploader for the given collector endpoint.
+    pub(crate) fn http(endpoint: http::Uri) -> Self {
+        Uploader::Http {
+            endpoint,
+            client: hyper::Client::new(),
+        }
+    }
+
+    /// Send an export request, must be polled within a tokio runtime.
+    pub(crate) async fn upload(self, request: ExportTraceServiceRequest) -> trace::ExportResult {
+        match self {
+            Uploader::Grpc { endpoint, client } => {
+                let cached = client.lock().ok().and_then(|client| client.clone());
+                let mut grpc_client = match cached {
+                    Some(grpc_client) => grpc_client,
+                    None => match TraceServiceClient::connect(endpoint).await {
+                        Ok(grpc_client) => {
+                            if let Ok(mut client) = client.lock() {
+                                *client = Some(grpc_client.clone());
+                            }
+                            grpc_client
+                        }
+                        // The collector may not be up yet
+                        Err(_) => return trace::ExportResult::FailedRetryable,
+                    },
+                };
+
+                match grpc_client.export(request).await {
+                    Ok(_) => trace::ExportResult::Success,
+                    Err(status) => grpc_result(status.code()),
+                }
+            }
+            Uploader::Http { endpoint, client } => {
+                let mut body = Vec::with_capacity(request.encoded_len());
+                if request.encode(&mut body).is_err() {
+                    return trace::ExportResult::FailedNotRetryable;
+                }
+
+                let http_request = match http::Request::post(endpoint)
+                    .header(http::header::CONTENT_TYPE, PROTOBUF_CONTENT_TYPE)
+                    .body(hyper::Body::from(body))
+                {
+                    Ok(http_request) => http_request,
+                    Err(_) => return trace::ExportResult::FailedNotRetryable,
+                };
+
+                match client.request(http_request).await {
+                    Ok(response) => http_result(response.status()),
+                    // Connection level failures are transient
+                    Err(_) => trace::ExportResult::FailedRetryable,
+                }
+            }
+        }
+    }
+}
+
+/// Map gRPC status codes to export results, following the OTLP retry guidance.
+fn grpc_result(code: tonic::Code) -> trace::ExportResult {
+    match code {
+        tonic::Code::Ok => trace::ExportResult::Success,
+        tonic::Code::Cancelled
+        | tonic::Code::DeadlineExceeded
+        | tonic::Code::ResourceExhausted
+        | tonic::Code::Aborted
+        | tonic::Code::OutOfRange
+        | tonic::Code::Unavailable
+        | tonic::Code::DataLoss => trace::ExportResult::FailedRetryable,
+        _ => trace::ExportResult::FailedNotRetryable,
+    }
+}
+
+/// Map HTTP status codes to export results, following the OTLP retry guidance.
+fn http_result(status: http::StatusCode) -> trace::ExportResult {
+    match status.as_u16() {
+        200..=299 => trace::ExportResult::Success,
+        408 | 429 | 502 | 503 | 504 => trace::ExportResult::FailedRetryable,
+        _ => trace::ExportResult::FailedNotRetryable,
+    }
+}
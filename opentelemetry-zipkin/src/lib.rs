@@ -43,6 +43,8 @@ mod uploader;
 use model::{annotation, endpoint, span};
 use opentelemetry::api;
 use opentelemetry::exporter::trace;
+use opentelemetry::sdk::env::{self, EnvError};
+use opentelemetry::semantic_conventions as semcov;
 use std::collections::HashMap;
 use std::net;
 use std::sync::Arc;
@@ -51,6 +53,11 @@ use std::time::{Duration, SystemTime};
 /// Default Zipkin collector endpoint if none specified
 static DEFAULT_COLLECTOR_ENDPOINT: &str = "127.0.0.1:9411";
 
+/// Environment variable with the collector endpoint.
+const OTEL_EXPORTER_ZIPKIN_ENDPOINT: &str = "OTEL_EXPORTER_ZIPKIN_ENDPOINT";
+/// Environment variable with the service name.
+const OTEL_SERVICE_NAME: &str = "OTEL_SERVICE_NAME";
+
 /// Zipkin span exporter
 #[derive(Debug)]
 pub struct Exporter {
@@ -91,6 +98,17 @@ impl ExporterConfig {
 }
 
 impl ExporterConfigBuilder {
+    /// Create a builder configured from the `OTEL_EXPORTER_ZIPKIN_ENDPOINT` and
+    /// `OTEL_SERVICE_NAME` environment variables. Settings assigned on the
+    /// returned builder take precedence.
+    pub fn from_env() -> Result<Self, EnvError> {
+        Ok(ExporterConfigBuilder {
+            service_name: env::var(OTEL_SERVICE_NAME)?,
+            collector_endpoint: env::var(OTEL_EXPORTER_ZIPKIN_ENDPOINT)?,
+            ..Default::default()
+        })
+    }
+
     /// Create `ExporterConfig` struct from current `ExporterConfigBuilder`
     pub fn build(&self) -> ExporterConfig {
         let local_endpoint: endpoint::Endpoint;
@@ -213,7 +231,7 @@ fn into_zipkin_span(config: &ExporterConfig, span_data: Arc<trace::SpanData>) ->
             .attributes
             .iter()
             .map(|(k, v)| {
-                if k == &api::Key::new("span.kind") {
+                if k == &semcov::trace::SPAN_KIND {
                     user_defined_span_kind = true;
                 }
                 api::KeyValue::new(k.clone(), v.clone())
@@ -3,4 +3,5 @@
 set -eu
 
 cargo test --all "$@"
-cargo test --all "$@" --features="default serialize base64_format binary_propagator"
+cargo test --all "$@" --features="default serialize base64_format binary_propagator regex prometheus_server zpages_server"
+cargo test --manifest-path=opentelemetry-jaeger/Cargo.toml "$@" --features="remote_sampler collector_grpc"
@@ -83,15 +83,14 @@ mod tests {
     use super::*;
     use crate::api::trace::b3_propagator::B3Encoding;
     use crate::api::TraceContextExt;
-    use crate::api::{B3Propagator, Context, SpanContext, SpanId, TraceContextPropagator, TraceId};
+    use crate::api::{
+        B3Propagator, Context, SpanContext, SpanId, TraceContextPropagator, TraceId, TraceState,
+    };
     use std::collections::HashMap;
 
     fn test_data() -> Vec<(&'static str, &'static str)> {
         vec![
-            (
-                "b3",
-                "00000000000000000000000000000001-0000000000000001-0",
-            ),
+            ("b3", "00000000000000000000000000000001-0000000000000001-0"),
             (
                 "traceparent",
                 "00-00000000000000000000000000000001-0000000000000001-00",
@@ -134,6 +133,7 @@ mod tests {
             SpanId::from_u64(1),
             0,
             false,
+            TraceState::default(),
         )));
         let mut carrier = HashMap::new();
         composite_propagator.inject_context(&cx, &mut carrier);
@@ -161,6 +161,7 @@ mod tests {
                     SpanId::from_u64(1),
                     0,
                     true,
+                    TraceState::default(),
                 ))
             );
         }
@@ -170,6 +170,12 @@ pub trait Carrier {
     fn get(&self, key: &str) -> Option<&str>;
     /// Add a key and value to the underlying.
     fn set(&mut self, key: &str, value: String);
+    /// Collect all the keys of the underlying data, used by propagators
+    /// reading prefixed keys. Carriers that can't list their keys return
+    /// no keys.
+    fn keys(&self) -> Vec<&str> {
+        Vec::new()
+    }
 }
 
 impl<S: std::hash::BuildHasher> api::Carrier for HashMap<String, String, S> {
@@ -182,6 +188,11 @@ impl<S: std::hash::BuildHasher> api::Carrier for HashMap<String, String, S> {
     fn set(&mut self, key: &str, value: String) {
         self.insert(String::from(key), value);
     }
+
+    /// Collect all the keys of the HashMap.
+    fn keys(&self) -> Vec<&str> {
+        self.keys().map(|key| key.as_str()).collect()
+    }
 }
 
 #[cfg(feature = "http")]
@@ -205,6 +216,11 @@ impl api::Carrier for http::HeaderMap {
             }
         }
     }
+
+    /// Collect all the keys of the HeaderMap.
+    fn keys(&self) -> Vec<&str> {
+        self.keys().map(|name| name.as_str()).collect()
+    }
 }
 
 #[cfg(feature = "tonic")]
@@ -222,4 +238,14 @@ impl api::Carrier for tonic::metadata::MetadataMap {
             }
         }
     }
+
+    /// Collect all the keys of the MetadataMap.
+    fn keys(&self) -> Vec<&str> {
+        self.keys()
+            .map(|key| match key {
+                tonic::metadata::KeyRef::Ascii(key) => key.as_str(),
+                tonic::metadata::KeyRef::Binary(key) => key.as_str(),
+            })
+            .collect()
+    }
 }
@@ -14,6 +14,12 @@ impl Key {
         Key(value.into())
     }
 
+    /// Create a new const `Key` from a static string, e.g. for well-known
+    /// attribute names.
+    pub const fn from_static_str(value: &'static str) -> Self {
+        Key(Cow::Borrowed(value))
+    }
+
     /// Create a `KeyValue` pair for `bool` values.
     pub fn bool<T: Into<bool>>(&self, value: T) -> KeyValue {
         KeyValue {
@@ -61,11 +61,14 @@ pub mod counter;
 pub mod gauge;
 pub mod measure;
 pub mod noop;
+pub mod observer;
+pub mod provider;
 pub mod value;
 
 use counter::Counter;
 use gauge::Gauge;
 use measure::Measure;
+use observer::{Observer, ObserverResult};
 use value::MeasurementValue;
 
 /// The implementation-level interface to Set/Add/Record individual
@@ -111,6 +114,9 @@ pub struct MetricOptions {
     ///
     /// - for `Measure`, `true` implies that the metric supports
     ///   positive and negative values
+    ///
+    /// - for `Observer`, `true` implies that the observed values are
+    ///   non-descending
     pub alternate: bool,
 }
 
@@ -133,6 +139,11 @@ impl MetricOptions {
         MetricOptions { keys, ..self }
     }
 
+    /// Set the `alternate` property for the current set of metric options.
+    pub fn with_alternate(self, alternate: bool) -> Self {
+        MetricOptions { alternate, ..self }
+    }
+
     /// Set monotonic for the given set of metric options.
     pub fn with_monotonic(self, _monotonic: bool) -> Self {
         // TODO figure out counter vs gauge issue here.
@@ -216,6 +227,10 @@ pub trait Meter {
     type I64Measure: Measure<i64, Self::LabelSet>;
     /// The `F64Measure` data type for this meter.
     type F64Measure: Measure<f64, Self::LabelSet>;
+    /// The `I64Observer` data type for this meter.
+    type I64Observer: Observer;
+    /// The `F64Observer` data type for this meter.
+    type F64Observer: Observer;
 
     /// Returns a reference to a set of labels that cannot be read by the application.
     fn labels(&self, key_values: Vec<api::KeyValue>) -> Self::LabelSet;
@@ -238,6 +253,30 @@ pub trait Meter {
     /// Creates a new `f64` measure with a given name and customized with passed options.
     fn new_f64_measure<S: Into<String>>(&self, name: S, opts: MetricOptions) -> Self::F64Measure;
 
+    /// Registers a new `i64` observer with a given name, customized with
+    /// passed options. The callback is invoked on every collection.
+    fn register_i64_observer<S, F>(
+        &self,
+        name: S,
+        opts: MetricOptions,
+        callback: F,
+    ) -> Self::I64Observer
+    where
+        S: Into<String>,
+        F: Fn(&dyn ObserverResult<i64, Self::LabelSet>) + Send + Sync + 'static;
+
+    /// Registers a new `f64` observer with a given name, customized with
+    /// passed options. The callback is invoked on every collection.
+    fn register_f64_observer<S, F>(
+        &self,
+        name: S,
+        opts: MetricOptions,
+        callback: F,
+    ) -> Self::F64Observer
+    where
+        S: Into<String>,
+        F: Fn(&dyn ObserverResult<f64, Self::LabelSet>) + Send + Sync + 'static;
+
     /// Atomically records a batch of measurements.
     fn record_batch<M: IntoIterator<Item = Measurement<Self::LabelSet>>>(
         &self,
@@ -7,6 +7,19 @@ use crate::api;
 use std::marker;
 use std::sync::Arc;
 
+/// A no-op instance of a `MeterProvider`.
+#[derive(Clone, Debug)]
+pub struct NoopMeterProvider {}
+
+impl api::MeterProvider for NoopMeterProvider {
+    type Meter = NoopMeter;
+
+    /// Returns a new `NoopMeter` instance.
+    fn meter(&self, _name: &'static str) -> Self::Meter {
+        NoopMeter {}
+    }
+}
+
 /// A no-op instance of a `Meter`.
 #[derive(Clone, Debug)]
 pub struct NoopMeter {}
@@ -19,6 +32,8 @@ impl api::Meter for NoopMeter {
     type F64Gauge = NoopGauge<f64>;
     type I64Measure = NoopMeasure<i64>;
     type F64Measure = NoopMeasure<f64>;
+    type I64Observer = NoopObserver;
+    type F64Observer = NoopObserver;
 
     /// Returns a no-op `NoopLabelSet`.
     fn labels(&self, _key_values: Vec<api::KeyValue>) -> Self::LabelSet {
@@ -91,6 +106,34 @@ impl api::Meter for NoopMeter {
         }
     }
 
+    /// Returns a no-op `I64Observer` instance, the callback is never invoked.
+    fn register_i64_observer<S, F>(
+        &self,
+        _name: S,
+        _opts: api::MetricOptions,
+        _callback: F,
+    ) -> Self::I64Observer
+    where
+        S: Into<String>,
+        F: Fn(&dyn api::ObserverResult<i64, NoopLabelSet>) + Send + Sync + 'static,
+    {
+        NoopObserver {}
+    }
+
+    /// Returns a no-op `F64Observer` instance, the callback is never invoked.
+    fn register_f64_observer<S, F>(
+        &self,
+        _name: S,
+        _opts: api::MetricOptions,
+        _callback: F,
+    ) -> Self::F64Observer
+    where
+        S: Into<String>,
+        F: Fn(&dyn api::ObserverResult<f64, NoopLabelSet>) + Send + Sync + 'static,
+    {
+        NoopObserver {}
+    }
+
     /// Ignores batch recordings
     fn record_batch<M: IntoIterator<Item = api::Measurement<NoopLabelSet>>>(
         &self,
@@ -266,3 +309,13 @@ impl<T> api::Instrument<NoopLabelSet> for NoopMeasure<T> {
         // Ignored
     }
 }
+
+/// A no-op instance of an `Observer`.
+#[derive(Clone, Debug)]
+pub struct NoopObserver {}
+
+impl api::Observer for NoopObserver {
+    fn unregister(&self) {
+        // Ignored
+    }
+}
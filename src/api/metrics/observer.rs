@@ -0,0 +1,34 @@
+//! # Metrics Observer Interface
+//!
+//! `Observer`s are asynchronous instruments. Instead of recording values as
+//! they happen, an observer is registered with a callback that the SDK
+//! invokes every time metrics are collected. The callback reports the
+//! current values through the `ObserverResult` it is passed, once for each
+//! label set of interest.
+//!
+//! Observers are useful for values that are expensive to compute or that
+//! are owned by another component, such as queue depths, memory usage or
+//! the size of a connection pool.
+//!
+//! Observed values are treated like `Gauge` values. As with gauges, the
+//! `alternate` option declares the observed values to rise monotonically,
+//! which allows reporting computed cumulative sums like a count of bytes
+//! read since startup.
+use crate::api::metrics;
+
+/// Reports the values of an `Observer` during a collection.
+pub trait ObserverResult<T, LS>
+where
+    T: Into<metrics::value::MeasurementValue>,
+    LS: metrics::LabelSet,
+{
+    /// Reports the current value for the given labels.
+    fn observe(&self, value: T, label_set: &LS);
+}
+
+/// A registered observer instrument.
+pub trait Observer {
+    /// Removes the observer, its callback will no longer be invoked and its
+    /// values are no longer reported.
+    fn unregister(&self);
+}
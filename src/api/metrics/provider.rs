@@ -0,0 +1,17 @@
+//! # OpenTelemetry Meter Provider Interface
+//!
+//! New `Meter` instances are created via a `MeterProvider` and its `meter`
+//! method. The name passed to it should identify the instrumentation library
+//! (e.g. `io.opentelemetry.contrib.mongodb`) and is used by SDKs to namespace
+//! the instruments created by the returned meter.
+use crate::api;
+use std::fmt;
+
+/// An interface to create `Meter` instances.
+pub trait MeterProvider: fmt::Debug + 'static {
+    /// The `Meter` type that this `MeterProvider` will return.
+    type Meter: api::Meter;
+
+    /// Creates a named meter instance of `Self::Meter`.
+    fn meter(&self, name: &'static str) -> Self::Meter;
+}
@@ -32,7 +32,9 @@ pub use metrics::{
     counter::{Counter, CounterHandle},
     gauge::{Gauge, GaugeHandle},
     measure::{Measure, MeasureHandle},
-    noop::NoopMeter,
+    noop::{NoopMeter, NoopMeterProvider},
+    observer::{Observer, ObserverResult},
+    provider::MeterProvider,
     value::MeasurementValue,
     Instrument, InstrumentHandle, LabelSet, Measurement, Meter, MetricOptions,
 };
@@ -42,15 +44,18 @@ pub use trace::{
     event::Event,
     futures::FutureExt,
     id_generator::IdGenerator,
+    jaeger_propagator::JaegerPropagator,
     link::Link,
     noop::{NoopProvider, NoopSpan, NoopSpanExporter, NoopTracer},
     provider::Provider,
     sampler::{Sampler, SamplingDecision, SamplingResult},
     span::{Span, SpanKind, StatusCode},
     span_context::{
-        SpanContext, SpanId, TraceId, TRACE_FLAG_DEBUG, TRACE_FLAG_DEFERRED, TRACE_FLAG_SAMPLED, TRACE_FLAG_NOT_SAMPLED
+        SpanContext, SpanId, TraceId, TraceState, TraceStateError, TRACE_FLAG_DEBUG,
+        TRACE_FLAG_DEFERRED, TRACE_FLAG_NOT_SAMPLED, TRACE_FLAG_SAMPLED,
     },
     span_processor::SpanProcessor,
     trace_context_propagator::TraceContextPropagator,
     tracer::{SpanBuilder, Tracer},
+    xray_propagator::XrayPropagator,
 };
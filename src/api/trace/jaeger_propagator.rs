@@ -0,0 +1,360 @@
+//! # Jaeger Propagator
+//!
+//! The `JaegerPropagator` facilitates `SpanContext` and `CorrelationContext`
+//! propagation using the headers of the Jaeger client libraries:
+//!
+//!  1. Span context:
+//!    uber-trace-id: {trace-id}:{span-id}:{parent-span-id}:{flags}
+//!  2. Baggage, one header per entry:
+//!    uberctx-{key}: {value}
+//!
+//! Trace ids may be 64 or 128 bits, and leading zeros of the ids may be
+//! omitted. The parent span id is deprecated, it is ignored on extraction and
+//! injected as `0`. Flags are a bitmask where `1` means sampled and `2` debug.
+use crate::api::{self, CorrelationContextExt, TraceContextExt};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, CONTROLS};
+
+static JAEGER_HEADER: &str = "uber-trace-id";
+static JAEGER_BAGGAGE_PREFIX: &str = "uberctx-";
+const JAEGER_FLAG_SAMPLED: u8 = 0x01;
+const JAEGER_FLAG_DEBUG: u8 = 0x02;
+const FRAGMENT: &AsciiSet = &CONTROLS.add(b' ').add(b'"').add(b'%').add(b',');
+
+/// Extracts and injects `SpanContext`s and `CorrelationContext`s into
+/// `Carrier`s using the Jaeger header format.
+#[derive(Clone, Debug, Default)]
+pub struct JaegerPropagator {
+    _private: (),
+}
+
+impl JaegerPropagator {
+    /// Create a new `JaegerPropagator`.
+    pub fn new() -> Self {
+        JaegerPropagator::default()
+    }
+
+    /// Extract trace id from hex encoded &str value.
+    fn extract_trace_id(&self, trace_id: &str) -> Result<api::TraceId, ()> {
+        if trace_id.is_empty() || trace_id.len() > 32 {
+            return Err(());
+        }
+
+        u128::from_str_radix(trace_id, 16)
+            .map(api::TraceId::from_u128)
+            .map_err(|_| ())
+    }
+
+    /// Extract span id from hex encoded &str value.
+    fn extract_span_id(&self, span_id: &str) -> Result<api::SpanId, ()> {
+        if span_id.is_empty() || span_id.len() > 16 {
+            return Err(());
+        }
+
+        u64::from_str_radix(span_id, 16)
+            .map(api::SpanId::from_u64)
+            .map_err(|_| ())
+    }
+
+    /// Extract trace flags from the Jaeger flags bitmask, debug implies sampled.
+    fn extract_trace_flags(&self, flags: &str) -> Result<u8, ()> {
+        if flags.is_empty() || flags.len() > 2 {
+            return Err(());
+        }
+
+        let flags = u8::from_str_radix(flags, 16).map_err(|_| ())?;
+        if flags & JAEGER_FLAG_DEBUG == JAEGER_FLAG_DEBUG {
+            Ok(api::TRACE_FLAG_DEBUG | api::TRACE_FLAG_SAMPLED)
+        } else if flags & JAEGER_FLAG_SAMPLED == JAEGER_FLAG_SAMPLED {
+            Ok(api::TRACE_FLAG_SAMPLED)
+        } else {
+            Ok(api::TRACE_FLAG_NOT_SAMPLED)
+        }
+    }
+
+    /// Extract a `SpanContext` from the `uber-trace-id` header.
+    fn extract_span_context(&self, carrier: &dyn api::Carrier) -> Result<api::SpanContext, ()> {
+        let header_value = carrier.get(JAEGER_HEADER).ok_or(())?;
+        // Some clients send the header url encoded
+        let header_value = percent_decode_str(header_value)
+            .decode_utf8()
+            .map_err(|_| ())?;
+        let parts = header_value.split(':').collect::<Vec<&str>>();
+        if parts.len() != 4 {
+            return Err(());
+        }
+
+        let trace_id = self.extract_trace_id(parts[0])?;
+        let span_id = self.extract_span_id(parts[1])?;
+        // Ensure parent id was valid
+        let _ = self.extract_span_id(parts[2])?;
+        let trace_flags = self.extract_trace_flags(parts[3])?;
+
+        let span_context = api::SpanContext::new(
+            trace_id,
+            span_id,
+            trace_flags,
+            true,
+            api::TraceState::default(),
+        );
+
+        // Ensure span is valid
+        if !span_context.is_valid() {
+            return Err(());
+        }
+
+        Ok(span_context)
+    }
+
+    /// Extract the baggage from the `uberctx-` headers.
+    fn extract_baggage(&self, carrier: &dyn api::Carrier) -> Vec<api::KeyValue> {
+        carrier
+            .keys()
+            .into_iter()
+            .filter_map(|key| {
+                let name = key
+                    .to_lowercase()
+                    .strip_prefix(JAEGER_BAGGAGE_PREFIX)?
+                    .to_string();
+                let value = percent_decode_str(carrier.get(key)?).decode_utf8().ok()?;
+                if name.is_empty() {
+                    None
+                } else {
+                    Some(api::KeyValue::new(name, value.into_owned()))
+                }
+            })
+            .collect()
+    }
+}
+
+impl api::HttpTextFormat for JaegerPropagator {
+    /// Properly encodes the values of the `Context`'s `SpanContext` and
+    /// `CorrelationContext` and injects them into the `Carrier`.
+    fn inject_context(&self, context: &api::Context, carrier: &mut dyn api::Carrier) {
+        let span_context = context.span().span_context();
+        if span_context.is_valid() {
+            let trace_id = span_context.trace_id().to_u128();
+            // Only send 128 bit trace ids if needed, not all clients support them
+            let trace_id = if trace_id >> 64 == 0 {
+                format!("{:016x}", trace_id)
+            } else {
+                format!("{:032x}", trace_id)
+            };
+            let flags = if span_context.is_debug() {
+                JAEGER_FLAG_DEBUG | JAEGER_FLAG_SAMPLED
+            } else if span_context.is_sampled() {
+                JAEGER_FLAG_SAMPLED
+            } else {
+                0
+            };
+
+            carrier.set(
+                JAEGER_HEADER,
+                format!(
+                    "{}:{:016x}:0:{}",
+                    trace_id,
+                    span_context.span_id().to_u64(),
+                    flags
+                ),
+            );
+        }
+
+        for (name, value) in context.correlation_context() {
+            carrier.set(
+                &format!("{}{}", JAEGER_BAGGAGE_PREFIX, name.as_str()),
+                utf8_percent_encode(&String::from(value), FRAGMENT).to_string(),
+            );
+        }
+    }
+
+    /// Retrieves encoded data using the provided `Carrier`. If no span context
+    /// was retrieved OR if the retrieved data is invalid, then an empty span
+    /// context is set on the returned `Context`.
+    fn extract_with_context(&self, cx: &api::Context, carrier: &dyn api::Carrier) -> api::Context {
+        let span_context = self
+            .extract_span_context(carrier)
+            .unwrap_or_else(|_| api::SpanContext::empty_context());
+        let baggage = self.extract_baggage(carrier);
+
+        let cx = cx.with_remote_span_context(span_context);
+        if baggage.is_empty() {
+            cx
+        } else {
+            cx.with_correlations(baggage)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{HttpTextFormat, SpanId, TraceId, TRACE_FLAG_DEBUG, TRACE_FLAG_SAMPLED};
+    use std::collections::HashMap;
+
+    const TRACE_ID: u128 = 0x4bf9_2f35_77b3_4da6_a3ce_929d_0e0e_4736;
+    const SPAN_ID: u64 = 0x00f0_67aa_0ba9_02b7;
+
+    fn span_context(trace_id: u128, span_id: u64, trace_flags: u8) -> api::SpanContext {
+        api::SpanContext::new(
+            TraceId::from_u128(trace_id),
+            SpanId::from_u64(span_id),
+            trace_flags,
+            true,
+            api::TraceState::default(),
+        )
+    }
+
+    #[rustfmt::skip]
+    fn extract_data() -> Vec<(&'static str, api::SpanContext)> {
+        vec![
+            ("4bf92f3577b34da6a3ce929d0e0e4736:00f067aa0ba902b7:0:1", span_context(TRACE_ID, SPAN_ID, TRACE_FLAG_SAMPLED)), // sampled
+            ("4bf92f3577b34da6a3ce929d0e0e4736:00f067aa0ba902b7:0:0", span_context(TRACE_ID, SPAN_ID, api::TRACE_FLAG_NOT_SAMPLED)), // not sampled
+            ("4bf92f3577b34da6a3ce929d0e0e4736:00f067aa0ba902b7:0:3", span_context(TRACE_ID, SPAN_ID, TRACE_FLAG_DEBUG | TRACE_FLAG_SAMPLED)), // debug
+            ("4bf92f3577b34da6a3ce929d0e0e4736:00f067aa0ba902b7:0:2", span_context(TRACE_ID, SPAN_ID, TRACE_FLAG_DEBUG | TRACE_FLAG_SAMPLED)), // debug implies sampled
+            ("4bf92f3577b34da6a3ce929d0e0e4736:00f067aa0ba902b7:a3ce929d0e0e4736:1", span_context(TRACE_ID, SPAN_ID, TRACE_FLAG_SAMPLED)), // with parent span id
+            ("a3ce929d0e0e4736:00f067aa0ba902b7:0:1", span_context(0xa3ce_929d_0e0e_4736, SPAN_ID, TRACE_FLAG_SAMPLED)), // 64 bit trace id
+            ("3ce929d0e0e4736:f067aa0ba902b7:0:1", span_context(0x03ce_929d_0e0e_4736, SPAN_ID, TRACE_FLAG_SAMPLED)), // leading zeros omitted
+            ("4bf92f3577b34da6a3ce929d0e0e4736%3A00f067aa0ba902b7%3A0%3A1", span_context(TRACE_ID, SPAN_ID, TRACE_FLAG_SAMPLED)), // url encoded
+        ]
+    }
+
+    #[rustfmt::skip]
+    fn extract_invalid_data() -> Vec<&'static str> {
+        vec![
+            "",
+            "4bf92f3577b34da6a3ce929d0e0e4736:00f067aa0ba902b7:0", // missing flags
+            "4bf92f3577b34da6a3ce929d0e0e4736:00f067aa0ba902b7:0:1:1", // too many parts
+            "ab4bf92f3577b34da6a3ce929d0e0e4736:00f067aa0ba902b7:0:1", // trace id too long
+            "4bf92f3577b34da6a3ce929d0e0e4736:ab00f067aa0ba902b7:0:1", // span id too long
+            "qw4bf92f3577b34da6a3ce929d0e0e47:00f067aa0ba902b7:0:1", // invalid trace id
+            "4bf92f3577b34da6a3ce929d0e0e4736:00f067aa0ba902b7:0:q", // invalid flags
+            "0:00f067aa0ba902b7:0:1", // zero trace id
+            "4bf92f3577b34da6a3ce929d0e0e4736:0:0:1", // zero span id
+        ]
+    }
+
+    #[test]
+    fn extract_jaeger() {
+        let propagator = JaegerPropagator::new();
+
+        for (header, expected_context) in extract_data() {
+            let mut carrier = HashMap::new();
+            carrier.insert(JAEGER_HEADER.to_string(), header.to_string());
+            assert_eq!(
+                propagator.extract(&carrier).remote_span_context(),
+                Some(&expected_context),
+                "{}",
+                header
+            );
+        }
+
+        for header in extract_invalid_data() {
+            let mut carrier = HashMap::new();
+            carrier.insert(JAEGER_HEADER.to_string(), header.to_string());
+            assert_eq!(
+                propagator.extract(&carrier).remote_span_context(),
+                Some(&api::SpanContext::empty_context()),
+                "{}",
+                header
+            );
+        }
+    }
+
+    #[test]
+    fn extract_baggage() {
+        let mut carrier = HashMap::new();
+        carrier.insert("uberctx-user-id".to_string(), "42".to_string());
+        carrier.insert("Uberctx-Tenant".to_string(), "acme%20corp".to_string());
+        carrier.insert("uberctx-".to_string(), "ignored".to_string());
+        carrier.insert("other".to_string(), "ignored".to_string());
+
+        let cx = JaegerPropagator::new().extract(&carrier);
+        let correlations = cx.correlation_context();
+        assert_eq!(correlations.len(), 2);
+        assert_eq!(
+            correlations.get("user-id"),
+            Some(&api::Value::String("42".to_string()))
+        );
+        assert_eq!(
+            correlations.get("tenant"),
+            Some(&api::Value::String("acme corp".to_string()))
+        );
+    }
+
+    #[derive(Debug)]
+    struct TestSpan(api::SpanContext);
+
+    impl api::Span for TestSpan {
+        fn add_event_with_timestamp(
+            &self,
+            _name: String,
+            _timestamp: std::time::SystemTime,
+            _attributes: Vec<api::KeyValue>,
+        ) {
+        }
+        fn span_context(&self) -> api::SpanContext {
+            self.0.clone()
+        }
+        fn is_recording(&self) -> bool {
+            false
+        }
+        fn set_attribute(&self, _attribute: api::KeyValue) {}
+        fn set_status(&self, _code: api::StatusCode, _message: String) {}
+        fn update_name(&self, _new_name: String) {}
+        fn end(&self) {}
+    }
+
+    #[rustfmt::skip]
+    fn inject_data() -> Vec<(Option<&'static str>, api::SpanContext)> {
+        vec![
+            (Some("4bf92f3577b34da6a3ce929d0e0e4736:00f067aa0ba902b7:0:1"), span_context(TRACE_ID, SPAN_ID, TRACE_FLAG_SAMPLED)),
+            (Some("4bf92f3577b34da6a3ce929d0e0e4736:00f067aa0ba902b7:0:0"), span_context(TRACE_ID, SPAN_ID, api::TRACE_FLAG_NOT_SAMPLED)),
+            (Some("4bf92f3577b34da6a3ce929d0e0e4736:00f067aa0ba902b7:0:3"), span_context(TRACE_ID, SPAN_ID, TRACE_FLAG_DEBUG)),
+            (Some("00000000000000ff:00f067aa0ba902b7:0:1"), span_context(0xff, SPAN_ID, TRACE_FLAG_SAMPLED)), // 64 bit trace id
+            (None, api::SpanContext::empty_context()),
+        ]
+    }
+
+    #[test]
+    fn inject_jaeger() {
+        let propagator = JaegerPropagator::new();
+
+        for (expected_header, context) in inject_data() {
+            let mut carrier = HashMap::new();
+            propagator.inject_context(
+                &api::Context::current_with_span(TestSpan(context)),
+                &mut carrier,
+            );
+
+            assert_eq!(
+                carrier.get(JAEGER_HEADER).map(String::as_str),
+                expected_header
+            );
+        }
+    }
+
+    #[test]
+    fn inject_baggage() {
+        let cx = api::Context::current_with_correlations(vec![
+            api::KeyValue::new("user-id", "42"),
+            api::KeyValue::new("tenant", "acme corp"),
+        ]);
+        let mut carrier = HashMap::new();
+        JaegerPropagator::new().inject_context(&cx, &mut carrier);
+
+        assert_eq!(carrier.get(JAEGER_HEADER), None);
+        assert_eq!(
+            carrier.get("uberctx-user-id").map(String::as_str),
+            Some("42")
+        );
+        assert_eq!(
+            carrier.get("uberctx-tenant").map(String::as_str),
+            Some("acme%20corp")
+        );
+
+        let extracted = JaegerPropagator::new().extract(&carrier);
+        assert_eq!(
+            extracted.correlation_context().get("tenant"),
+            Some(&api::Value::String("acme corp".to_string()))
+        );
+    }
+}
@@ -115,6 +115,7 @@ pub mod context;
 pub mod event;
 pub mod futures;
 pub mod id_generator;
+pub mod jaeger_propagator;
 pub mod link;
 pub mod noop;
 pub mod provider;
@@ -124,3 +125,4 @@ pub mod span_context;
 pub mod span_processor;
 pub mod trace_context_propagator;
 pub mod tracer;
+pub mod xray_propagator;
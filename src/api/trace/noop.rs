@@ -41,6 +41,7 @@ impl NoopSpan {
                 api::SpanId::invalid(),
                 0,
                 false,
+                api::TraceState::default(),
             ),
         }
     }
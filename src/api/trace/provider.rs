@@ -30,4 +30,9 @@ pub trait Provider: fmt::Debug + 'static {
     /// Creates a named tracer instance of `Self::Tracer`.
     /// If the name is an empty string then provider uses default name.
     fn get_tracer(&self, name: &'static str) -> Self::Tracer;
+
+    /// Exports all spans that have ended but are still buffered by this provider.
+    ///
+    /// Providers that do not buffer spans do nothing.
+    fn force_flush(&self) {}
 }
@@ -12,7 +12,9 @@
 //! [w3c TraceContext specification]: https://www.w3.org/TR/trace-context/
 #[cfg(feature = "serialize")]
 use serde::{Deserialize, Serialize};
-
+use std::collections::VecDeque;
+use std::fmt;
+use std::str::FromStr;
 
 /// A SpanContext with TRACE_FLAG_NOT_SAMPLED means the span is not sampled.
 pub const TRACE_FLAG_NOT_SAMPLED: u8 = 0x00;
@@ -80,21 +82,35 @@ pub struct SpanContext {
     span_id: SpanId,
     trace_flags: u8,
     is_remote: bool,
+    trace_state: TraceState,
 }
 
 impl SpanContext {
     /// Create an invalid empty span context
     pub fn empty_context() -> Self {
-        SpanContext::new(TraceId::invalid(), SpanId::invalid(), 0, false)
+        SpanContext::new(
+            TraceId::invalid(),
+            SpanId::invalid(),
+            0,
+            false,
+            TraceState::default(),
+        )
     }
 
     /// Construct a new `SpanContext`
-    pub fn new(trace_id: TraceId, span_id: SpanId, trace_flags: u8, is_remote: bool) -> Self {
+    pub fn new(
+        trace_id: TraceId,
+        span_id: SpanId,
+        trace_flags: u8,
+        is_remote: bool,
+        trace_state: TraceState,
+    ) -> Self {
         SpanContext {
             trace_id,
             span_id,
             trace_flags,
             is_remote,
+            trace_state,
         }
     }
 
@@ -139,4 +155,304 @@ impl SpanContext {
     pub fn is_sampled(&self) -> bool {
         (self.trace_flags & TRACE_FLAG_SAMPLED) == TRACE_FLAG_SAMPLED
     }
+
+    /// Returns the vendor specific `TraceState` carried with this context.
+    pub fn trace_state(&self) -> &TraceState {
+        &self.trace_state
+    }
+}
+
+/// Maximum number of list members allowed in a `TraceState`.
+const TRACE_STATE_MAX_MEMBERS: usize = 32;
+
+/// `TraceState` carries vendor specific trace identification data as an ordered
+/// list of key-value pairs, as described by the [w3c tracestate header].
+///
+/// Mutations return a new `TraceState` with the modified entry moved to the
+/// front of the list, as the most recently updated entry must be propagated
+/// first.
+///
+/// [w3c tracestate header]: https://www.w3.org/TR/trace-context/#tracestate-header
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TraceState(VecDeque<(String, String)>);
+
+impl TraceState {
+    /// Create a new `TraceState` from ordered key-value pairs.
+    ///
+    /// Returns an error if any key or value is invalid, if a key is repeated, or
+    /// if there are more than 32 entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use opentelemetry::api::TraceState;
+    ///
+    /// let trace_state = TraceState::from_key_value(vec![("foo", "bar"), ("rojo", "00f067aa0ba902b7")]);
+    ///
+    /// assert!(trace_state.is_ok());
+    /// assert_eq!(trace_state.unwrap().header(), "foo=bar,rojo=00f067aa0ba902b7")
+    /// ```
+    pub fn from_key_value<T, K, V>(trace_state: T) -> Result<Self, TraceStateError>
+    where
+        T: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let mut entries: VecDeque<(String, String)> = VecDeque::new();
+        for (key, value) in trace_state {
+            let (key, value) = (key.into(), value.into());
+            if !valid_key(&key) {
+                return Err(TraceStateError::InvalidKey(key));
+            }
+            if !valid_value(&value) {
+                return Err(TraceStateError::InvalidValue(value));
+            }
+            if entries.iter().any(|(existing, _)| *existing == key) {
+                return Err(TraceStateError::DuplicateKey(key));
+            }
+            entries.push_back((key, value));
+        }
+
+        if entries.len() > TRACE_STATE_MAX_MEMBERS {
+            return Err(TraceStateError::TooManyMembers(entries.len()));
+        }
+
+        Ok(TraceState(entries))
+    }
+
+    /// Retrieves the value for a given key, if present.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(existing, _)| existing == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns a new `TraceState` with the given entry inserted at the front.
+    ///
+    /// An existing entry for the same key is replaced. If the list would grow
+    /// beyond 32 entries the right-most entry is dropped.
+    pub fn insert<K, V>(&self, key: K, value: V) -> Result<TraceState, TraceStateError>
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let (key, value) = (key.into(), value.into());
+        if !valid_key(&key) {
+            return Err(TraceStateError::InvalidKey(key));
+        }
+        if !valid_value(&value) {
+            return Err(TraceStateError::InvalidValue(value));
+        }
+
+        let mut entries = self.0.clone();
+        entries.retain(|(existing, _)| *existing != key);
+        entries.push_front((key, value));
+        entries.truncate(TRACE_STATE_MAX_MEMBERS);
+
+        Ok(TraceState(entries))
+    }
+
+    /// Returns a new `TraceState` without the entry for the given key.
+    pub fn delete(&self, key: &str) -> Result<TraceState, TraceStateError> {
+        if !valid_key(key) {
+            return Err(TraceStateError::InvalidKey(key.to_string()));
+        }
+
+        let mut entries = self.0.clone();
+        entries.retain(|(existing, _)| existing != key);
+
+        Ok(TraceState(entries))
+    }
+
+    /// Returns an iterator over the entries in propagation order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
+    /// Returns `true` if there are no entries.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Encodes the entries as a w3c `tracestate` header value.
+    pub fn header(&self) -> String {
+        self.iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+impl FromStr for TraceState {
+    type Err = TraceStateError;
+
+    /// Parses a w3c `tracestate` header value, empty list members are ignored.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let entries = s
+            .split(',')
+            .map(str::trim)
+            .filter(|member| !member.is_empty())
+            .map(|member| {
+                let mut parts = member.splitn(2, '=');
+                match (parts.next(), parts.next()) {
+                    (Some(key), Some(value)) => Ok((key, value)),
+                    _ => Err(TraceStateError::InvalidMember(member.to_string())),
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        TraceState::from_key_value(entries)
+    }
+}
+
+/// Errors returned when building or modifying a `TraceState`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TraceStateError {
+    /// The key does not match the w3c key format.
+    InvalidKey(String),
+    /// The value does not match the w3c value format.
+    InvalidValue(String),
+    /// A list member is not a `key=value` pair.
+    InvalidMember(String),
+    /// The same key appears more than once.
+    DuplicateKey(String),
+    /// The list has more than 32 members.
+    TooManyMembers(usize),
+}
+
+impl fmt::Display for TraceStateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TraceStateError::InvalidKey(key) => write!(f, "invalid trace state key {:?}", key),
+            TraceStateError::InvalidValue(value) => {
+                write!(f, "invalid trace state value {:?}", value)
+            }
+            TraceStateError::InvalidMember(member) => {
+                write!(f, "invalid trace state list member {:?}", member)
+            }
+            TraceStateError::DuplicateKey(key) => {
+                write!(f, "duplicate trace state key {:?}", key)
+            }
+            TraceStateError::TooManyMembers(count) => write!(
+                f,
+                "trace state has {} list members, at most {} are allowed",
+                count, TRACE_STATE_MAX_MEMBERS
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TraceStateError {}
+
+/// Keys are either a simple key (`lcalpha 0*255(lcalpha / DIGIT / "_" / "-"/ "*" / "/")`)
+/// or a multi-tenant key of the form `tenant-id@system-id`.
+fn valid_key(key: &str) -> bool {
+    fn valid_char(c: char) -> bool {
+        c.is_ascii_lowercase() || c.is_ascii_digit() || "_-*/".contains(c)
+    }
+
+    if key.len() > 256 {
+        return false;
+    }
+
+    let mut parts = key.splitn(2, '@');
+    let (tenant, system) = (parts.next().unwrap_or(""), parts.next());
+    match system {
+        None => {
+            tenant.starts_with(|c: char| c.is_ascii_lowercase()) && tenant.chars().all(valid_char)
+        }
+        Some(system) => {
+            !tenant.is_empty()
+                && tenant.len() <= 241
+                && tenant.starts_with(|c: char| c.is_ascii_lowercase() || c.is_ascii_digit())
+                && tenant.chars().all(valid_char)
+                && !system.is_empty()
+                && system.len() <= 14
+                && system.starts_with(|c: char| c.is_ascii_lowercase())
+                && system.chars().all(valid_char)
+        }
+    }
+}
+
+/// Values are up to 256 printable ASCII characters excluding `,` and `=`, and
+/// must not end with a space.
+fn valid_value(value: &str) -> bool {
+    value.len() <= 256
+        && !value.ends_with(' ')
+        && value
+            .chars()
+            .all(|c| (' '..='~').contains(&c) && c != ',' && c != '=')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_trace_state_header() {
+        let trace_state: TraceState = "rojo=00f067aa0ba902b7, congo=t61rcWkgMzE,,tenant@vendor=x"
+            .parse()
+            .unwrap();
+
+        assert_eq!(trace_state.get("congo"), Some("t61rcWkgMzE"));
+        assert_eq!(trace_state.get("missing"), None);
+        assert_eq!(
+            trace_state.header(),
+            "rojo=00f067aa0ba902b7,congo=t61rcWkgMzE,tenant@vendor=x"
+        );
+    }
+
+    #[test]
+    fn reject_invalid_trace_state() {
+        for header in &[
+            "Upper=value",
+            "1key=value",
+            "key=value,key=other",
+            "key=val=ue",
+            "key",
+            "@vendor=value",
+        ] {
+            assert!(header.parse::<TraceState>().is_err(), "{}", header);
+        }
+
+        let too_many = (0..33)
+            .map(|i| format!("key{}=value", i))
+            .collect::<Vec<_>>()
+            .join(",");
+        assert_eq!(
+            too_many.parse::<TraceState>(),
+            Err(TraceStateError::TooManyMembers(33))
+        );
+    }
+
+    #[test]
+    fn mutations_move_entries_to_front() {
+        let trace_state = TraceState::from_key_value(vec![("a", "1"), ("b", "2")]).unwrap();
+
+        let updated = trace_state.insert("b", "3").unwrap();
+        assert_eq!(updated.header(), "b=3,a=1");
+        let added = updated.insert("c", "4").unwrap();
+        assert_eq!(added.header(), "c=4,b=3,a=1");
+        assert_eq!(added.delete("b").unwrap().header(), "c=4,a=1");
+
+        // the original is left untouched
+        assert_eq!(trace_state.header(), "a=1,b=2");
+        assert!(trace_state.insert("B", "1").is_err());
+        assert!(trace_state.insert("b", "1,2").is_err());
+        assert!(trace_state.insert("b", "trailing ").is_err());
+    }
+
+    #[test]
+    fn insert_drops_oldest_entry_when_full() {
+        let full = TraceState::from_key_value((0..32).map(|i| (format!("key{}", i), "v"))).unwrap();
+
+        let updated = full.insert("new", "v").unwrap();
+        assert_eq!(updated.iter().count(), 32);
+        assert_eq!(updated.get("new"), Some("v"));
+        assert_eq!(updated.get("key31"), None);
+    }
 }
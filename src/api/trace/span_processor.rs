@@ -40,6 +40,10 @@ use std::sync::Arc;
 /// `SpanProcessor`s allow finished spans to be processed.
 pub trait SpanProcessor: Send + Sync + std::fmt::Debug {
     /// `on_start` method is invoked when a `Span` is started.
+    ///
+    /// Processors are called for all recording spans, spans that are recorded
+    /// but not sampled do not have the sampled flag set in their
+    /// `span_context` and should not be exported.
     fn on_start(&self, span: Arc<exporter::trace::SpanData>);
     /// `on_end` method is invoked when a `Span` is ended.
     fn on_end(&self, span: Arc<exporter::trace::SpanData>);
@@ -47,4 +51,9 @@ pub trait SpanProcessor: Send + Sync + std::fmt::Debug {
     /// processor data. No calls to `on_start` and `on_end` method is invoked
     /// after `shutdown` call is made.
     fn shutdown(&self);
+    /// Exports all spans that have ended but have not yet been exported,
+    /// blocking until the export completes or the processor's timeout elapses.
+    ///
+    /// Does nothing by default, for processors that do not buffer spans.
+    fn force_flush(&self) {}
 }
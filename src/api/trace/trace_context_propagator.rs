@@ -14,6 +14,11 @@
 //!    - parent-id
 //!    - trace-flags
 //!
+//! The optional `tracestate` header carries vendor specific trace
+//! identification data alongside the `traceparent`, for example:
+//!
+//! `tracestate: rojo=00f067aa0ba902b7,congo=t61rcWkgMzE`
+//!
 //! See the [w3c trace-context docs] for more details.
 //!
 //! [w3c trace-context docs]: https://w3c.github.io/trace-context/
@@ -23,6 +28,7 @@ use crate::{api, api::TraceContextExt};
 static SUPPORTED_VERSION: u8 = 0;
 static MAX_VERSION: u8 = 254;
 static TRACEPARENT_HEADER: &str = "traceparent";
+static TRACESTATE_HEADER: &str = "tracestate";
 
 /// Extracts and injects `SpanContext`s into `Carrier`s using the
 /// trace-context format.
@@ -70,8 +76,14 @@ impl TraceContextPropagator {
         // Build trace flags
         let trace_flags = opts & api::TRACE_FLAG_SAMPLED;
 
+        // An invalid tracestate header is discarded without invalidating the traceparent
+        let trace_state = carrier
+            .get(TRACESTATE_HEADER)
+            .and_then(|trace_state| trace_state.parse().ok())
+            .unwrap_or_default();
+
         // create context
-        let span_context = api::SpanContext::new(trace_id, span_id, trace_flags, true);
+        let span_context = api::SpanContext::new(trace_id, span_id, trace_flags, true, trace_state);
 
         // Ensure span is valid
         if !span_context.is_valid() {
@@ -95,7 +107,12 @@ impl api::HttpTextFormat for TraceContextPropagator {
                 span_context.span_id().to_u64(),
                 span_context.trace_flags() & api::TRACE_FLAG_SAMPLED
             );
-            carrier.set(TRACEPARENT_HEADER, header_value)
+            carrier.set(TRACEPARENT_HEADER, header_value);
+
+            let trace_state = span_context.trace_state();
+            if !trace_state.is_empty() {
+                carrier.set(TRACESTATE_HEADER, trace_state.header());
+            }
         }
     }
 
@@ -119,22 +136,22 @@ mod tests {
     #[rustfmt::skip]
     fn extract_data() -> Vec<(&'static str, api::SpanContext)> {
         vec![
-            ("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-00", api::SpanContext::new(api::TraceId::from_u128(0x4bf9_2f35_77b3_4da6_a3ce_929d_0e0e_4736), api::SpanId::from_u64(0x00f0_67aa_0ba9_02b7), 0, true)),
-            ("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01", api::SpanContext::new(api::TraceId::from_u128(0x4bf9_2f35_77b3_4da6_a3ce_929d_0e0e_4736), api::SpanId::from_u64(0x00f0_67aa_0ba9_02b7), 1, true)),
-            ("02-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01", api::SpanContext::new(api::TraceId::from_u128(0x4bf9_2f35_77b3_4da6_a3ce_929d_0e0e_4736), api::SpanId::from_u64(0x00f0_67aa_0ba9_02b7), 1, true)),
-            ("02-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-09", api::SpanContext::new(api::TraceId::from_u128(0x4bf9_2f35_77b3_4da6_a3ce_929d_0e0e_4736), api::SpanId::from_u64(0x00f0_67aa_0ba9_02b7), 1, true)),
-            ("02-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-08", api::SpanContext::new(api::TraceId::from_u128(0x4bf9_2f35_77b3_4da6_a3ce_929d_0e0e_4736), api::SpanId::from_u64(0x00f0_67aa_0ba9_02b7), 0, true)),
-            ("02-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-09-XYZxsf09", api::SpanContext::new(api::TraceId::from_u128(0x4bf9_2f35_77b3_4da6_a3ce_929d_0e0e_4736), api::SpanId::from_u64(0x00f0_67aa_0ba9_02b7), 1, true)),
-            ("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-", api::SpanContext::new(api::TraceId::from_u128(0x4bf9_2f35_77b3_4da6_a3ce_929d_0e0e_4736), api::SpanId::from_u64(0x00f0_67aa_0ba9_02b7), 1, true)),
-            ("01-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-09-", api::SpanContext::new(api::TraceId::from_u128(0x4bf9_2f35_77b3_4da6_a3ce_929d_0e0e_4736), api::SpanId::from_u64(0x00f0_67aa_0ba9_02b7), 1, true)),
+            ("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-00", api::SpanContext::new(api::TraceId::from_u128(0x4bf9_2f35_77b3_4da6_a3ce_929d_0e0e_4736), api::SpanId::from_u64(0x00f0_67aa_0ba9_02b7), 0, true, api::TraceState::default())),
+            ("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01", api::SpanContext::new(api::TraceId::from_u128(0x4bf9_2f35_77b3_4da6_a3ce_929d_0e0e_4736), api::SpanId::from_u64(0x00f0_67aa_0ba9_02b7), 1, true, api::TraceState::default())),
+            ("02-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01", api::SpanContext::new(api::TraceId::from_u128(0x4bf9_2f35_77b3_4da6_a3ce_929d_0e0e_4736), api::SpanId::from_u64(0x00f0_67aa_0ba9_02b7), 1, true, api::TraceState::default())),
+            ("02-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-09", api::SpanContext::new(api::TraceId::from_u128(0x4bf9_2f35_77b3_4da6_a3ce_929d_0e0e_4736), api::SpanId::from_u64(0x00f0_67aa_0ba9_02b7), 1, true, api::TraceState::default())),
+            ("02-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-08", api::SpanContext::new(api::TraceId::from_u128(0x4bf9_2f35_77b3_4da6_a3ce_929d_0e0e_4736), api::SpanId::from_u64(0x00f0_67aa_0ba9_02b7), 0, true, api::TraceState::default())),
+            ("02-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-09-XYZxsf09", api::SpanContext::new(api::TraceId::from_u128(0x4bf9_2f35_77b3_4da6_a3ce_929d_0e0e_4736), api::SpanId::from_u64(0x00f0_67aa_0ba9_02b7), 1, true, api::TraceState::default())),
+            ("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-", api::SpanContext::new(api::TraceId::from_u128(0x4bf9_2f35_77b3_4da6_a3ce_929d_0e0e_4736), api::SpanId::from_u64(0x00f0_67aa_0ba9_02b7), 1, true, api::TraceState::default())),
+            ("01-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-09-", api::SpanContext::new(api::TraceId::from_u128(0x4bf9_2f35_77b3_4da6_a3ce_929d_0e0e_4736), api::SpanId::from_u64(0x00f0_67aa_0ba9_02b7), 1, true, api::TraceState::default())),
         ]
     }
     #[rustfmt::skip]
     fn inject_data() -> Vec<(&'static str, api::SpanContext)> {
         vec![
-            ("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01", api::SpanContext::new(api::TraceId::from_u128(0x4bf9_2f35_77b3_4da6_a3ce_929d_0e0e_4736), api::SpanId::from_u64(0x00f0_67aa_0ba9_02b7), 1, true)),
-            ("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-00", api::SpanContext::new(api::TraceId::from_u128(0x4bf9_2f35_77b3_4da6_a3ce_929d_0e0e_4736), api::SpanId::from_u64(0x00f0_67aa_0ba9_02b7), 0, true)),
-            ("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01", api::SpanContext::new(api::TraceId::from_u128(0x4bf9_2f35_77b3_4da6_a3ce_929d_0e0e_4736), api::SpanId::from_u64(0x00f0_67aa_0ba9_02b7), 0xff, true)),
+            ("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01", api::SpanContext::new(api::TraceId::from_u128(0x4bf9_2f35_77b3_4da6_a3ce_929d_0e0e_4736), api::SpanId::from_u64(0x00f0_67aa_0ba9_02b7), 1, true, api::TraceState::default())),
+            ("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-00", api::SpanContext::new(api::TraceId::from_u128(0x4bf9_2f35_77b3_4da6_a3ce_929d_0e0e_4736), api::SpanId::from_u64(0x00f0_67aa_0ba9_02b7), 0, true, api::TraceState::default())),
+            ("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01", api::SpanContext::new(api::TraceId::from_u128(0x4bf9_2f35_77b3_4da6_a3ce_929d_0e0e_4736), api::SpanId::from_u64(0x00f0_67aa_0ba9_02b7), 0xff, true, api::TraceState::default())),
             ("", api::SpanContext::empty_context()),
         ]
     }
@@ -153,6 +170,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn extract_w3c_tracestate() {
+        let propagator = TraceContextPropagator::new();
+        let traceparent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+        let mut carrier = HashMap::new();
+        carrier.insert(TRACEPARENT_HEADER.to_string(), traceparent.to_string());
+        carrier.insert(
+            TRACESTATE_HEADER.to_string(),
+            "foo=bar,rojo=00f067aa0ba902b7".to_string(),
+        );
+        let extracted = propagator.extract(&carrier);
+        let trace_state = extracted.remote_span_context().unwrap().trace_state();
+        assert_eq!(trace_state.get("foo"), Some("bar"));
+        assert_eq!(trace_state.header(), "foo=bar,rojo=00f067aa0ba902b7");
+
+        carrier.insert(TRACESTATE_HEADER.to_string(), "foo=bar,foo=baz".to_string());
+        let extracted = propagator.extract(&carrier);
+        let span_context = extracted.remote_span_context().unwrap();
+        assert!(
+            span_context.is_valid(),
+            "invalid tracestate keeps traceparent"
+        );
+        assert!(span_context.trace_state().is_empty());
+    }
+
     #[derive(Debug)]
     struct TestSpan(api::SpanContext);
     impl api::Span for TestSpan {
@@ -192,4 +235,31 @@ mod tests {
             )
         }
     }
+
+    #[test]
+    fn inject_w3c_tracestate() {
+        let propagator = TraceContextPropagator::new();
+        let trace_state = api::TraceState::from_key_value(vec![("foo", "bar")]).unwrap();
+        let context = api::SpanContext::new(
+            api::TraceId::from_u128(1),
+            api::SpanId::from_u64(1),
+            api::TRACE_FLAG_SAMPLED,
+            true,
+            trace_state,
+        );
+
+        let mut carrier = HashMap::new();
+        propagator.inject_context(
+            &api::Context::current_with_span(TestSpan(context)),
+            &mut carrier,
+        );
+        assert_eq!(Carrier::get(&carrier, TRACESTATE_HEADER), Some("foo=bar"));
+
+        let mut carrier = HashMap::new();
+        propagator.inject_context(
+            &api::Context::current_with_span(TestSpan(inject_data().remove(0).1)),
+            &mut carrier,
+        );
+        assert_eq!(Carrier::get(&carrier, TRACESTATE_HEADER), None);
+    }
 }
@@ -0,0 +1,293 @@
+//! # AWS X-Ray Propagator
+//!
+//! The `XrayPropagator` facilitates `SpanContext` propagation using the
+//! `X-Amzn-Trace-Id` header of AWS X-Ray, as set by AWS load balancers and
+//! services:
+//!
+//!   X-Amzn-Trace-Id: Root={trace_id};Parent={span_id};Sampled={sampling_state}
+//!
+//! The X-Ray trace id `1-{epoch}-{unique}` is the version `1`, 8 hex digits of
+//! the trace start time in epoch seconds and 24 random hex digits. Joined they
+//! form the 128 bit OpenTelemetry trace id, so spans keep their trace across
+//! AWS services. Use it with the `sdk::XrayIdGenerator` to generate trace ids
+//! X-Ray accepts.
+//!
+//! The sampling state is `1` when sampled, `0` when not sampled and `?` when
+//! the decision is deferred. Headers without `Parent`, set by load balancers
+//! for requests starting a trace, don't carry a span context and are ignored.
+use crate::{api, api::TraceContextExt};
+
+static AWS_XRAY_TRACE_HEADER: &str = "x-amzn-trace-id";
+static TRACE_ID_KEY: &str = "Root";
+static PARENT_ID_KEY: &str = "Parent";
+static SAMPLED_KEY: &str = "Sampled";
+static TRACE_ID_VERSION: &str = "1";
+
+/// Extracts and injects `SpanContext`s into `Carrier`s using the AWS X-Ray
+/// header format.
+#[derive(Clone, Debug, Default)]
+pub struct XrayPropagator {
+    _private: (),
+}
+
+impl XrayPropagator {
+    /// Create a new `XrayPropagator`.
+    pub fn new() -> Self {
+        XrayPropagator::default()
+    }
+
+    /// Extract trace id from the `1-{epoch}-{unique}` X-Ray format.
+    fn extract_trace_id(&self, trace_id: &str) -> Result<api::TraceId, ()> {
+        let parts = trace_id.split('-').collect::<Vec<&str>>();
+        if parts.len() != 3
+            || parts[0] != TRACE_ID_VERSION
+            || parts[1].len() != 8
+            || parts[2].len() != 24
+        {
+            return Err(());
+        }
+
+        u128::from_str_radix(&format!("{}{}", parts[1], parts[2]), 16)
+            .map(api::TraceId::from_u128)
+            .map_err(|_| ())
+    }
+
+    /// Extract span id from hex encoded &str value.
+    fn extract_span_id(&self, span_id: &str) -> Result<api::SpanId, ()> {
+        if span_id.len() != 16 {
+            return Err(());
+        }
+
+        u64::from_str_radix(span_id, 16)
+            .map(api::SpanId::from_u64)
+            .map_err(|_| ())
+    }
+
+    /// Extract sampled state from encoded &str value.
+    fn extract_sampled_state(&self, sampled: &str) -> Result<u8, ()> {
+        match sampled {
+            "0" => Ok(api::TRACE_FLAG_NOT_SAMPLED),
+            "1" => Ok(api::TRACE_FLAG_SAMPLED),
+            "?" => Ok(api::TRACE_FLAG_DEFERRED),
+            _ => Err(()),
+        }
+    }
+
+    /// Extract a `SpanContext` from the `X-Amzn-Trace-Id` header.
+    fn extract_span_context(&self, carrier: &dyn api::Carrier) -> Result<api::SpanContext, ()> {
+        let header_value = carrier.get(AWS_XRAY_TRACE_HEADER).ok_or(())?;
+
+        let mut trace_id = None;
+        let mut span_id = None;
+        let mut trace_flags = api::TRACE_FLAG_DEFERRED;
+        for field in header_value.split(';') {
+            let mut parts = field.splitn(2, '=');
+            let (key, value) = match (parts.next(), parts.next()) {
+                (Some(key), Some(value)) => (key.trim(), value.trim()),
+                // Ignore empty fields, e.g. from a trailing `;`
+                _ if field.trim().is_empty() => continue,
+                _ => return Err(()),
+            };
+
+            if key == TRACE_ID_KEY {
+                trace_id = Some(self.extract_trace_id(value)?);
+            } else if key == PARENT_ID_KEY {
+                span_id = Some(self.extract_span_id(value)?);
+            } else if key == SAMPLED_KEY {
+                trace_flags = self.extract_sampled_state(value)?;
+            }
+            // Other fields, like `Self` set by load balancers, are ignored
+        }
+
+        let span_context = api::SpanContext::new(
+            trace_id.ok_or(())?,
+            span_id.ok_or(())?,
+            trace_flags,
+            true,
+            api::TraceState::default(),
+        );
+
+        // Ensure span is valid
+        if !span_context.is_valid() {
+            return Err(());
+        }
+
+        Ok(span_context)
+    }
+}
+
+impl api::HttpTextFormat for XrayPropagator {
+    /// Properly encodes the values of the `Context`'s `SpanContext` and injects
+    /// them into the `Carrier`.
+    fn inject_context(&self, context: &api::Context, carrier: &mut dyn api::Carrier) {
+        let span_context = context.span().span_context();
+        if span_context.is_valid() {
+            let trace_id = format!("{:032x}", span_context.trace_id().to_u128());
+            let sampled = if span_context.is_deferred() {
+                "?"
+            } else if span_context.is_sampled() {
+                "1"
+            } else {
+                "0"
+            };
+
+            carrier.set(
+                AWS_XRAY_TRACE_HEADER,
+                format!(
+                    "{}={}-{}-{};{}={:016x};{}={}",
+                    TRACE_ID_KEY,
+                    TRACE_ID_VERSION,
+                    &trace_id[..8],
+                    &trace_id[8..],
+                    PARENT_ID_KEY,
+                    span_context.span_id().to_u64(),
+                    SAMPLED_KEY,
+                    sampled
+                ),
+            );
+        }
+    }
+
+    /// Retrieves encoded data using the provided `Carrier`. If no data for this
+    /// format was retrieved OR if the retrieved data is invalid, then an empty
+    /// span context is set on the returned `Context`.
+    fn extract_with_context(&self, cx: &api::Context, carrier: &dyn api::Carrier) -> api::Context {
+        let span_context = self
+            .extract_span_context(carrier)
+            .unwrap_or_else(|_| api::SpanContext::empty_context());
+
+        cx.with_remote_span_context(span_context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{
+        HttpTextFormat, SpanId, TraceId, TRACE_FLAG_DEBUG, TRACE_FLAG_DEFERRED,
+        TRACE_FLAG_NOT_SAMPLED, TRACE_FLAG_SAMPLED,
+    };
+    use std::collections::HashMap;
+
+    const TRACE_ID: u128 = 0x5759_e988_bd86_2e3f_e1be_46a9_9427_2793;
+    const SPAN_ID: u64 = 0x5399_5c3f_42cd_8ad8;
+
+    fn span_context(trace_flags: u8) -> api::SpanContext {
+        api::SpanContext::new(
+            TraceId::from_u128(TRACE_ID),
+            SpanId::from_u64(SPAN_ID),
+            trace_flags,
+            true,
+            api::TraceState::default(),
+        )
+    }
+
+    #[rustfmt::skip]
+    fn extract_data() -> Vec<(&'static str, api::SpanContext)> {
+        vec![
+            ("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1", span_context(TRACE_FLAG_SAMPLED)),
+            ("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=0", span_context(TRACE_FLAG_NOT_SAMPLED)),
+            ("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=?", span_context(TRACE_FLAG_DEFERRED)),
+            ("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8", span_context(TRACE_FLAG_DEFERRED)), // missing sampled state
+            ("Sampled=1;Parent=53995c3f42cd8ad8;Root=1-5759e988-bd862e3fe1be46a994272793", span_context(TRACE_FLAG_SAMPLED)), // any order
+            ("Self=1-5759e988-0123456789abcdef01234567;Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1;", span_context(TRACE_FLAG_SAMPLED)), // unknown fields
+            ("Root=1-5759e988-bd862e3fe1be46a994272793; Parent=53995c3f42cd8ad8; Sampled=1", span_context(TRACE_FLAG_SAMPLED)), // whitespace
+        ]
+    }
+
+    #[rustfmt::skip]
+    fn extract_invalid_data() -> Vec<&'static str> {
+        vec![
+            "",
+            "Root=1-5759e988-bd862e3fe1be46a994272793", // missing parent, as set by load balancers
+            "Parent=53995c3f42cd8ad8;Sampled=1", // missing root
+            "Root=2-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8", // unknown version
+            "Root=1-5759e98-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8", // wrong epoch length
+            "Root=1-5759e988-bd862e3fe1be46a99427279;Parent=53995c3f42cd8ad8", // wrong unique id length
+            "Root=1-5759e988-bd862e3fe1be46a99427279q;Parent=53995c3f42cd8ad8", // invalid trace id
+            "Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad", // wrong span id length
+            "Root=1-5759e988-bd862e3fe1be46a994272793;Parent=0000000000000000", // zero span id
+            "Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=2", // invalid sampled state
+            "Root=1-5759e988-bd862e3fe1be46a994272793;Parent", // invalid field
+        ]
+    }
+
+    #[test]
+    fn extract_xray() {
+        let propagator = XrayPropagator::new();
+
+        for (header, expected_context) in extract_data() {
+            let mut carrier = HashMap::new();
+            carrier.insert(AWS_XRAY_TRACE_HEADER.to_string(), header.to_string());
+            assert_eq!(
+                propagator.extract(&carrier).remote_span_context(),
+                Some(&expected_context),
+                "{}",
+                header
+            );
+        }
+
+        for header in extract_invalid_data() {
+            let mut carrier = HashMap::new();
+            carrier.insert(AWS_XRAY_TRACE_HEADER.to_string(), header.to_string());
+            assert_eq!(
+                propagator.extract(&carrier).remote_span_context(),
+                Some(&api::SpanContext::empty_context()),
+                "{}",
+                header
+            );
+        }
+    }
+
+    #[derive(Debug)]
+    struct TestSpan(api::SpanContext);
+
+    impl api::Span for TestSpan {
+        fn add_event_with_timestamp(
+            &self,
+            _name: String,
+            _timestamp: std::time::SystemTime,
+            _attributes: Vec<api::KeyValue>,
+        ) {
+        }
+        fn span_context(&self) -> api::SpanContext {
+            self.0.clone()
+        }
+        fn is_recording(&self) -> bool {
+            false
+        }
+        fn set_attribute(&self, _attribute: api::KeyValue) {}
+        fn set_status(&self, _code: api::StatusCode, _message: String) {}
+        fn update_name(&self, _new_name: String) {}
+        fn end(&self) {}
+    }
+
+    #[rustfmt::skip]
+    fn inject_data() -> Vec<(Option<&'static str>, api::SpanContext)> {
+        vec![
+            (Some("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1"), span_context(TRACE_FLAG_SAMPLED)),
+            (Some("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=0"), span_context(TRACE_FLAG_NOT_SAMPLED)),
+            (Some("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=?"), span_context(TRACE_FLAG_DEFERRED)),
+            (Some("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1"), span_context(TRACE_FLAG_DEBUG | TRACE_FLAG_SAMPLED)),
+            (None, api::SpanContext::empty_context()),
+        ]
+    }
+
+    #[test]
+    fn inject_xray() {
+        let propagator = XrayPropagator::new();
+
+        for (expected_header, context) in inject_data() {
+            let mut carrier = HashMap::new();
+            propagator.inject_context(
+                &api::Context::current_with_span(TestSpan(context)),
+                &mut carrier,
+            );
+
+            assert_eq!(
+                carrier.get(AWS_XRAY_TRACE_HEADER).map(String::as_str),
+                expected_header
+            );
+        }
+    }
+}
@@ -1,2 +1,309 @@
-//! Metric exporters
+//! # Metric exporters
+//!
+//! The metrics SDK aggregates measurements per instrument and label set and
+//! periodically checkpoints the aggregated state into `Record`s. Each
+//! checkpoint is handed to a `MetricsExporter`, which is responsible for
+//! encoding and transmitting (or exposing) the records to a backend.
+use crate::exporter::ExportResult;
+use crate::sdk;
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops;
+use std::sync::Arc;
+use std::time::SystemTime;
+
 pub mod prometheus;
+
+/// `MetricsExporter` defines the interface that protocol-specific exporters
+/// must implement so that they can be plugged into the metrics SDK.
+pub trait MetricsExporter: Send + Sync + fmt::Debug {
+    /// Exports the records of a single collection. Records of the same
+    /// collection are passed in a single call.
+    ///
+    /// This function will never be called concurrently for the same exporter
+    /// instance.
+    fn export(&self, records: Vec<Record>) -> ExportResult;
+}
+
+/// The kind of an instrument, determining the semantics of its measurements.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum InstrumentKind {
+    /// Measurements are added to a running total.
+    Counter,
+    /// Measurements replace the current value.
+    Gauge,
+    /// Measurements are individual values in a distribution.
+    Measure,
+    /// Values are reported by a callback when metrics are collected.
+    Observer,
+}
+
+/// The type of number recorded by an instrument.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NumberKind {
+    /// Signed 64-bit integers.
+    I64,
+    /// 64-bit floating point numbers.
+    F64,
+}
+
+/// A single measured or aggregated value.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Number {
+    /// An integer value.
+    I64(i64),
+    /// A floating point value.
+    F64(f64),
+}
+
+impl Number {
+    /// The zero value of the given kind.
+    pub fn zero(kind: NumberKind) -> Self {
+        match kind {
+            NumberKind::I64 => Number::I64(0),
+            NumberKind::F64 => Number::F64(0.0),
+        }
+    }
+
+    /// The kind of this number.
+    pub fn kind(&self) -> NumberKind {
+        match self {
+            Number::I64(_) => NumberKind::I64,
+            Number::F64(_) => NumberKind::F64,
+        }
+    }
+
+    /// This number as an `f64`, which may lose precision for large integers.
+    pub fn to_f64(&self) -> f64 {
+        match *self {
+            Number::I64(value) => value as f64,
+            Number::F64(value) => value,
+        }
+    }
+
+    /// The smaller of two numbers.
+    pub fn min(self, other: Number) -> Number {
+        if self.compare(&other) == Ordering::Greater {
+            other
+        } else {
+            self
+        }
+    }
+
+    /// The larger of two numbers.
+    pub fn max(self, other: Number) -> Number {
+        if self.compare(&other) == Ordering::Less {
+            other
+        } else {
+            self
+        }
+    }
+
+    fn compare(&self, other: &Number) -> Ordering {
+        match (self, other) {
+            (Number::I64(a), Number::I64(b)) => a.cmp(b),
+            (a, b) => a
+                .to_f64()
+                .partial_cmp(&b.to_f64())
+                .unwrap_or(Ordering::Equal),
+        }
+    }
+}
+
+impl ops::Add for Number {
+    type Output = Number;
+
+    /// The sum of two numbers. Integers wrap on overflow, mixed kinds are
+    /// added as floating point numbers.
+    fn add(self, other: Number) -> Number {
+        match (self, other) {
+            (Number::I64(a), Number::I64(b)) => Number::I64(a.wrapping_add(b)),
+            (a, b) => Number::F64(a.to_f64() + b.to_f64()),
+        }
+    }
+}
+
+/// Describes a metric instrument.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Descriptor {
+    /// Name of the instrument.
+    pub name: String,
+    /// Name of the component whose `Meter` created the instrument.
+    pub component: &'static str,
+    /// Kind of the instrument.
+    pub instrument_kind: InstrumentKind,
+    /// Type of numbers recorded by the instrument.
+    pub number_kind: NumberKind,
+    /// Human readable description of the instrument.
+    pub description: String,
+    /// Unit of the recorded values, empty if not specified.
+    pub unit: String,
+    /// The `alternate` option the instrument was created with.
+    pub alternate: bool,
+}
+
+/// Aggregated state of one instrument and label set.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Aggregation {
+    /// Sum of all measurements.
+    Sum(Number),
+    /// The most recent measurement.
+    LastValue {
+        /// The recorded value.
+        value: Number,
+        /// When the value was recorded.
+        timestamp: SystemTime,
+    },
+    /// Summary statistics of all measurements.
+    MinMaxSumCount {
+        /// Smallest measurement.
+        min: Number,
+        /// Largest measurement.
+        max: Number,
+        /// Sum of all measurements.
+        sum: Number,
+        /// Number of measurements.
+        count: u64,
+    },
+    /// Distribution of measurements over fixed buckets.
+    Histogram {
+        /// Sorted upper bounds (inclusive) of the buckets.
+        boundaries: Vec<f64>,
+        /// Number of measurements per bucket. Has one more entry than
+        /// `boundaries`, counting measurements above the last boundary.
+        counts: Vec<u64>,
+        /// Sum of all measurements.
+        sum: Number,
+        /// Number of measurements.
+        count: u64,
+    },
+}
+
+impl Aggregation {
+    /// Merge a later aggregation of the same instrument and label set into
+    /// this one, as done when reporting cumulative values.
+    ///
+    /// Aggregations of a different kind, or histograms with different
+    /// boundaries, replace the current state.
+    pub fn merge(&mut self, other: &Aggregation) {
+        match (self, other) {
+            (Aggregation::Sum(sum), Aggregation::Sum(other)) => *sum = *sum + *other,
+            (
+                Aggregation::MinMaxSumCount {
+                    min,
+                    max,
+                    sum,
+                    count,
+                },
+                Aggregation::MinMaxSumCount {
+                    min: other_min,
+                    max: other_max,
+                    sum: other_sum,
+                    count: other_count,
+                },
+            ) => {
+                *min = min.min(*other_min);
+                *max = max.max(*other_max);
+                *sum = *sum + *other_sum;
+                *count += other_count;
+            }
+            (
+                Aggregation::Histogram {
+                    boundaries,
+                    counts,
+                    sum,
+                    count,
+                },
+                Aggregation::Histogram {
+                    boundaries: other_boundaries,
+                    counts: other_counts,
+                    sum: other_sum,
+                    count: other_count,
+                },
+            ) if boundaries == other_boundaries => {
+                for (bucket, other) in counts.iter_mut().zip(other_counts) {
+                    *bucket += other;
+                }
+                *sum = *sum + *other_sum;
+                *count += other_count;
+            }
+            (this, other) => *this = other.clone(),
+        }
+    }
+}
+
+/// The aggregated value of one instrument and label set in a checkpoint.
+#[derive(Clone, Debug)]
+pub struct Record {
+    /// The instrument the value was recorded with.
+    pub descriptor: Arc<Descriptor>,
+    /// The labels the value was recorded with.
+    pub labels: sdk::LabelSet,
+    /// The aggregated value.
+    pub aggregation: Aggregation,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_keep_integer_precision() {
+        let big = Number::I64(i64::max_value() - 1);
+        assert_eq!(big + Number::I64(1), Number::I64(i64::max_value()));
+        assert_eq!(Number::I64(2) + Number::F64(0.5), Number::F64(2.5));
+        assert_eq!(Number::I64(-3).min(Number::I64(2)), Number::I64(-3));
+        assert_eq!(Number::F64(1.5).max(Number::F64(-1.0)), Number::F64(1.5));
+    }
+
+    #[test]
+    fn merge_accumulates_matching_aggregations() {
+        let mut sum = Aggregation::Sum(Number::I64(3));
+        sum.merge(&Aggregation::Sum(Number::I64(4)));
+        assert_eq!(sum, Aggregation::Sum(Number::I64(7)));
+
+        let mut summary = Aggregation::MinMaxSumCount {
+            min: Number::F64(1.0),
+            max: Number::F64(2.0),
+            sum: Number::F64(3.0),
+            count: 2,
+        };
+        summary.merge(&Aggregation::MinMaxSumCount {
+            min: Number::F64(0.5),
+            max: Number::F64(1.0),
+            sum: Number::F64(1.5),
+            count: 2,
+        });
+        assert_eq!(
+            summary,
+            Aggregation::MinMaxSumCount {
+                min: Number::F64(0.5),
+                max: Number::F64(2.0),
+                sum: Number::F64(4.5),
+                count: 4,
+            }
+        );
+
+        let mut histogram = Aggregation::Histogram {
+            boundaries: vec![1.0],
+            counts: vec![1, 0],
+            sum: Number::F64(0.5),
+            count: 1,
+        };
+        histogram.merge(&Aggregation::Histogram {
+            boundaries: vec![1.0],
+            counts: vec![1, 1],
+            sum: Number::F64(2.5),
+            count: 2,
+        });
+        assert_eq!(
+            histogram,
+            Aggregation::Histogram {
+                boundaries: vec![1.0],
+                counts: vec![2, 1],
+                sum: Number::F64(3.0),
+                count: 3,
+            }
+        );
+    }
+}
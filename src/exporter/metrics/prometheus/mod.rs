@@ -1,264 +1,424 @@
 //! # OpenTelemetry Prometheus Exporter
 //!
-//! This exporter currently delegates to the [Prometheus library]
-//! library which implements the [Prometheus API].
+//! This exporter exposes the records of the latest collection through a
+//! [Prometheus library] `Registry`, so they can be served in the format
+//! defined by the [Prometheus API].
+//!
+//! Aggregations are converted into Prometheus metric families as follows:
+//!
+//! - Sums of counters become counters, sums of counters created with the
+//!   `alternate` option (up-down counters) become gauges.
+//! - Last values become gauges, or counters for gauges and observers created
+//!   with the `alternate` option (non-descending values).
+//! - Histograms become histograms.
+//! - Min, max, sum and count summaries become histograms without buckets,
+//!   reporting only the sum and count.
+//!
+//! By default the exporter exposes the records of the most recent collection
+//! of its `Controller`. With `collect_on_gather`, the controller instead
+//! collects whenever the registry is gathered, so observer callbacks are
+//! evaluated on every scrape:
+//!
+//! ```
+//! use opentelemetry::api::{Meter, MetricOptions};
+//! use opentelemetry::exporter::metrics::prometheus::{PrometheusExporter, Registry};
+//! use opentelemetry::sdk;
+//!
+//! let exporter = PrometheusExporter::new(Registry::new()).unwrap();
+//! let controller = sdk::Controller::builder(exporter.clone()).build();
+//! exporter.collect_on_gather(&controller);
+//!
+//! let meter = controller.meter("my_component");
+//! let labels = meter.labels(vec![]);
+//! let _uptime = meter.register_f64_observer("uptime", MetricOptions::default(), move |result| {
+//!     result.observe(42.0, &labels)
+//! });
+//!
+//! assert_eq!(exporter.registry().gather().len(), 1);
+//! ```
+//!
+//! With the `prometheus_server` feature enabled, the [`server`] module can
+//! serve the registry on a `/metrics` HTTP endpoint.
+//!
+//! Family names are the instrument's component and name, followed by its
+//! unit if one is set, with characters that are not valid in Prometheus names
+//! replaced by underscores. When records of different metric types end up
+//! with the same family name, only the first type is exposed and the
+//! conflicting records are skipped, see
+//! [`PrometheusExporter::conflicting_record_count`].
 //!
 //! [Prometheus library]: https://github.com/tikv/rust-prometheus
 //! [Prometheus API]: https://prometheus.io
-use crate::api;
-use crate::sdk;
-use api::Key;
-pub use prometheus::{
-    default_registry, gather, Counter, CounterVec, Encoder, Gauge, GaugeVec, Histogram,
-    HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry,
-    TextEncoder,
-};
-use std::collections::HashMap;
-use std::sync::Arc;
-
-/// Convert from `sdk::LabelSet` to `prometheus`' label format.
-fn convert_label_set(label_set: &sdk::LabelSet) -> HashMap<&str, &str> {
-    label_set
-        .iter()
-        .map(|(key, value)| (key.as_ref(), value.as_ref()))
-        .collect()
-}
-
-/// Convert from list of `Key`s to prometheus' label format.
-pub(crate) fn convert_labels(labels: &[Key]) -> Vec<&str> {
-    labels.iter().map(|k| k.as_str()).collect()
-}
-
-/// Prometheus IntCounterHandle
+//! [`server`]: server/index.html
+//! [`PrometheusExporter::conflicting_record_count`]: struct.PrometheusExporter.html#method.conflicting_record_count
+use crate::exporter::metrics::{Aggregation, InstrumentKind, MetricsExporter, Record};
+use crate::exporter::ExportResult;
+use crate::sdk::metrics::Controller;
+use prometheus::core::{Collector, Desc};
+use prometheus::proto;
+pub use prometheus::{default_registry, gather, Encoder, Error, Registry, TextEncoder};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "prometheus_server")]
+pub mod server;
+
+/// Exposes the records of the latest collection through a Prometheus
+/// `Registry`.
 #[derive(Clone)]
-#[allow(missing_debug_implementations)]
-pub struct IntCounterHandle(prometheus::IntCounter);
-
-impl api::Counter<i64, sdk::LabelSet> for prometheus::IntCounterVec {
-    /// Prometheus' `CounterHandle`
-    type Handle = IntCounterHandle;
-
-    /// Creates a `Measurement` object to be used by a `Meter` when batch recording.
-    fn measurement(&self, value: i64) -> api::Measurement<sdk::LabelSet> {
-        api::Measurement::new(Arc::new(self.clone()), api::MeasurementValue::from(value))
-    }
-
-    /// Creates a handle for this instrument.
-    fn acquire_handle(&self, labels: &sdk::LabelSet) -> Self::Handle {
-        IntCounterHandle(self.with(&convert_label_set(labels)))
-    }
+pub struct PrometheusExporter {
+    registry: Registry,
+    state: Arc<State>,
 }
 
-impl api::Instrument<sdk::LabelSet> for prometheus::IntCounterVec {
-    /// Record a single counter measurement value
-    fn record_one(&self, value: api::MeasurementValue, label_set: &sdk::LabelSet) {
-        self.with(&convert_label_set(label_set))
-            .inc_by(value.into_i64())
-    }
+/// State shared between an exporter and its registered collector.
+#[derive(Default)]
+struct State {
+    records: Mutex<Vec<Record>>,
+    collector: Mutex<Option<Arc<dyn Fn() + Send + Sync>>>,
+    conflicting_records: AtomicU64,
 }
 
-impl api::InstrumentHandle for IntCounterHandle {
-    /// Record a single counter measurement value for preset values
-    fn record_one(&self, value: api::MeasurementValue) {
-        self.0.inc_by(value.into_i64())
+impl PrometheusExporter {
+    /// Creates an exporter and registers it with the given registry. Only one
+    /// exporter can be registered per registry.
+    pub fn new(registry: Registry) -> Result<Self, Error> {
+        let state = Arc::new(State::default());
+        let collector = RecordCollector {
+            desc: Desc::new(
+                "opentelemetry_metrics".to_string(),
+                "Metrics exported by the OpenTelemetry SDK".to_string(),
+                Vec::new(),
+                HashMap::new(),
+            )?,
+            state: state.clone(),
+        };
+        registry.register(Box::new(collector))?;
+
+        Ok(PrometheusExporter { registry, state })
     }
-}
-
-impl api::CounterHandle<i64> for IntCounterHandle {}
-
-/// Prometheus CounterHandle
-#[derive(Clone)]
-#[allow(missing_debug_implementations)]
-pub struct CounterHandle(prometheus::Counter);
 
-impl api::Counter<f64, sdk::LabelSet> for prometheus::CounterVec {
-    type Handle = CounterHandle;
-
-    /// Creates a `Measurement` object to be used by a `Meter` when batch recording.
-    fn measurement(&self, value: f64) -> api::Measurement<sdk::LabelSet> {
-        api::Measurement::new(Arc::new(self.clone()), api::MeasurementValue::from(value))
+    /// Collect from the given controller every time the registry is
+    /// gathered, instead of exposing the records of its last collection.
+    ///
+    /// The exporter does not keep the controller alive, once it is dropped
+    /// the records of the last collection are exposed.
+    pub fn collect_on_gather(&self, controller: &Controller) {
+        if let Ok(mut collector) = self.state.collector.lock() {
+            *collector = Some(Arc::new(controller.collector()));
+        }
     }
 
-    /// Creates a handle for this instrument.
-    fn acquire_handle(&self, labels: &sdk::LabelSet) -> Self::Handle {
-        CounterHandle(self.with(&convert_label_set(labels)))
+    /// Number of records skipped during the last gather because their family
+    /// name was already used by records of a different metric type.
+    pub fn conflicting_record_count(&self) -> u64 {
+        self.state.conflicting_records.load(Ordering::Relaxed)
     }
-}
 
-impl api::Instrument<sdk::LabelSet> for prometheus::CounterVec {
-    /// record a single counter measurement value
-    fn record_one(&self, value: api::MeasurementValue, label_set: &sdk::LabelSet) {
-        self.with(&convert_label_set(label_set))
-            .inc_by(value.into_f64())
+    /// The registry this exporter is registered with.
+    pub fn registry(&self) -> &Registry {
+        &self.registry
     }
 }
 
-impl api::InstrumentHandle for CounterHandle {
-    /// record a single counter measurement value for precomputed labels
-    fn record_one(&self, value: api::MeasurementValue) {
-        self.0.inc_by(value.into_f64())
-    }
-}
-
-impl api::CounterHandle<f64> for CounterHandle {}
-
-// GAUGE COMPAT
-
-/// Prometheus IntGaugeHandle
-#[derive(Clone)]
-#[allow(missing_debug_implementations)]
-pub struct IntGaugeHandle(prometheus::IntGauge);
-
-impl api::Gauge<i64, sdk::LabelSet> for prometheus::IntGaugeVec {
-    type Handle = IntGaugeHandle;
-
-    /// Creates a `Measurement` object to be used by a `Meter` when batch recording.
-    fn measurement(&self, value: i64) -> api::Measurement<sdk::LabelSet> {
-        api::Measurement::new(Arc::new(self.clone()), api::MeasurementValue::from(value))
-    }
-
-    /// Creates a handle for this instrument.
-    fn acquire_handle(&self, labels: &sdk::LabelSet) -> Self::Handle {
-        IntGaugeHandle(self.with(&convert_label_set(labels)))
+impl fmt::Debug for PrometheusExporter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PrometheusExporter")
+            .field("records", &self.state.records)
+            .finish()
     }
 }
 
-impl api::Instrument<sdk::LabelSet> for prometheus::IntGaugeVec {
-    /// record a single gauge measurement value
-    fn record_one(&self, value: api::MeasurementValue, label_set: &sdk::LabelSet) {
-        self.with(&convert_label_set(label_set))
-            .set(value.into_i64())
+impl MetricsExporter for PrometheusExporter {
+    /// Replaces the records exposed to the registry.
+    fn export(&self, records: Vec<Record>) -> ExportResult {
+        match self.state.records.lock() {
+            Ok(mut current) => {
+                *current = records;
+                ExportResult::Success
+            }
+            Err(_) => ExportResult::FailedNotRetryable,
+        }
     }
 }
 
-impl api::InstrumentHandle for IntGaugeHandle {
-    /// record a single gauge measurement value for precomputed labels
-    fn record_one(&self, value: api::MeasurementValue) {
-        self.0.set(value.into_i64())
-    }
+/// Converts the stored records into metric families when the registry is
+/// gathered.
+struct RecordCollector {
+    desc: Desc,
+    state: Arc<State>,
 }
 
-impl api::GaugeHandle<i64> for IntGaugeHandle {}
-
-/// Prometheus GaugeHandle
-#[derive(Clone)]
-#[allow(missing_debug_implementations)]
-pub struct GaugeHandle(prometheus::Gauge);
-
-impl api::Gauge<f64, sdk::LabelSet> for prometheus::GaugeVec {
-    type Handle = GaugeHandle;
-
-    /// Creates a `Measurement` object to be used by a `Meter` when batch recording.
-    fn measurement(&self, value: f64) -> api::Measurement<sdk::LabelSet> {
-        api::Measurement::new(Arc::new(self.clone()), api::MeasurementValue::from(value))
+impl Collector for RecordCollector {
+    fn desc(&self) -> Vec<&Desc> {
+        vec![&self.desc]
     }
 
-    /// Creates a handle for this instrument.
-    fn acquire_handle(&self, labels: &sdk::LabelSet) -> Self::Handle {
-        GaugeHandle(self.with(&convert_label_set(labels)))
+    fn collect(&self) -> Vec<proto::MetricFamily> {
+        let collector = self
+            .state
+            .collector
+            .lock()
+            .ok()
+            .and_then(|collector| collector.clone());
+        if let Some(collect) = collector {
+            collect();
+        }
+
+        let records = match self.state.records.lock() {
+            Ok(records) => records,
+            Err(_) => return Vec::new(),
+        };
+        let mut families = BTreeMap::new();
+        let mut conflicting_records = 0;
+
+        for record in records.iter() {
+            let descriptor = &record.descriptor;
+            let name = family_name(descriptor.component, &descriptor.name, &descriptor.unit);
+            let (metric_type, metric) = convert_record(record);
+
+            let family = families.entry(name.clone()).or_insert_with(|| {
+                let mut family = proto::MetricFamily::default();
+                family.set_name(name.clone());
+                family.set_help(if descriptor.description.is_empty() {
+                    format!("{} metric", name)
+                } else {
+                    descriptor.description.clone()
+                });
+                family.set_field_type(metric_type);
+                family
+            });
+            if family.get_field_type() != metric_type {
+                conflicting_records += 1;
+                continue;
+            }
+            family.mut_metric().push(metric);
+        }
+
+        self.state
+            .conflicting_records
+            .store(conflicting_records, Ordering::Relaxed);
+        families.into_values().collect()
     }
 }
 
-impl api::Instrument<sdk::LabelSet> for prometheus::GaugeVec {
-    /// record a single gauge measurement value
-    fn record_one(&self, value: api::MeasurementValue, label_set: &sdk::LabelSet) {
-        self.with(&convert_label_set(label_set))
-            .set(value.into_f64())
+/// Builds the Prometheus metric for a single record.
+fn convert_record(record: &Record) -> (proto::MetricType, proto::Metric) {
+    let mut metric = proto::Metric::default();
+    for (key, value) in record.labels.iter() {
+        let mut label = proto::LabelPair::default();
+        label.set_name(sanitize(key.as_str()));
+        label.set_value(value.clone().into());
+        metric.mut_label().push(label);
     }
-}
 
-impl api::InstrumentHandle for GaugeHandle {
-    /// record a single gauge measurement value for precomputed labels
-    fn record_one(&self, value: api::MeasurementValue) {
-        self.0.set(value.into_f64())
-    }
+    let metric_type = match &record.aggregation {
+        Aggregation::Sum(sum) => {
+            if record.descriptor.instrument_kind == InstrumentKind::Counter
+                && !record.descriptor.alternate
+            {
+                let mut counter = proto::Counter::default();
+                counter.set_value(sum.to_f64());
+                metric.set_counter(counter);
+                proto::MetricType::COUNTER
+            } else {
+                let mut gauge = proto::Gauge::default();
+                gauge.set_value(sum.to_f64());
+                metric.set_gauge(gauge);
+                proto::MetricType::GAUGE
+            }
+        }
+        Aggregation::LastValue { value, .. } => {
+            if record.descriptor.alternate {
+                let mut counter = proto::Counter::default();
+                counter.set_value(value.to_f64());
+                metric.set_counter(counter);
+                proto::MetricType::COUNTER
+            } else {
+                let mut gauge = proto::Gauge::default();
+                gauge.set_value(value.to_f64());
+                metric.set_gauge(gauge);
+                proto::MetricType::GAUGE
+            }
+        }
+        Aggregation::MinMaxSumCount { sum, count, .. } => {
+            let mut histogram = proto::Histogram::default();
+            histogram.set_sample_sum(sum.to_f64());
+            histogram.set_sample_count(*count);
+            metric.set_histogram(histogram);
+            proto::MetricType::HISTOGRAM
+        }
+        Aggregation::Histogram {
+            boundaries,
+            counts,
+            sum,
+            count,
+        } => {
+            let mut histogram = proto::Histogram::default();
+            let mut cumulative_count = 0;
+            for (upper_bound, bucket_count) in boundaries.iter().zip(counts) {
+                cumulative_count += bucket_count;
+                let mut bucket = proto::Bucket::default();
+                bucket.set_upper_bound(*upper_bound);
+                bucket.set_cumulative_count(cumulative_count);
+                histogram.mut_bucket().push(bucket);
+            }
+            histogram.set_sample_sum(sum.to_f64());
+            histogram.set_sample_count(*count);
+            metric.set_histogram(histogram);
+            proto::MetricType::HISTOGRAM
+        }
+    };
+
+    (metric_type, metric)
 }
 
-impl api::GaugeHandle<f64> for GaugeHandle {}
-
-// MEASURE COMPAT
-
-/// Prometheus IntMeasureHandle
-#[derive(Clone)]
-#[allow(missing_debug_implementations)]
-pub struct IntMeasureHandle(prometheus::Histogram);
-
-/// Prometheus Histograms do not have i64 variant, `IntMeasure` will convert i64 to float when it
-/// records values.
-#[derive(Clone)]
-#[allow(missing_debug_implementations)]
-pub struct IntMeasure(prometheus::HistogramVec);
-
-impl IntMeasure {
-    pub(crate) fn new(histogram: prometheus::HistogramVec) -> Self {
-        IntMeasure(histogram)
+/// Prometheus family name of an instrument.
+fn family_name(component: &str, name: &str, unit: &str) -> String {
+    let mut family = format!("{}_{}", component, name);
+    if !unit.is_empty() {
+        family.push('_');
+        family.push_str(unit);
     }
+    sanitize(&family)
 }
 
-impl api::Measure<i64, sdk::LabelSet> for IntMeasure {
-    type Handle = IntMeasureHandle;
-
-    /// Creates a `Measurement` object to be used by a `Meter` when batch recording.
-    fn measurement(&self, value: i64) -> api::Measurement<sdk::LabelSet> {
-        api::Measurement::new(Arc::new(self.clone()), api::MeasurementValue::from(value))
-    }
-
-    /// Creates a handle for this instrument.
-    fn acquire_handle(&self, labels: &sdk::LabelSet) -> Self::Handle {
-        IntMeasureHandle(self.0.with(&convert_label_set(labels)))
+/// Replaces characters that are not valid in Prometheus names with
+/// underscores, prefixing names that start with a digit.
+fn sanitize(name: &str) -> String {
+    let mut sanitized = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+    if sanitized.starts_with(|c: char| c.is_ascii_digit()) {
+        sanitized.insert(0, '_');
     }
+    sanitized
 }
 
-impl api::Instrument<sdk::LabelSet> for IntMeasure {
-    /// record a single measure measurement value
-    fn record_one(&self, value: api::MeasurementValue, label_set: &sdk::LabelSet) {
-        self.0
-            .with(&convert_label_set(label_set))
-            .observe(value.into_i64() as f64)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{self, Counter, Gauge, Measure, Meter, MetricOptions};
+    use crate::sdk::metrics::{LabelSet, Selector};
+    use std::sync::atomic::{AtomicI64, Ordering};
+
+    fn scrape(exporter: &PrometheusExporter) -> String {
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&exporter.registry().gather(), &mut buffer)
+            .unwrap();
+        String::from_utf8(buffer).unwrap()
     }
-}
 
-impl api::InstrumentHandle for IntMeasureHandle {
-    /// record a single measure measurement value for precomputed labels
-    fn record_one(&self, value: api::MeasurementValue) {
-        self.0.observe(value.into_i64() as f64)
+    #[test]
+    fn exposes_latest_collection() {
+        let exporter = PrometheusExporter::new(Registry::new()).unwrap();
+        let controller = Controller::builder(exporter.clone()).build();
+        let meter = controller.meter("ex.com");
+        let labels = meter.labels(vec![api::Key::new("http.method").string("GET")]);
+
+        let requests = meter.new_i64_counter(
+            "requests",
+            MetricOptions::default().with_description("Handled requests"),
+        );
+        let queue = meter.new_f64_gauge("queue", MetricOptions::default());
+        requests.add(3, &labels);
+        queue.set(1.5, &labels);
+
+        assert_eq!(scrape(&exporter), "");
+        controller.collect();
+
+        let output = scrape(&exporter);
+        assert!(output.contains("# HELP ex_com_requests Handled requests\n"));
+        assert!(output.contains("# TYPE ex_com_requests counter\n"));
+        assert!(output.contains("ex_com_requests{http_method=\"GET\"} 3\n"));
+        assert!(output.contains("# HELP ex_com_queue ex_com_queue metric\n"));
+        assert!(output.contains("# TYPE ex_com_queue gauge\n"));
+        assert!(output.contains("ex_com_queue{http_method=\"GET\"} 1.5\n"));
     }
-}
 
-impl api::MeasureHandle<i64> for IntMeasureHandle {}
-
-/// Prometheus MeasureHandle
-#[derive(Clone)]
-#[allow(missing_debug_implementations)]
-pub struct MeasureHandle(prometheus::Histogram);
-
-impl api::Measure<f64, sdk::LabelSet> for prometheus::HistogramVec {
-    type Handle = MeasureHandle;
-
-    /// Creates a `Measurement` object to be used by a `Meter` when batch recording.
-    fn measurement(&self, value: f64) -> api::Measurement<sdk::LabelSet> {
-        api::Measurement::new(Arc::new(self.clone()), api::MeasurementValue::from(value))
+    #[test]
+    fn exposes_measures_as_histograms() {
+        let exporter = PrometheusExporter::new(Registry::new()).unwrap();
+        let controller = Controller::builder(exporter.clone())
+            .with_aggregator_selector(Selector::Histogram(vec![1.0, 5.0]))
+            .build();
+        let meter = controller.meter("test");
+        let latency = meter.new_f64_measure(
+            "latency",
+            MetricOptions::default().with_unit(api::Unit::new("ms")),
+        );
+        let labels = meter.labels(vec![]);
+        for value in &[0.5, 2.0, 10.0] {
+            latency.record(*value, &labels);
+        }
+        controller.collect();
+
+        let output = scrape(&exporter);
+        assert!(output.contains("# TYPE test_latency_ms histogram\n"));
+        assert!(output.contains("test_latency_ms_bucket{le=\"1\"} 1\n"));
+        assert!(output.contains("test_latency_ms_bucket{le=\"5\"} 2\n"));
+        assert!(output.contains("test_latency_ms_bucket{le=\"+Inf\"} 3\n"));
+        assert!(output.contains("test_latency_ms_sum 12.5\n"));
+        assert!(output.contains("test_latency_ms_count 3\n"));
     }
 
-    /// Creates a handle for this instrument.
-    fn acquire_handle(&self, labels: &sdk::LabelSet) -> Self::Handle {
-        MeasureHandle(self.with(&convert_label_set(labels)))
+    #[test]
+    fn evaluates_observers_on_gather() {
+        let exporter = PrometheusExporter::new(Registry::new()).unwrap();
+        let controller = Controller::builder(exporter.clone()).build();
+        exporter.collect_on_gather(&controller);
+        let meter = controller.meter("pool");
+        let labels = meter.labels(vec![api::Key::new("pool").string("db")]);
+
+        let connections = Arc::new(AtomicI64::new(3));
+        let observed = connections.clone();
+        let _connections =
+            meter.register_i64_observer("connections", MetricOptions::default(), move |result| {
+                result.observe(observed.load(Ordering::SeqCst), &labels)
+            });
+        let _opened = meter.register_i64_observer(
+            "opened",
+            MetricOptions::default().with_alternate(true),
+            |result| result.observe(12, &LabelSet::default()),
+        );
+
+        let output = scrape(&exporter);
+        assert!(output.contains("# TYPE pool_connections gauge\n"));
+        assert!(output.contains("pool_connections{pool=\"db\"} 3\n"));
+        assert!(output.contains("# TYPE pool_opened counter\n"));
+        assert!(output.contains("pool_opened 12\n"));
+
+        connections.store(5, Ordering::SeqCst);
+        assert!(scrape(&exporter).contains("pool_connections{pool=\"db\"} 5\n"));
     }
-}
 
-impl api::Instrument<sdk::LabelSet> for prometheus::HistogramVec {
-    /// record a single measure measurement value
-    fn record_one(&self, value: api::MeasurementValue, label_set: &sdk::LabelSet) {
-        self.with(&convert_label_set(label_set))
-            .observe(value.into_f64())
+    #[test]
+    fn skips_records_with_conflicting_types() {
+        let exporter = PrometheusExporter::new(Registry::new()).unwrap();
+        let controller = Controller::builder(exporter.clone()).build();
+        let meter = controller.meter("app");
+        let labels = meter.labels(vec![]);
+
+        let counter = meter.new_i64_counter("jobs.done", MetricOptions::default());
+        let gauge = meter.new_i64_gauge("jobs_done", MetricOptions::default());
+        counter.add(1, &labels);
+        gauge.set(2, &labels);
+        controller.collect();
+
+        let output = scrape(&exporter);
+        assert_eq!(output.matches("# TYPE app_jobs_done ").count(), 1);
+        assert_eq!(output.matches("\napp_jobs_done ").count(), 1);
+        assert_eq!(exporter.conflicting_record_count(), 1);
     }
-}
 
-impl api::InstrumentHandle for MeasureHandle {
-    /// record a single measure measurement value for precomputed labels
-    fn record_one(&self, value: api::MeasurementValue) {
-        self.0.observe(value.into_f64())
+    #[test]
+    fn one_exporter_per_registry() {
+        let registry = Registry::new();
+        assert!(PrometheusExporter::new(registry.clone()).is_ok());
+        assert!(PrometheusExporter::new(registry).is_err());
     }
 }
-
-impl api::MeasureHandle<f64> for MeasureHandle {}
@@ -0,0 +1,289 @@
+//! # Prometheus HTTP endpoint
+//!
+//! Serves a Prometheus `Registry` over HTTP so it can be scraped directly,
+//! without wiring up a server in every application. Each scrape gathers the
+//! registry, so exporters configured with `collect_on_gather` collect on
+//! every request.
+//!
+//! The [`Server`] answers `GET` requests on a single path (`/metrics` by
+//! default). Applications that already run an HTTP server can use
+//! [`response`] from a hyper service, or [`encode`] with any other framework.
+//!
+//! ```no_run
+//! use opentelemetry::exporter::metrics::prometheus::{server, PrometheusExporter, Registry};
+//! use opentelemetry::sdk;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let exporter = PrometheusExporter::new(Registry::new()).unwrap();
+//!     let controller = sdk::Controller::builder(exporter.clone()).build();
+//!     exporter.collect_on_gather(&controller);
+//!
+//!     let server = server::Server::builder(exporter.registry().clone())
+//!         .with_address(([127, 0, 0, 1], 9464).into())
+//!         .bind()
+//!         .expect("failed to bind metrics server");
+//!     server.await.expect("metrics server failed");
+//! }
+//! ```
+//!
+//! [`Server`]: struct.Server.html
+//! [`response`]: fn.response.html
+//! [`encode`]: fn.encode.html
+use super::{Encoder, Error, Registry, TextEncoder};
+use hyper::header::{HeaderValue, CONTENT_TYPE};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, StatusCode};
+use prometheus::{ProtobufEncoder, PROTOBUF_FORMAT, TEXT_FORMAT};
+use std::convert::Infallible;
+use std::fmt;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// Default address the server binds to, using the port reserved for
+/// OpenTelemetry Prometheus exporters.
+const DEFAULT_ADDRESS: ([u8; 4], u16) = ([0, 0, 0, 0], 9464);
+/// Default path metrics are served on.
+const DEFAULT_PATH: &str = "/metrics";
+
+/// The exposition format metrics are served in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// The Prometheus text format.
+    Text,
+    /// The delimited protocol buffer format.
+    Protobuf,
+}
+
+impl Format {
+    /// The `Content-Type` of responses in this format.
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Format::Text => TEXT_FORMAT,
+            Format::Protobuf => PROTOBUF_FORMAT,
+        }
+    }
+}
+
+/// Gathers the registry and encodes the metric families in the given format.
+pub fn encode(registry: &Registry, format: Format) -> Result<Vec<u8>, Error> {
+    let families = registry.gather();
+    let mut buffer = Vec::new();
+    match format {
+        Format::Text => TextEncoder::new().encode(&families, &mut buffer)?,
+        Format::Protobuf => ProtobufEncoder::new().encode(&families, &mut buffer)?,
+    }
+    Ok(buffer)
+}
+
+/// Builds the response to a scrape of the given registry. Encoding errors
+/// are reported as `500 Internal Server Error`.
+pub fn response(registry: &Registry, format: Format) -> Response<Body> {
+    match encode(registry, format) {
+        Ok(buffer) => {
+            let mut response = Response::new(Body::from(buffer));
+            response.headers_mut().insert(
+                CONTENT_TYPE,
+                HeaderValue::from_static(format.content_type()),
+            );
+            response
+        }
+        Err(err) => status_response(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    }
+}
+
+fn status_response(status: StatusCode, body: String) -> Response<Body> {
+    let mut response = Response::new(Body::from(body));
+    *response.status_mut() = status;
+    response
+}
+
+/// Routes a request received by the `Server`.
+fn route(registry: &Registry, format: Format, path: &str, req: &Request<Body>) -> Response<Body> {
+    if req.uri().path() != path {
+        status_response(StatusCode::NOT_FOUND, "Not Found".to_string())
+    } else if req.method() != Method::GET && req.method() != Method::HEAD {
+        status_response(
+            StatusCode::METHOD_NOT_ALLOWED,
+            "Method Not Allowed".to_string(),
+        )
+    } else {
+        response(registry, format)
+    }
+}
+
+/// Builder for a `Server`.
+pub struct Builder {
+    registry: Registry,
+    address: SocketAddr,
+    path: String,
+    format: Format,
+}
+
+impl fmt::Debug for Builder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Builder")
+            .field("address", &self.address)
+            .field("path", &self.path)
+            .field("format", &self.format)
+            .finish()
+    }
+}
+
+impl Builder {
+    /// Address to listen on, `0.0.0.0:9464` by default.
+    pub fn with_address(self, address: SocketAddr) -> Self {
+        Builder { address, ..self }
+    }
+
+    /// Path to serve metrics on, `/metrics` by default.
+    pub fn with_path<T: Into<String>>(self, path: T) -> Self {
+        Builder {
+            path: path.into(),
+            ..self
+        }
+    }
+
+    /// Format to serve metrics in, `Format::Text` by default.
+    pub fn with_format(self, format: Format) -> Self {
+        Builder { format, ..self }
+    }
+
+    /// Binds the configured address. The returned server only handles
+    /// requests while it is polled.
+    ///
+    /// This must be called from within a Tokio runtime.
+    pub fn bind(self) -> Result<Server, hyper::Error> {
+        let Builder {
+            registry,
+            address,
+            path,
+            format,
+        } = self;
+        let path: Arc<str> = path.into();
+
+        let make_service = make_service_fn(move |_conn| {
+            let registry = registry.clone();
+            let path = path.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |req| {
+                    let response = route(&registry, format, &path, &req);
+                    async move { Ok::<_, Infallible>(response) }
+                }))
+            }
+        });
+
+        let server = hyper::Server::try_bind(&address)?.serve(make_service);
+        Ok(Server {
+            local_addr: server.local_addr(),
+            server: Box::pin(server),
+        })
+    }
+}
+
+/// An HTTP server exposing a Prometheus `Registry`, running until an error
+/// occurs while it is awaited.
+pub struct Server {
+    local_addr: SocketAddr,
+    server: Pin<Box<dyn Future<Output = Result<(), hyper::Error>> + Send>>,
+}
+
+impl Server {
+    /// Create a new server builder serving the given registry.
+    pub fn builder(registry: Registry) -> Builder {
+        Builder {
+            registry,
+            address: DEFAULT_ADDRESS.into(),
+            path: DEFAULT_PATH.to_string(),
+            format: Format::Text,
+        }
+    }
+
+    /// The address the server is listening on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+}
+
+impl fmt::Debug for Server {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Server")
+            .field("local_addr", &self.local_addr)
+            .finish()
+    }
+}
+
+impl Future for Server {
+    type Output = Result<(), hyper::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.server.as_mut().poll(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{Counter, Meter, MetricOptions};
+    use crate::exporter::metrics::prometheus::PrometheusExporter;
+    use crate::sdk::metrics::Controller;
+    use hyper::Client;
+
+    fn exporter_with_requests() -> (PrometheusExporter, Controller) {
+        let exporter = PrometheusExporter::new(Registry::new()).unwrap();
+        let controller = Controller::builder(exporter.clone()).build();
+        exporter.collect_on_gather(&controller);
+        let meter = controller.meter("server");
+        meter
+            .new_i64_counter("requests", MetricOptions::default())
+            .add(2, &meter.labels(vec![]));
+        (exporter, controller)
+    }
+
+    #[test]
+    fn encodes_text_and_protobuf() {
+        let (exporter, _controller) = exporter_with_requests();
+
+        let text = encode(exporter.registry(), Format::Text).unwrap();
+        assert!(String::from_utf8(text)
+            .unwrap()
+            .contains("server_requests 2\n"));
+
+        let response = response(exporter.registry(), Format::Protobuf);
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[CONTENT_TYPE], PROTOBUF_FORMAT);
+    }
+
+    #[tokio::test]
+    async fn serves_registry_on_path() {
+        let (exporter, _controller) = exporter_with_requests();
+        let server = Server::builder(exporter.registry().clone())
+            .with_address(([127, 0, 0, 1], 0).into())
+            .with_path("/custom")
+            .bind()
+            .unwrap();
+        let address = server.local_addr();
+        tokio::spawn(server);
+
+        let client = Client::new();
+        let response = client
+            .get(format!("http://{}/custom", address).parse().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[CONTENT_TYPE], TEXT_FORMAT);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert!(String::from_utf8(body.to_vec())
+            .unwrap()
+            .contains("server_requests 2\n"));
+
+        let response = client
+            .get(format!("http://{}/metrics", address).parse().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
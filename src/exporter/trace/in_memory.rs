@@ -0,0 +1,237 @@
+//! # In-Memory Span Exporter
+//!
+//! The in-memory [`SpanExporter`] keeps exported spans in memory, so tests of
+//! instrumentation can assert on the spans that were produced.
+//!
+//! [`SpanExporter`]: ../trait.SpanExporter.html
+//!
+//! # Examples
+//!
+//! ```
+//! use opentelemetry::api::{Provider, Span, TraceContextExt, Tracer};
+//! use opentelemetry::exporter::trace::in_memory::InMemorySpanExporter;
+//! use opentelemetry::sdk;
+//!
+//! let exporter = InMemorySpanExporter::new();
+//! let provider = sdk::Provider::builder()
+//!     .with_simple_exporter(exporter.clone())
+//!     .build();
+//! let tracer = provider.get_tracer("my-component");
+//!
+//! tracer.in_span("parent", |cx| {
+//!     cx.span().add_event("started".to_string(), vec![]);
+//!     tracer.in_span("child", |_cx| {});
+//! });
+//!
+//! let tree = exporter.span_tree();
+//! assert_eq!(tree.len(), 1);
+//! assert_eq!(tree[0].span.name, "parent");
+//! assert!(tree[0].find("child").is_some());
+//! ```
+use crate::exporter::trace::{ExportResult, SpanData, SpanExporter};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// A [`SpanExporter`] storing exported spans in memory.
+///
+/// Clones share the same spans, so a clone can be passed to the provider
+/// while another is used to inspect the exported spans. After `shutdown`, the
+/// exporter rejects new spans with `ExportResult::FailedNotRetryable`.
+///
+/// [`SpanExporter`]: ../trait.SpanExporter.html
+#[derive(Clone, Debug, Default)]
+pub struct InMemorySpanExporter {
+    spans: Arc<Mutex<Vec<Arc<SpanData>>>>,
+    is_shutdown: Arc<AtomicBool>,
+}
+
+impl InMemorySpanExporter {
+    /// Create a new, empty exporter.
+    pub fn new() -> Self {
+        InMemorySpanExporter::default()
+    }
+
+    /// The spans exported so far, in the order they were exported.
+    pub fn get_finished_spans(&self) -> Vec<Arc<SpanData>> {
+        self.lock().clone()
+    }
+
+    /// Removes all exported spans.
+    pub fn reset(&self) {
+        self.lock().clear();
+    }
+
+    /// Whether the exporter was shut down.
+    pub fn is_shutdown(&self) -> bool {
+        self.is_shutdown.load(Ordering::SeqCst)
+    }
+
+    /// The first exported span with the given name.
+    pub fn find_by_name(&self, name: &str) -> Option<Arc<SpanData>> {
+        self.lock().iter().find(|span| span.name == name).cloned()
+    }
+
+    /// The exported spans whose parent is the given span.
+    pub fn children_of(&self, parent: &SpanData) -> Vec<Arc<SpanData>> {
+        self.lock()
+            .iter()
+            .filter(|span| is_child_of(span, parent))
+            .cloned()
+            .collect()
+    }
+
+    /// Reconstructs the parent/child trees of the exported spans.
+    ///
+    /// Spans without parent, or whose parent wasn't exported, are the roots
+    /// of the returned trees. Roots and children are ordered by start time.
+    pub fn span_tree(&self) -> Vec<SpanNode> {
+        let mut spans = self.get_finished_spans();
+        spans.sort_by_key(|span| span.start_time);
+
+        spans
+            .iter()
+            .filter(|span| !spans.iter().any(|parent| is_child_of(span, parent)))
+            .map(|root| SpanNode::build(root, &spans))
+            .collect()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Arc<SpanData>>> {
+        self.spans
+            .lock()
+            .expect("InMemorySpanExporter Mutex poisoned")
+    }
+}
+
+impl SpanExporter for InMemorySpanExporter {
+    fn export(&self, batch: Vec<Arc<SpanData>>) -> ExportResult {
+        if self.is_shutdown() {
+            return ExportResult::FailedNotRetryable;
+        }
+
+        self.lock().extend(batch);
+        ExportResult::Success
+    }
+
+    fn shutdown(&self) {
+        self.is_shutdown.store(true, Ordering::SeqCst);
+    }
+}
+
+/// A span and its children, as reconstructed by
+/// `InMemorySpanExporter::span_tree`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpanNode {
+    /// The span.
+    pub span: Arc<SpanData>,
+    /// The children of the span, ordered by start time.
+    pub children: Vec<SpanNode>,
+}
+
+impl SpanNode {
+    fn build(span: &Arc<SpanData>, spans: &[Arc<SpanData>]) -> Self {
+        SpanNode {
+            span: span.clone(),
+            children: spans
+                .iter()
+                .filter(|child| is_child_of(child, span))
+                .map(|child| SpanNode::build(child, spans))
+                .collect(),
+        }
+    }
+
+    /// Finds the first node with the given name in this tree, searching
+    /// depth-first and including this node.
+    pub fn find(&self, name: &str) -> Option<&SpanNode> {
+        if self.span.name == name {
+            return Some(self);
+        }
+        self.children.iter().find_map(|child| child.find(name))
+    }
+
+    /// The names of the direct children of this node.
+    pub fn child_names(&self) -> Vec<&str> {
+        self.children
+            .iter()
+            .map(|child| child.span.name.as_str())
+            .collect()
+    }
+}
+
+fn is_child_of(span: &SpanData, parent: &SpanData) -> bool {
+    span.span_context.trace_id() == parent.span_context.trace_id()
+        && span.parent_span_id == parent.span_context.span_id()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{Provider, TraceContextExt, Tracer};
+    use crate::sdk;
+
+    fn provider(exporter: &InMemorySpanExporter) -> sdk::Provider {
+        sdk::Provider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build()
+    }
+
+    #[test]
+    fn collects_and_resets_spans() {
+        let exporter = InMemorySpanExporter::new();
+        let tracer = provider(&exporter).get_tracer("test");
+
+        tracer.in_span("first", |_cx| {});
+        tracer.in_span("second", |_cx| {});
+        let names = exporter
+            .get_finished_spans()
+            .iter()
+            .map(|span| span.name.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["first", "second"]);
+        assert!(exporter.find_by_name("second").is_some());
+        assert!(exporter.find_by_name("third").is_none());
+
+        exporter.reset();
+        assert!(exporter.get_finished_spans().is_empty());
+    }
+
+    #[test]
+    fn reconstructs_span_tree() {
+        let exporter = InMemorySpanExporter::new();
+        let tracer = provider(&exporter).get_tracer("test");
+
+        tracer.in_span("root", |_cx| {
+            tracer.in_span("a", |_cx| tracer.in_span("a.1", |_cx| {}));
+            tracer.in_span("b", |_cx| {});
+        });
+        tracer.in_span("other root", |_cx| {});
+
+        let tree = exporter.span_tree();
+        let roots = tree
+            .iter()
+            .map(|node| node.span.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(roots, vec!["root", "other root"]);
+        assert_eq!(tree[0].child_names(), vec!["a", "b"]);
+        assert_eq!(tree[0].find("a").unwrap().child_names(), vec!["a.1"]);
+
+        let root = exporter.find_by_name("root").unwrap();
+        assert_eq!(exporter.children_of(&root).len(), 2);
+    }
+
+    #[test]
+    fn rejects_spans_after_shutdown() {
+        let exporter = InMemorySpanExporter::new();
+        let provider = provider(&exporter);
+        provider.get_tracer("test").in_span("before", |cx| {
+            assert!(cx.span().span_context().is_sampled())
+        });
+
+        exporter.shutdown();
+        assert!(exporter.is_shutdown());
+        assert_eq!(
+            exporter.export(exporter.get_finished_spans()),
+            ExportResult::FailedNotRetryable
+        );
+        assert_eq!(exporter.get_finished_spans().len(), 1);
+    }
+}
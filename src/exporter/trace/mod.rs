@@ -5,18 +5,10 @@ use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use std::time::SystemTime;
 
+pub mod in_memory;
 pub mod stdout;
 
-/// Describes the result of an export.
-#[derive(Clone, Debug, PartialEq, Eq)]
-pub enum ExportResult {
-    /// Batch is successfully exported.
-    Success,
-    /// Batch export failed. Caller must not retry.
-    FailedNotRetryable,
-    /// Batch export failed transiently. Caller should record error and may retry.
-    FailedRetryable,
-}
+pub use crate::exporter::ExportResult;
 
 /// `SpanExporter` defines the interface that protocol-specific exporters must
 /// implement so that they can be plugged into OpenTelemetry SDK and support
@@ -54,6 +46,13 @@ pub trait SpanExporter: Send + Sync + std::fmt::Debug {
     /// data and the destination is unavailable). SDK authors can
     /// decide if they want to make the shutdown timeout to be configurable.
     fn shutdown(&self);
+
+    /// Sends any telemetry data the exporter buffers internally. Called by span
+    /// processors after exporting their own buffers when a flush is requested.
+    ///
+    /// Exporters that send each batch as part of `export` do not need to
+    /// implement this method.
+    fn force_flush(&self) {}
 }
 
 /// `SpanData` contains all the information collected by a `Span` and can be used
@@ -104,6 +103,7 @@ mod tests {
             api::SpanId::from_u64(span_id),
             trace_flags,
             remote,
+            api::TraceState::default(),
         );
 
         let parent_span_id = 1;
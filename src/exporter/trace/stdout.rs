@@ -21,16 +21,42 @@
 //!     .build();
 //! global::set_provider(provider);
 //! ```
+//!
+//! With [`Format::Json`] every span is written as a single line of JSON, for
+//! log shippers ingesting spans from stdout:
+//!
+//! ```
+//! use opentelemetry::exporter::trace::stdout;
+//!
+//! let exporter = stdout::Builder::default()
+//!     .with_format(stdout::Format::Json)
+//!     .init();
+//! ```
+//!
+//! [`Format::Json`]: enum.Format.html#variant.Json
+use crate::api;
 use crate::exporter::trace;
-use std::fmt::Debug;
+use std::fmt::{Debug, Write as _};
 use std::io::{self, stdout, Stdout, Write};
 use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The format spans are written in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// The `Debug` representation of the span, pretty printed if configured.
+    Debug,
+    /// One JSON object per line, with hex encoded trace and span ids and
+    /// RFC 3339 timestamps. The pretty print setting is ignored.
+    Json,
+}
 
 /// Builder
 #[derive(Debug)]
 pub struct Builder<W: Write + Debug> {
     writer: Mutex<W>,
     pretty_print: bool,
+    format: Format,
 }
 
 impl<W: Write + Debug> Builder<W> {
@@ -39,6 +65,7 @@ impl<W: Write + Debug> Builder<W> {
         Builder {
             writer: Mutex::new(writer),
             pretty_print: self.pretty_print,
+            format: self.format,
         }
     }
 
@@ -50,11 +77,17 @@ impl<W: Write + Debug> Builder<W> {
         }
     }
 
+    /// Specify the format spans are written in, `Format::Debug` by default
+    pub fn with_format(self, format: Format) -> Self {
+        Builder { format, ..self }
+    }
+
     /// Build a new exporter
     pub fn init(self) -> Exporter<W> {
         Exporter {
             writer: self.writer,
             pretty_print: self.pretty_print,
+            format: self.format,
         }
     }
 }
@@ -65,6 +98,7 @@ impl Default for Builder<Stdout> {
         Builder {
             writer: Mutex::new(stdout()),
             pretty_print: false,
+            format: Format::Debug,
         }
     }
 }
@@ -78,6 +112,7 @@ impl Default for Builder<Stdout> {
 pub struct Exporter<W: Write> {
     writer: Mutex<W>,
     pretty_print: bool,
+    format: Format,
 }
 
 impl<W> trace::SpanExporter for Exporter<W>
@@ -92,10 +127,12 @@ where
             .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()));
         let result = writer.and_then(|mut w| {
             for span in batch {
-                if self.pretty_print {
-                    w.write_all(format!("{:#?}\n", span).as_bytes())?;
-                } else {
-                    w.write_all(format!("{:?}\n", span).as_bytes())?;
+                match self.format {
+                    Format::Json => w.write_all(json_line(&span).as_bytes())?,
+                    Format::Debug if self.pretty_print => {
+                        w.write_all(format!("{:#?}\n", span).as_bytes())?
+                    }
+                    Format::Debug => w.write_all(format!("{:?}\n", span).as_bytes())?,
                 }
             }
 
@@ -113,3 +150,286 @@ where
     /// Ignored for now.
     fn shutdown(&self) {}
 }
+
+/// Encodes a span as a line of JSON.
+fn json_line(span: &trace::SpanData) -> String {
+    let mut line = String::new();
+    let _ = write!(
+        line,
+        "{{\"trace_id\":\"{:032x}\",\"span_id\":\"{:016x}\",\"parent_span_id\":\"{:016x}\"",
+        span.span_context.trace_id().to_u128(),
+        span.span_context.span_id().to_u64(),
+        span.parent_span_id.to_u64(),
+    );
+    line.push_str(",\"name\":");
+    write_json_string(&mut line, &span.name);
+    let kind = match span.span_kind {
+        api::SpanKind::Client => "client",
+        api::SpanKind::Server => "server",
+        api::SpanKind::Producer => "producer",
+        api::SpanKind::Consumer => "consumer",
+        api::SpanKind::Internal => "internal",
+    };
+    let _ = write!(
+        line,
+        ",\"kind\":\"{}\",\"start_time\":\"{}\",\"end_time\":\"{}\"",
+        kind,
+        rfc3339(span.start_time),
+        rfc3339(span.end_time),
+    );
+    line.push_str(",\"attributes\":");
+    write_json_attributes(&mut line, span.attributes.iter());
+
+    line.push_str(",\"events\":[");
+    for (i, event) in span.message_events.iter().enumerate() {
+        if i > 0 {
+            line.push(',');
+        }
+        line.push_str("{\"name\":");
+        write_json_string(&mut line, &event.name);
+        let _ = write!(
+            line,
+            ",\"timestamp\":\"{}\",\"attributes\":",
+            rfc3339(event.timestamp)
+        );
+        write_json_attributes(
+            &mut line,
+            event.attributes.iter().map(|kv| (&kv.key, &kv.value)),
+        );
+        line.push('}');
+    }
+
+    line.push_str("],\"links\":[");
+    for (i, link) in span.links.iter().enumerate() {
+        if i > 0 {
+            line.push(',');
+        }
+        let _ = write!(
+            line,
+            "{{\"trace_id\":\"{:032x}\",\"span_id\":\"{:016x}\",\"attributes\":",
+            link.span_context().trace_id().to_u128(),
+            link.span_context().span_id().to_u64(),
+        );
+        write_json_attributes(
+            &mut line,
+            link.attributes().iter().map(|kv| (&kv.key, &kv.value)),
+        );
+        line.push('}');
+    }
+
+    let status_code = match span.status_code {
+        api::StatusCode::OK => "OK",
+        api::StatusCode::Canceled => "Canceled",
+        api::StatusCode::Unknown => "Unknown",
+        api::StatusCode::InvalidArgument => "InvalidArgument",
+        api::StatusCode::DeadlineExceeded => "DeadlineExceeded",
+        api::StatusCode::NotFound => "NotFound",
+        api::StatusCode::AlreadyExists => "AlreadyExists",
+        api::StatusCode::PermissionDenied => "PermissionDenied",
+        api::StatusCode::ResourceExhausted => "ResourceExhausted",
+        api::StatusCode::FailedPrecondition => "FailedPrecondition",
+        api::StatusCode::Aborted => "Aborted",
+        api::StatusCode::OutOfRange => "OutOfRange",
+        api::StatusCode::Unimplemented => "Unimplemented",
+        api::StatusCode::Internal => "Internal",
+        api::StatusCode::Unavailable => "Unavailable",
+        api::StatusCode::DataLoss => "DataLoss",
+        api::StatusCode::Unauthenticated => "Unauthenticated",
+    };
+    let _ = write!(
+        line,
+        "],\"status_code\":\"{}\",\"status_message\":",
+        status_code
+    );
+    write_json_string(&mut line, &span.status_message);
+    line.push_str(",\"resource\":");
+    write_json_attributes(&mut line, span.resource.iter());
+    line.push_str("}\n");
+
+    line
+}
+
+fn write_json_attributes<'a, I>(out: &mut String, attributes: I)
+where
+    I: Iterator<Item = (&'a api::Key, &'a api::Value)>,
+{
+    out.push('{');
+    for (i, (key, value)) in attributes.enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        write_json_string(out, key.as_str());
+        out.push(':');
+        write_json_value(out, value);
+    }
+    out.push('}');
+}
+
+fn write_json_value(out: &mut String, value: &api::Value) {
+    match value {
+        api::Value::Bool(value) => {
+            let _ = write!(out, "{}", value);
+        }
+        api::Value::I64(value) => {
+            let _ = write!(out, "{}", value);
+        }
+        api::Value::U64(value) => {
+            let _ = write!(out, "{}", value);
+        }
+        // JSON has no representation for NaN and infinity
+        api::Value::F64(value) if !value.is_finite() => out.push_str("null"),
+        api::Value::F64(value) => {
+            let _ = write!(out, "{:?}", value);
+        }
+        api::Value::String(value) => write_json_string(out, value),
+        api::Value::Bytes(bytes) => {
+            out.push('[');
+            for (i, byte) in bytes.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                let _ = write!(out, "{}", byte);
+            }
+            out.push(']');
+        }
+        api::Value::Array(values) => {
+            out.push('[');
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_json_value(out, value);
+            }
+            out.push(']');
+        }
+    }
+}
+
+fn write_json_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Formats a time as an RFC 3339 UTC timestamp with nanosecond precision.
+fn rfc3339(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs();
+    let (days, secs_of_day) = (secs / 86_400, secs % 86_400);
+
+    // Converts days since the epoch to a civil date, see
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:09}Z",
+        year,
+        month,
+        day,
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60,
+        since_epoch.subsec_nanos(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sdk;
+    use std::time::Duration;
+
+    #[test]
+    fn formats_rfc3339() {
+        assert_eq!(rfc3339(UNIX_EPOCH), "1970-01-01T00:00:00.000000000Z");
+        assert_eq!(
+            rfc3339(UNIX_EPOCH + Duration::new(951_827_696, 5)),
+            "2000-02-29T12:34:56.000000005Z"
+        );
+        assert_eq!(
+            rfc3339(UNIX_EPOCH + Duration::from_secs(4_107_542_399)),
+            "2100-02-28T23:59:59.000000000Z"
+        );
+    }
+
+    #[test]
+    fn writes_json_lines() {
+        let mut attributes = sdk::EvictedHashMap::new(10);
+        attributes.insert(api::Key::new("http.method").string("GET"));
+        let mut message_events = sdk::EvictedQueue::new(10);
+        message_events.append_vec(&mut vec![api::Event::new(
+            "retry \"1\"\n".to_string(),
+            UNIX_EPOCH + Duration::from_millis(1500),
+            vec![api::Key::new("attempt").i64(1)],
+        )]);
+        let span = Arc::new(trace::SpanData {
+            span_context: api::SpanContext::new(
+                api::TraceId::from_u128(0x0af7_6519_16cd_43dd_8448_eb21_1c80_319c),
+                api::SpanId::from_u64(0xb7ad_6b71_6920_3331),
+                api::TRACE_FLAG_SAMPLED,
+                false,
+                api::TraceState::default(),
+            ),
+            parent_span_id: api::SpanId::invalid(),
+            span_kind: api::SpanKind::Server,
+            name: "GET /".to_string(),
+            start_time: UNIX_EPOCH + Duration::from_secs(1),
+            end_time: UNIX_EPOCH + Duration::from_secs(2),
+            attributes,
+            message_events,
+            links: sdk::EvictedQueue::new(10),
+            status_code: api::StatusCode::DeadlineExceeded,
+            status_message: String::new(),
+            resource: Arc::new(sdk::Resource::new(vec![
+                api::Key::new("service.name").string("test")
+            ])),
+        });
+        let exporter = Builder::default()
+            .with_writer(Vec::new())
+            .with_format(Format::Json)
+            .init();
+
+        trace::SpanExporter::export(&exporter, vec![span.clone(), span]);
+        let output = String::from_utf8(exporter.writer.into_inner().unwrap()).unwrap();
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 2);
+
+        let json: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(json["trace_id"], "0af7651916cd43dd8448eb211c80319c");
+        assert_eq!(json["span_id"], "b7ad6b7169203331");
+        assert_eq!(json["parent_span_id"], "0000000000000000");
+        assert_eq!(json["kind"], "server");
+        assert_eq!(json["start_time"], "1970-01-01T00:00:01.000000000Z");
+        assert_eq!(json["attributes"]["http.method"], "GET");
+        assert_eq!(json["events"][0]["name"], "retry \"1\"\n");
+        assert_eq!(
+            json["events"][0]["timestamp"],
+            "1970-01-01T00:00:01.500000000Z"
+        );
+        assert_eq!(json["events"][0]["attributes"]["attempt"], 1);
+        assert_eq!(json["links"], serde_json::json!([]));
+        assert_eq!(json["status_code"], "DeadlineExceeded");
+        assert_eq!(json["resource"]["service.name"], "test");
+    }
+}
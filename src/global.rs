@@ -66,11 +66,42 @@
 //! [`BoxedSpan`]: struct.BoxedSpan.html
 //! [`trace_provider`]: fn.trace_provider.html
 //! [trait objects]: https://doc.rust-lang.org/reference/types/trait-object.html#trait-objects
+//!
+//! ## Metrics
+//!
+//! Metrics work the same way: a [`MeterProvider`] is installed with
+//! [`set_meter_provider`], and [`meter`] returns a [`BoxedMeter`] whose
+//! instruments record through the configured provider.
+//!
+//! ```rust
+//! use opentelemetry::api::{Counter, Meter, MetricOptions};
+//! use opentelemetry::global;
+//!
+//! // Configure the global `MeterProvider` singleton when your app starts
+//! global::set_meter_provider(opentelemetry::api::NoopMeterProvider {});
+//!
+//! let meter = global::meter("my-component");
+//! let requests = meter.new_i64_counter("requests", MetricOptions::default());
+//! requests.add(1, &meter.labels(vec![]));
+//! ```
+//!
+//! [`MeterProvider`]: ../api/metrics/provider/trait.MeterProvider.html
+//! [`set_meter_provider`]: fn.set_meter_provider.html
+//! [`meter`]: fn.meter.html
+//! [`BoxedMeter`]: struct.BoxedMeter.html
 use crate::{api, api::Provider};
 use std::fmt;
 use std::sync::{Arc, RwLock};
 use std::time::SystemTime;
 
+mod metrics;
+
+pub use metrics::{
+    meter, meter_provider, set_meter_provider, BoxedCounter, BoxedGauge, BoxedHandle,
+    BoxedLabelSet, BoxedMeasure, BoxedMeter, BoxedObserver, BoxedObserverCallback,
+    GenericInstrument, GenericMeter, GenericMeterProvider, GlobalMeterProvider,
+};
+
 /// Wraps the [`BoxedTracer`]'s [`Span`] so it can be used generically by
 /// applications without knowing the underlying type.
 ///
@@ -232,6 +263,9 @@ where
 pub trait GenericProvider: fmt::Debug + 'static {
     /// Creates a named tracer instance that is a trait object through the underlying `Provider`.
     fn get_tracer_boxed(&self, name: &'static str) -> Box<dyn GenericTracer + Send + Sync>;
+
+    /// Flushes spans buffered by the underlying `Provider`.
+    fn force_flush_generic(&self);
 }
 
 impl<S, T, P> GenericProvider for P
@@ -244,6 +278,11 @@ where
     fn get_tracer_boxed(&self, name: &'static str) -> Box<dyn GenericTracer + Send + Sync> {
         Box::new(self.get_tracer(name))
     }
+
+    /// Flush the underlying provider
+    fn force_flush_generic(&self) {
+        self.force_flush()
+    }
 }
 
 /// Represents the globally configured [`Provider`] instance for this
@@ -278,6 +317,11 @@ impl api::Provider for GlobalProvider {
     fn get_tracer(&self, name: &'static str) -> Self::Tracer {
         BoxedTracer(self.provider.get_tracer_boxed(name))
     }
+
+    /// Flush spans buffered by the global provider.
+    fn force_flush(&self) {
+        self.provider.force_flush_generic()
+    }
 }
 
 lazy_static::lazy_static! {
@@ -313,6 +357,16 @@ pub fn tracer(name: &'static str) -> BoxedTracer {
     trace_provider().get_tracer(name)
 }
 
+/// Exports all spans that have ended but are still buffered by the global
+/// [`Provider`], blocking until the export completes or times out.
+///
+/// Useful before a process exits or a serverless invocation is frozen.
+///
+/// [`Provider`]: ../api/trace/provider/trait.Provider.html
+pub fn force_flush() {
+    trace_provider().force_flush()
+}
+
 /// Sets the given [`Provider`] instance as the current global provider.
 ///
 /// [`Provider`]: ../api/trace/provider/trait.Provider.html
@@ -378,9 +432,10 @@ where
         .unwrap_or_else(|_| f(&*DEFAULT_HTTP_TEXT_PROPAGATOR as &dyn api::HttpTextFormat))
 }
 
-/// Returns [`NoopMeter`] for now
+/// Returns a [`NoopMeter`].
 ///
-/// [`NoopMeter`]: ../api/trace/noop/struct.NoopMeter.html
+/// [`NoopMeter`]: ../api/metrics/noop/struct.NoopMeter.html
+#[deprecated(note = "use `global::meter` to create meters from the global meter provider")]
 pub fn global_meter() -> crate::api::NoopMeter {
     crate::api::NoopMeter {}
 }
@@ -0,0 +1,810 @@
+//! # Global Meter Provider
+//!
+//! Type-erased counterparts of the metrics API, allowing library code to
+//! record metrics through the globally configured [`MeterProvider`] without
+//! knowing its concrete type.
+//!
+//! Label sets are boxed by the [`BoxedMeter`] that created them. Instruments
+//! silently ignore label sets that were created by a meter of a different
+//! type, e.g. labels created before the global provider was replaced.
+//!
+//! [`MeterProvider`]: ../api/metrics/provider/trait.MeterProvider.html
+//! [`BoxedMeter`]: struct.BoxedMeter.html
+use crate::api;
+use std::any::Any;
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::{Arc, RwLock};
+
+/// A label set created by a [`BoxedMeter`], wrapping the label set of the
+/// underlying meter.
+///
+/// [`BoxedMeter`]: struct.BoxedMeter.html
+pub struct BoxedLabelSet(Box<dyn Any + Send + Sync>);
+
+impl BoxedLabelSet {
+    fn downcast<LS: 'static>(&self) -> Option<&LS> {
+        self.0.downcast_ref()
+    }
+}
+
+impl fmt::Debug for BoxedLabelSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BoxedLabelSet").finish()
+    }
+}
+
+impl api::LabelSet for BoxedLabelSet {}
+
+type DynHandle = dyn api::InstrumentHandle + Send + Sync;
+type DynObserver = dyn api::Observer + Send + Sync;
+
+/// Callback of an observer registered through a [`BoxedMeter`].
+///
+/// [`BoxedMeter`]: struct.BoxedMeter.html
+pub type BoxedObserverCallback<T> =
+    Box<dyn Fn(&dyn api::ObserverResult<T, BoxedLabelSet>) + Send + Sync>;
+
+/// Allows a specific instrument to be used generically by the boxed
+/// instruments of a [`BoxedMeter`].
+///
+/// [`BoxedMeter`]: struct.BoxedMeter.html
+pub trait GenericInstrument<T>: fmt::Debug + Send + Sync {
+    /// Records a value, ignoring labels created by a different kind of meter.
+    fn record_boxed(&self, value: api::MeasurementValue, labels: &BoxedLabelSet);
+
+    /// Creates a handle for the given labels, or `None` if they were created
+    /// by a different kind of meter.
+    fn acquire_handle_boxed(&self, labels: &BoxedLabelSet) -> Option<Box<DynHandle>>;
+}
+
+#[derive(Debug)]
+struct CounterInstrument<C, LS>(C, PhantomData<fn() -> LS>);
+
+impl<T, C, LS> GenericInstrument<T> for CounterInstrument<C, LS>
+where
+    T: Into<api::MeasurementValue>,
+    C: api::Counter<T, LS> + fmt::Debug + Send + Sync,
+    C::Handle: Send + Sync + 'static,
+    LS: api::LabelSet + fmt::Debug + 'static,
+{
+    fn record_boxed(&self, value: api::MeasurementValue, labels: &BoxedLabelSet) {
+        if let Some(labels) = labels.downcast() {
+            self.0.record_one(value, labels)
+        }
+    }
+
+    fn acquire_handle_boxed(&self, labels: &BoxedLabelSet) -> Option<Box<DynHandle>> {
+        labels
+            .downcast()
+            .map(|labels| Box::new(self.0.acquire_handle(labels)) as Box<DynHandle>)
+    }
+}
+
+#[derive(Debug)]
+struct GaugeInstrument<G, LS>(G, PhantomData<fn() -> LS>);
+
+impl<T, G, LS> GenericInstrument<T> for GaugeInstrument<G, LS>
+where
+    T: Into<api::MeasurementValue>,
+    G: api::Gauge<T, LS> + fmt::Debug + Send + Sync,
+    G::Handle: Send + Sync + 'static,
+    LS: api::LabelSet + fmt::Debug + 'static,
+{
+    fn record_boxed(&self, value: api::MeasurementValue, labels: &BoxedLabelSet) {
+        if let Some(labels) = labels.downcast() {
+            self.0.record_one(value, labels)
+        }
+    }
+
+    fn acquire_handle_boxed(&self, labels: &BoxedLabelSet) -> Option<Box<DynHandle>> {
+        labels
+            .downcast()
+            .map(|labels| Box::new(self.0.acquire_handle(labels)) as Box<DynHandle>)
+    }
+}
+
+#[derive(Debug)]
+struct MeasureInstrument<M, LS>(M, PhantomData<fn() -> LS>);
+
+impl<T, M, LS> GenericInstrument<T> for MeasureInstrument<M, LS>
+where
+    T: Into<api::MeasurementValue>,
+    M: api::Measure<T, LS> + fmt::Debug + Send + Sync,
+    M::Handle: Send + Sync + 'static,
+    LS: api::LabelSet + fmt::Debug + 'static,
+{
+    fn record_boxed(&self, value: api::MeasurementValue, labels: &BoxedLabelSet) {
+        if let Some(labels) = labels.downcast() {
+            self.0.record_one(value, labels)
+        }
+    }
+
+    fn acquire_handle_boxed(&self, labels: &BoxedLabelSet) -> Option<Box<DynHandle>> {
+        labels
+            .downcast()
+            .map(|labels| Box::new(self.0.acquire_handle(labels)) as Box<DynHandle>)
+    }
+}
+
+/// Passes observations made with boxed label sets on to the underlying
+/// meter's `ObserverResult`.
+struct ObserverResultAdapter<'a, T, LS>(&'a dyn api::ObserverResult<T, LS>);
+
+impl<'a, T, LS> api::ObserverResult<T, BoxedLabelSet> for ObserverResultAdapter<'a, T, LS>
+where
+    T: Into<api::MeasurementValue>,
+    LS: api::LabelSet + 'static,
+{
+    fn observe(&self, value: T, label_set: &BoxedLabelSet) {
+        if let Some(labels) = label_set.downcast() {
+            self.0.observe(value, labels)
+        }
+    }
+}
+
+/// Allows a specific [`Meter`] to be used generically by [`BoxedMeter`]
+/// instances by mirroring the interface and boxing the return types.
+///
+/// [`Meter`]: ../api/metrics/trait.Meter.html
+/// [`BoxedMeter`]: struct.BoxedMeter.html
+pub trait GenericMeter: fmt::Debug + Send + Sync + 'static {
+    /// Returns a boxed label set of the underlying meter.
+    fn labels_boxed(&self, key_values: Vec<api::KeyValue>) -> BoxedLabelSet;
+
+    /// Creates a new `i64` counter as a trait object.
+    fn new_i64_counter_boxed(
+        &self,
+        name: String,
+        opts: api::MetricOptions,
+    ) -> Arc<dyn GenericInstrument<i64>>;
+
+    /// Creates a new `f64` counter as a trait object.
+    fn new_f64_counter_boxed(
+        &self,
+        name: String,
+        opts: api::MetricOptions,
+    ) -> Arc<dyn GenericInstrument<f64>>;
+
+    /// Creates a new `i64` gauge as a trait object.
+    fn new_i64_gauge_boxed(
+        &self,
+        name: String,
+        opts: api::MetricOptions,
+    ) -> Arc<dyn GenericInstrument<i64>>;
+
+    /// Creates a new `f64` gauge as a trait object.
+    fn new_f64_gauge_boxed(
+        &self,
+        name: String,
+        opts: api::MetricOptions,
+    ) -> Arc<dyn GenericInstrument<f64>>;
+
+    /// Creates a new `i64` measure as a trait object.
+    fn new_i64_measure_boxed(
+        &self,
+        name: String,
+        opts: api::MetricOptions,
+    ) -> Arc<dyn GenericInstrument<i64>>;
+
+    /// Creates a new `f64` measure as a trait object.
+    fn new_f64_measure_boxed(
+        &self,
+        name: String,
+        opts: api::MetricOptions,
+    ) -> Arc<dyn GenericInstrument<f64>>;
+
+    /// Registers a new `i64` observer, returning it as a trait object.
+    fn register_i64_observer_boxed(
+        &self,
+        name: String,
+        opts: api::MetricOptions,
+        callback: BoxedObserverCallback<i64>,
+    ) -> Box<DynObserver>;
+
+    /// Registers a new `f64` observer, returning it as a trait object.
+    fn register_f64_observer_boxed(
+        &self,
+        name: String,
+        opts: api::MetricOptions,
+        callback: BoxedObserverCallback<f64>,
+    ) -> Box<DynObserver>;
+}
+
+impl<M> GenericMeter for M
+where
+    M: api::Meter + fmt::Debug + Send + Sync + 'static,
+    M::LabelSet: fmt::Debug + Send + Sync + 'static,
+    M::I64Counter: fmt::Debug + Send + Sync + 'static,
+    <M::I64Counter as api::Counter<i64, M::LabelSet>>::Handle: Send + Sync + 'static,
+    M::F64Counter: fmt::Debug + Send + Sync + 'static,
+    <M::F64Counter as api::Counter<f64, M::LabelSet>>::Handle: Send + Sync + 'static,
+    M::I64Gauge: fmt::Debug + Send + Sync + 'static,
+    <M::I64Gauge as api::Gauge<i64, M::LabelSet>>::Handle: Send + Sync + 'static,
+    M::F64Gauge: fmt::Debug + Send + Sync + 'static,
+    <M::F64Gauge as api::Gauge<f64, M::LabelSet>>::Handle: Send + Sync + 'static,
+    M::I64Measure: fmt::Debug + Send + Sync + 'static,
+    <M::I64Measure as api::Measure<i64, M::LabelSet>>::Handle: Send + Sync + 'static,
+    M::F64Measure: fmt::Debug + Send + Sync + 'static,
+    <M::F64Measure as api::Measure<f64, M::LabelSet>>::Handle: Send + Sync + 'static,
+    M::I64Observer: Send + Sync + 'static,
+    M::F64Observer: Send + Sync + 'static,
+{
+    /// Return labels of the underlying meter as a trait object.
+    fn labels_boxed(&self, key_values: Vec<api::KeyValue>) -> BoxedLabelSet {
+        BoxedLabelSet(Box::new(self.labels(key_values)))
+    }
+
+    /// Return a boxed generic `i64` counter.
+    fn new_i64_counter_boxed(
+        &self,
+        name: String,
+        opts: api::MetricOptions,
+    ) -> Arc<dyn GenericInstrument<i64>> {
+        Arc::new(CounterInstrument(
+            self.new_i64_counter(name, opts),
+            PhantomData,
+        ))
+    }
+
+    /// Return a boxed generic `f64` counter.
+    fn new_f64_counter_boxed(
+        &self,
+        name: String,
+        opts: api::MetricOptions,
+    ) -> Arc<dyn GenericInstrument<f64>> {
+        Arc::new(CounterInstrument(
+            self.new_f64_counter(name, opts),
+            PhantomData,
+        ))
+    }
+
+    /// Return a boxed generic `i64` gauge.
+    fn new_i64_gauge_boxed(
+        &self,
+        name: String,
+        opts: api::MetricOptions,
+    ) -> Arc<dyn GenericInstrument<i64>> {
+        Arc::new(GaugeInstrument(self.new_i64_gauge(name, opts), PhantomData))
+    }
+
+    /// Return a boxed generic `f64` gauge.
+    fn new_f64_gauge_boxed(
+        &self,
+        name: String,
+        opts: api::MetricOptions,
+    ) -> Arc<dyn GenericInstrument<f64>> {
+        Arc::new(GaugeInstrument(self.new_f64_gauge(name, opts), PhantomData))
+    }
+
+    /// Return a boxed generic `i64` measure.
+    fn new_i64_measure_boxed(
+        &self,
+        name: String,
+        opts: api::MetricOptions,
+    ) -> Arc<dyn GenericInstrument<i64>> {
+        Arc::new(MeasureInstrument(
+            self.new_i64_measure(name, opts),
+            PhantomData,
+        ))
+    }
+
+    /// Return a boxed generic `f64` measure.
+    fn new_f64_measure_boxed(
+        &self,
+        name: String,
+        opts: api::MetricOptions,
+    ) -> Arc<dyn GenericInstrument<f64>> {
+        Arc::new(MeasureInstrument(
+            self.new_f64_measure(name, opts),
+            PhantomData,
+        ))
+    }
+
+    /// Register an `i64` observer, adapting its callback to the underlying
+    /// meter's label sets.
+    fn register_i64_observer_boxed(
+        &self,
+        name: String,
+        opts: api::MetricOptions,
+        callback: BoxedObserverCallback<i64>,
+    ) -> Box<DynObserver> {
+        Box::new(self.register_i64_observer(name, opts, move |result| {
+            callback(&ObserverResultAdapter(result))
+        }))
+    }
+
+    /// Register an `f64` observer, adapting its callback to the underlying
+    /// meter's label sets.
+    fn register_f64_observer_boxed(
+        &self,
+        name: String,
+        opts: api::MetricOptions,
+        callback: BoxedObserverCallback<f64>,
+    ) -> Box<DynObserver> {
+        Box::new(self.register_f64_observer(name, opts, move |result| {
+            callback(&ObserverResultAdapter(result))
+        }))
+    }
+}
+
+/// A handle of a boxed instrument. Handles acquired with labels of a
+/// different kind of meter ignore all values.
+pub struct BoxedHandle<T>(Option<Arc<DynHandle>>, PhantomData<T>);
+
+impl<T> Clone for BoxedHandle<T> {
+    fn clone(&self) -> Self {
+        BoxedHandle(self.0.clone(), PhantomData)
+    }
+}
+
+impl<T> fmt::Debug for BoxedHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BoxedHandle").finish()
+    }
+}
+
+impl<T> api::InstrumentHandle for BoxedHandle<T> {
+    /// Record a single value through the underlying handle.
+    fn record_one(&self, value: api::MeasurementValue) {
+        if let Some(handle) = &self.0 {
+            handle.record_one(value)
+        }
+    }
+}
+
+impl<T: Into<api::MeasurementValue>> api::CounterHandle<T> for BoxedHandle<T> {}
+impl<T: Into<api::MeasurementValue>> api::GaugeHandle<T> for BoxedHandle<T> {}
+impl<T: Into<api::MeasurementValue>> api::MeasureHandle<T> for BoxedHandle<T> {}
+
+/// Wraps a [`BoxedMeter`]'s counter so it can be used generically.
+///
+/// [`BoxedMeter`]: struct.BoxedMeter.html
+#[derive(Debug)]
+pub struct BoxedCounter<T>(Arc<dyn GenericInstrument<T>>);
+
+impl<T> Clone for BoxedCounter<T> {
+    fn clone(&self) -> Self {
+        BoxedCounter(self.0.clone())
+    }
+}
+
+impl<T> api::Instrument<BoxedLabelSet> for BoxedCounter<T> {
+    /// Record a single counter measurement value.
+    fn record_one(&self, value: api::MeasurementValue, label_set: &BoxedLabelSet) {
+        self.0.record_boxed(value, label_set)
+    }
+}
+
+impl<T: Into<api::MeasurementValue> + 'static> api::Counter<T, BoxedLabelSet> for BoxedCounter<T> {
+    type Handle = BoxedHandle<T>;
+
+    /// Creates a `Measurement` object to be used by a `Meter` when batch recording.
+    fn measurement(&self, value: T) -> api::Measurement<BoxedLabelSet> {
+        api::Measurement::new(Arc::new(self.clone()), value.into())
+    }
+
+    /// Creates a handle for this instrument.
+    fn acquire_handle(&self, labels: &BoxedLabelSet) -> Self::Handle {
+        BoxedHandle(
+            self.0.acquire_handle_boxed(labels).map(Arc::from),
+            PhantomData,
+        )
+    }
+}
+
+/// Wraps a [`BoxedMeter`]'s gauge so it can be used generically.
+///
+/// [`BoxedMeter`]: struct.BoxedMeter.html
+#[derive(Debug)]
+pub struct BoxedGauge<T>(Arc<dyn GenericInstrument<T>>);
+
+impl<T> Clone for BoxedGauge<T> {
+    fn clone(&self) -> Self {
+        BoxedGauge(self.0.clone())
+    }
+}
+
+impl<T> api::Instrument<BoxedLabelSet> for BoxedGauge<T> {
+    /// Record a single gauge measurement value.
+    fn record_one(&self, value: api::MeasurementValue, label_set: &BoxedLabelSet) {
+        self.0.record_boxed(value, label_set)
+    }
+}
+
+impl<T: Into<api::MeasurementValue> + 'static> api::Gauge<T, BoxedLabelSet> for BoxedGauge<T> {
+    type Handle = BoxedHandle<T>;
+
+    /// Creates a `Measurement` object to be used by a `Meter` when batch recording.
+    fn measurement(&self, value: T) -> api::Measurement<BoxedLabelSet> {
+        api::Measurement::new(Arc::new(self.clone()), value.into())
+    }
+
+    /// Creates a handle for this instrument.
+    fn acquire_handle(&self, labels: &BoxedLabelSet) -> Self::Handle {
+        BoxedHandle(
+            self.0.acquire_handle_boxed(labels).map(Arc::from),
+            PhantomData,
+        )
+    }
+}
+
+/// Wraps a [`BoxedMeter`]'s measure so it can be used generically.
+///
+/// [`BoxedMeter`]: struct.BoxedMeter.html
+#[derive(Debug)]
+pub struct BoxedMeasure<T>(Arc<dyn GenericInstrument<T>>);
+
+impl<T> Clone for BoxedMeasure<T> {
+    fn clone(&self) -> Self {
+        BoxedMeasure(self.0.clone())
+    }
+}
+
+impl<T> api::Instrument<BoxedLabelSet> for BoxedMeasure<T> {
+    /// Record a single measure measurement value.
+    fn record_one(&self, value: api::MeasurementValue, label_set: &BoxedLabelSet) {
+        self.0.record_boxed(value, label_set)
+    }
+}
+
+impl<T: Into<api::MeasurementValue> + 'static> api::Measure<T, BoxedLabelSet> for BoxedMeasure<T> {
+    type Handle = BoxedHandle<T>;
+
+    /// Creates a `Measurement` object to be used by a `Meter` when batch recording.
+    fn measurement(&self, value: T) -> api::Measurement<BoxedLabelSet> {
+        api::Measurement::new(Arc::new(self.clone()), value.into())
+    }
+
+    /// Creates a handle for this instrument.
+    fn acquire_handle(&self, labels: &BoxedLabelSet) -> Self::Handle {
+        BoxedHandle(
+            self.0.acquire_handle_boxed(labels).map(Arc::from),
+            PhantomData,
+        )
+    }
+}
+
+/// Wraps a [`BoxedMeter`]'s observer so it can be used generically.
+///
+/// [`BoxedMeter`]: struct.BoxedMeter.html
+pub struct BoxedObserver(Box<DynObserver>);
+
+impl fmt::Debug for BoxedObserver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BoxedObserver").finish()
+    }
+}
+
+impl api::Observer for BoxedObserver {
+    /// Unregisters the underlying observer.
+    fn unregister(&self) {
+        self.0.unregister()
+    }
+}
+
+/// Wraps the [`GlobalMeterProvider`]'s [`Meter`] so it can be used
+/// generically by applications without knowing the underlying type.
+///
+/// [`GlobalMeterProvider`]: struct.GlobalMeterProvider.html
+/// [`Meter`]: ../api/metrics/trait.Meter.html
+#[derive(Clone, Debug)]
+pub struct BoxedMeter(Arc<dyn GenericMeter>);
+
+impl api::Meter for BoxedMeter {
+    type LabelSet = BoxedLabelSet;
+    type I64Counter = BoxedCounter<i64>;
+    type F64Counter = BoxedCounter<f64>;
+    type I64Gauge = BoxedGauge<i64>;
+    type F64Gauge = BoxedGauge<f64>;
+    type I64Measure = BoxedMeasure<i64>;
+    type F64Measure = BoxedMeasure<f64>;
+    type I64Observer = BoxedObserver;
+    type F64Observer = BoxedObserver;
+
+    /// Builds a label set of the underlying meter.
+    fn labels(&self, key_values: Vec<api::KeyValue>) -> Self::LabelSet {
+        self.0.labels_boxed(key_values)
+    }
+
+    /// Creates a new `i64` counter with a given name and customized with passed options.
+    fn new_i64_counter<S: Into<String>>(
+        &self,
+        name: S,
+        opts: api::MetricOptions,
+    ) -> Self::I64Counter {
+        BoxedCounter(self.0.new_i64_counter_boxed(name.into(), opts))
+    }
+
+    /// Creates a new `f64` counter with a given name and customized with passed options.
+    fn new_f64_counter<S: Into<String>>(
+        &self,
+        name: S,
+        opts: api::MetricOptions,
+    ) -> Self::F64Counter {
+        BoxedCounter(self.0.new_f64_counter_boxed(name.into(), opts))
+    }
+
+    /// Creates a new `i64` gauge with a given name and customized with passed options.
+    fn new_i64_gauge<S: Into<String>>(&self, name: S, opts: api::MetricOptions) -> Self::I64Gauge {
+        BoxedGauge(self.0.new_i64_gauge_boxed(name.into(), opts))
+    }
+
+    /// Creates a new `f64` gauge with a given name and customized with passed options.
+    fn new_f64_gauge<S: Into<String>>(&self, name: S, opts: api::MetricOptions) -> Self::F64Gauge {
+        BoxedGauge(self.0.new_f64_gauge_boxed(name.into(), opts))
+    }
+
+    /// Creates a new `i64` measure with a given name and customized with passed options.
+    fn new_i64_measure<S: Into<String>>(
+        &self,
+        name: S,
+        opts: api::MetricOptions,
+    ) -> Self::I64Measure {
+        BoxedMeasure(self.0.new_i64_measure_boxed(name.into(), opts))
+    }
+
+    /// Creates a new `f64` measure with a given name and customized with passed options.
+    fn new_f64_measure<S: Into<String>>(
+        &self,
+        name: S,
+        opts: api::MetricOptions,
+    ) -> Self::F64Measure {
+        BoxedMeasure(self.0.new_f64_measure_boxed(name.into(), opts))
+    }
+
+    /// Registers a new `i64` observer with a given name and customized with passed options.
+    fn register_i64_observer<S, F>(
+        &self,
+        name: S,
+        opts: api::MetricOptions,
+        callback: F,
+    ) -> Self::I64Observer
+    where
+        S: Into<String>,
+        F: Fn(&dyn api::ObserverResult<i64, Self::LabelSet>) + Send + Sync + 'static,
+    {
+        BoxedObserver(
+            self.0
+                .register_i64_observer_boxed(name.into(), opts, Box::new(callback)),
+        )
+    }
+
+    /// Registers a new `f64` observer with a given name and customized with passed options.
+    fn register_f64_observer<S, F>(
+        &self,
+        name: S,
+        opts: api::MetricOptions,
+        callback: F,
+    ) -> Self::F64Observer
+    where
+        S: Into<String>,
+        F: Fn(&dyn api::ObserverResult<f64, Self::LabelSet>) + Send + Sync + 'static,
+    {
+        BoxedObserver(
+            self.0
+                .register_f64_observer_boxed(name.into(), opts, Box::new(callback)),
+        )
+    }
+
+    /// Records a batch of measurements. Unlike with most meters, the
+    /// measurements are recorded one at a time.
+    fn record_batch<M: IntoIterator<Item = api::Measurement<Self::LabelSet>>>(
+        &self,
+        label_set: &Self::LabelSet,
+        measurements: M,
+    ) {
+        for measure in measurements.into_iter() {
+            let instrument = measure.instrument();
+            instrument.record_one(measure.into_value(), label_set);
+        }
+    }
+}
+
+/// Allows a specific [`MeterProvider`] to be used generically by the
+/// [`GlobalMeterProvider`] by mirroring the interface and boxing the return
+/// types.
+///
+/// [`MeterProvider`]: ../api/metrics/provider/trait.MeterProvider.html
+/// [`GlobalMeterProvider`]: struct.GlobalMeterProvider.html
+pub trait GenericMeterProvider: fmt::Debug + Send + Sync + 'static {
+    /// Creates a named meter instance that is a trait object through the
+    /// underlying `MeterProvider`.
+    fn meter_boxed(&self, name: &'static str) -> Arc<dyn GenericMeter>;
+}
+
+impl<P> GenericMeterProvider for P
+where
+    P: api::MeterProvider + Send + Sync,
+    P::Meter: GenericMeter,
+{
+    /// Return a boxed generic meter
+    fn meter_boxed(&self, name: &'static str) -> Arc<dyn GenericMeter> {
+        Arc::new(self.meter(name))
+    }
+}
+
+/// Represents the globally configured [`MeterProvider`] instance for this
+/// application. This allows generic metrics through the returned
+/// [`BoxedMeter`] instances.
+///
+/// [`MeterProvider`]: ../api/metrics/provider/trait.MeterProvider.html
+/// [`BoxedMeter`]: struct.BoxedMeter.html
+#[derive(Clone, Debug)]
+pub struct GlobalMeterProvider {
+    provider: Arc<dyn GenericMeterProvider>,
+}
+
+impl GlobalMeterProvider {
+    /// Create a new GlobalMeterProvider instance from a struct that implements `MeterProvider`.
+    fn new<P>(provider: P) -> Self
+    where
+        P: api::MeterProvider + Send + Sync,
+        P::Meter: GenericMeter,
+    {
+        GlobalMeterProvider {
+            provider: Arc::new(provider),
+        }
+    }
+}
+
+impl api::MeterProvider for GlobalMeterProvider {
+    type Meter = BoxedMeter;
+
+    /// Create a named meter using the global provider.
+    fn meter(&self, name: &'static str) -> Self::Meter {
+        BoxedMeter(self.provider.meter_boxed(name))
+    }
+}
+
+lazy_static::lazy_static! {
+    /// The global `MeterProvider` singleton.
+    static ref GLOBAL_METER_PROVIDER: RwLock<GlobalMeterProvider> = RwLock::new(GlobalMeterProvider::new(api::NoopMeterProvider {}));
+}
+
+/// Returns an instance of the currently configured global [`MeterProvider`]
+/// through [`GlobalMeterProvider`].
+///
+/// [`MeterProvider`]: ../api/metrics/provider/trait.MeterProvider.html
+/// [`GlobalMeterProvider`]: struct.GlobalMeterProvider.html
+pub fn meter_provider() -> GlobalMeterProvider {
+    GLOBAL_METER_PROVIDER
+        .read()
+        .expect("GLOBAL_METER_PROVIDER RwLock poisoned")
+        .clone()
+}
+
+/// Creates a named instance of [`Meter`] via the configured
+/// [`GlobalMeterProvider`].
+///
+/// This is a more convenient way of expressing `global::meter_provider().meter(name)`.
+///
+/// [`Meter`]: ../api/metrics/trait.Meter.html
+/// [`GlobalMeterProvider`]: struct.GlobalMeterProvider.html
+pub fn meter(name: &'static str) -> BoxedMeter {
+    api::MeterProvider::meter(&meter_provider(), name)
+}
+
+/// Sets the given [`MeterProvider`] instance as the current global meter
+/// provider.
+///
+/// [`MeterProvider`]: ../api/metrics/provider/trait.MeterProvider.html
+pub fn set_meter_provider<P>(new_provider: P)
+where
+    P: api::MeterProvider + Send + Sync,
+    P::Meter: GenericMeter,
+{
+    let mut global_provider = GLOBAL_METER_PROVIDER
+        .write()
+        .expect("GLOBAL_METER_PROVIDER RwLock poisoned");
+    *global_provider = GlobalMeterProvider::new(new_provider);
+}
+
+#[cfg(all(test, feature = "metrics"))]
+mod tests {
+    use super::*;
+    use crate::api::{
+        Counter, CounterHandle, Gauge, GaugeHandle, Measure, Meter, MeterProvider, MetricOptions,
+        Observer,
+    };
+    use crate::exporter::metrics::{Aggregation, MetricsExporter, Number, Record};
+    use crate::exporter::ExportResult;
+    use crate::sdk;
+    use std::sync::Mutex;
+
+    #[derive(Clone, Debug, Default)]
+    struct TestExporter(Arc<Mutex<Vec<Record>>>);
+
+    impl TestExporter {
+        fn values(&self) -> Vec<(String, Aggregation)> {
+            let mut values = self
+                .0
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|record| (record.descriptor.name.clone(), record.aggregation.clone()))
+                .collect::<Vec<_>>();
+            values.sort_by(|a, b| a.0.cmp(&b.0));
+            values
+        }
+    }
+
+    impl MetricsExporter for TestExporter {
+        fn export(&self, records: Vec<Record>) -> ExportResult {
+            *self.0.lock().unwrap() = records;
+            ExportResult::Success
+        }
+    }
+
+    #[test]
+    fn boxed_meter_records_through_provider() {
+        let exporter = TestExporter::default();
+        let controller = sdk::Controller::builder(exporter.clone()).build();
+        let meter = GlobalMeterProvider::new(controller.clone()).meter("global");
+
+        let labels = meter.labels(vec![api::Key::new("kind").string("boxed")]);
+        let counter = meter.new_i64_counter("requests", MetricOptions::default());
+        let measure = meter.new_f64_measure("latency", MetricOptions::default());
+        counter.add(1, &labels);
+        counter.acquire_handle(&labels).add(2);
+        meter.record_batch(&labels, vec![measure.measurement(0.5)]);
+        let observer =
+            meter.register_i64_observer("threads", MetricOptions::default(), move |result| {
+                result.observe(4, &labels)
+            });
+
+        controller.collect();
+        let values = exporter.values();
+        assert_eq!(
+            values[1],
+            ("requests".to_string(), Aggregation::Sum(Number::I64(3)))
+        );
+        assert_eq!(values[0].0, "latency");
+        match &values[2] {
+            (name, Aggregation::LastValue { value, .. }) => {
+                assert_eq!(name, "threads");
+                assert_eq!(*value, Number::I64(4));
+            }
+            other => panic!("unexpected value {:?}", other),
+        }
+
+        observer.unregister();
+        controller.collect();
+        assert_eq!(exporter.values().len(), 2);
+    }
+
+    #[test]
+    fn ignores_labels_of_other_meters() {
+        let exporter = TestExporter::default();
+        let controller = sdk::Controller::builder(exporter.clone()).build();
+        let meter = GlobalMeterProvider::new(controller.clone()).meter("global");
+        let noop_labels = GlobalMeterProvider::new(api::NoopMeterProvider {})
+            .meter("noop")
+            .labels(vec![]);
+
+        let gauge = meter.new_i64_gauge("queue", MetricOptions::default());
+        gauge.set(3, &noop_labels);
+        gauge.acquire_handle(&noop_labels).set(3);
+
+        controller.collect();
+        assert!(exporter.values().is_empty());
+    }
+
+    #[test]
+    fn global_meter_uses_configured_provider() {
+        let exporter = TestExporter::default();
+        let controller = sdk::Controller::builder(exporter.clone()).build();
+        set_meter_provider(controller.clone());
+
+        let meter = super::meter("global");
+        meter
+            .new_i64_counter("calls", MetricOptions::default())
+            .add(1, &meter.labels(vec![]));
+
+        controller.collect();
+        assert_eq!(
+            exporter.values(),
+            vec![("calls".to_string(), Aggregation::Sum(Number::I64(1)))]
+        );
+    }
+}
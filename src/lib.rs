@@ -225,3 +225,4 @@ pub mod experimental;
 pub mod exporter;
 pub mod global;
 pub mod sdk;
+pub mod semantic_conventions;
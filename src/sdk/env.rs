@@ -0,0 +1,124 @@
+//! # Environment configuration
+//!
+//! Helpers to read SDK and exporter settings from the standard `OTEL_*`
+//! environment variables, so deployments can be tuned without rebuilding.
+//!
+//! Variables that are unset or set to an empty string are treated as not
+//! configured. Malformed values are reported as an `EnvError` naming the
+//! variable, instead of silently falling back to the default.
+use std::env;
+use std::fmt;
+use std::str::FromStr;
+
+/// Errors returned when reading configuration from the environment.
+#[derive(Clone, Debug, PartialEq)]
+pub enum EnvError {
+    /// The variable is set but is not valid unicode.
+    NotUnicode(String),
+    /// The variable is set to a value that cannot be used.
+    InvalidValue {
+        /// Name of the variable.
+        name: String,
+        /// The configured value.
+        value: String,
+        /// Why the value cannot be used.
+        reason: String,
+    },
+}
+
+impl EnvError {
+    /// Creates an `InvalidValue` error.
+    pub fn invalid_value<T: fmt::Display>(name: &str, value: &str, reason: T) -> Self {
+        EnvError::InvalidValue {
+            name: name.to_string(),
+            value: value.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for EnvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnvError::NotUnicode(name) => write!(f, "{} is not valid unicode", name),
+            EnvError::InvalidValue {
+                name,
+                value,
+                reason,
+            } => write!(f, "invalid value {:?} for {}: {}", value, name, reason),
+        }
+    }
+}
+
+impl std::error::Error for EnvError {}
+
+/// Returns the trimmed value of the variable, or `None` if it is unset or
+/// empty.
+pub fn var(name: &str) -> Result<Option<String>, EnvError> {
+    match env::var(name) {
+        Ok(value) => {
+            let value = value.trim();
+            if value.is_empty() {
+                Ok(None)
+            } else {
+                Ok(Some(value.to_string()))
+            }
+        }
+        Err(env::VarError::NotPresent) => Ok(None),
+        Err(env::VarError::NotUnicode(_)) => Err(EnvError::NotUnicode(name.to_string())),
+    }
+}
+
+/// Parses the value of the variable, returning `None` if it is unset or
+/// empty.
+pub fn parse<T>(name: &str) -> Result<Option<T>, EnvError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    match var(name)? {
+        Some(value) => value
+            .parse()
+            .map(Some)
+            .map_err(|err| EnvError::invalid_value(name, &value, err)),
+        None => Ok(None),
+    }
+}
+
+/// Guards tests that modify the process environment, as tests run in
+/// parallel.
+#[cfg(test)]
+pub(crate) fn lock_for_test() -> std::sync::MutexGuard<'static, ()> {
+    lazy_static::lazy_static! {
+        static ref ENV_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+    }
+    ENV_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_and_parses_variables() {
+        let _guard = lock_for_test();
+        env::set_var("OTEL_TEST_ENV_VALUE", " 42 ");
+        env::set_var("OTEL_TEST_ENV_EMPTY", "");
+
+        assert_eq!(var("OTEL_TEST_ENV_VALUE"), Ok(Some("42".to_string())));
+        assert_eq!(parse::<u32>("OTEL_TEST_ENV_VALUE"), Ok(Some(42)));
+        assert_eq!(var("OTEL_TEST_ENV_EMPTY"), Ok(None));
+        assert_eq!(parse::<u32>("OTEL_TEST_ENV_UNSET"), Ok(None));
+
+        let err = parse::<bool>("OTEL_TEST_ENV_VALUE").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid value \"42\" for OTEL_TEST_ENV_VALUE: provided string was not `true` or `false`"
+        );
+
+        env::remove_var("OTEL_TEST_ENV_VALUE");
+        env::remove_var("OTEL_TEST_ENV_EMPTY");
+    }
+}
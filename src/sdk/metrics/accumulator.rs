@@ -0,0 +1,161 @@
+//! # Metric Accumulator
+//!
+//! The accumulator owns one `Aggregator` per instrument and label set. All
+//! measurements of a `Controller`'s meters are recorded here, and each
+//! collection checkpoints the aggregators into exportable `Record`s.
+//!
+//! Observer callbacks are also registered with the accumulator, and invoked
+//! at the start of each checkpoint.
+use crate::exporter::metrics::{Aggregation, Descriptor, Number, Record};
+use crate::sdk::metrics::aggregators::{Aggregator, AggregatorSelector};
+use crate::sdk::metrics::LabelSet;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Aggregator state of one instrument and label set.
+#[derive(Debug)]
+struct Entry {
+    descriptor: Arc<Descriptor>,
+    labels: LabelSet,
+    aggregator: Arc<dyn Aggregator>,
+    cumulative: Option<Aggregation>,
+}
+
+/// Callback of an observer instrument, reporting its values to the given
+/// accumulator.
+pub(crate) type ObserverCallback = Arc<dyn Fn(&Accumulator) + Send + Sync>;
+
+/// Aggregates measurements per instrument and label set.
+pub(crate) struct Accumulator {
+    selector: Box<dyn AggregatorSelector>,
+    stateful: bool,
+    next_instrument: AtomicUsize,
+    entries: Mutex<HashMap<(usize, String), Entry>>,
+    observers: Mutex<Vec<(usize, ObserverCallback)>>,
+}
+
+impl fmt::Debug for Accumulator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Accumulator")
+            .field("selector", &self.selector)
+            .field("stateful", &self.stateful)
+            .field("entries", &self.entries)
+            .finish()
+    }
+}
+
+impl Accumulator {
+    /// Creates an accumulator. Stateful accumulators report values
+    /// accumulated since creation, otherwise each checkpoint only reports
+    /// the measurements recorded since the previous one.
+    pub(crate) fn new(selector: Box<dyn AggregatorSelector>, stateful: bool) -> Self {
+        Accumulator {
+            selector,
+            stateful,
+            next_instrument: AtomicUsize::new(0),
+            entries: Mutex::new(HashMap::new()),
+            observers: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Allocates a unique id for a new instrument.
+    pub(crate) fn register_instrument(&self) -> usize {
+        self.next_instrument.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Registers the callback of an observer instrument.
+    pub(crate) fn register_observer(&self, instrument: usize, callback: ObserverCallback) {
+        self.observers
+            .lock()
+            .expect("Mutex poisoned")
+            .push((instrument, callback));
+    }
+
+    /// Removes the callback of an observer instrument together with its
+    /// aggregated values.
+    pub(crate) fn unregister_observer(&self, instrument: usize) {
+        self.observers
+            .lock()
+            .expect("Mutex poisoned")
+            .retain(|(id, _)| *id != instrument);
+        self.entries
+            .lock()
+            .expect("Mutex poisoned")
+            .retain(|(id, _), _| *id != instrument);
+    }
+
+    /// Returns the aggregator of an instrument and label set, creating it on
+    /// first use.
+    pub(crate) fn aggregator(
+        &self,
+        instrument: usize,
+        descriptor: &Arc<Descriptor>,
+        labels: &LabelSet,
+    ) -> Arc<dyn Aggregator> {
+        let mut entries = self.entries.lock().expect("Mutex poisoned");
+        entries
+            .entry((instrument, labels.encoded().to_string()))
+            .or_insert_with(|| Entry {
+                descriptor: descriptor.clone(),
+                labels: labels.clone(),
+                aggregator: self.selector.aggregator_for(descriptor),
+                cumulative: None,
+            })
+            .aggregator
+            .clone()
+    }
+
+    /// Records a single measurement.
+    pub(crate) fn record(
+        &self,
+        instrument: usize,
+        descriptor: &Arc<Descriptor>,
+        labels: &LabelSet,
+        number: Number,
+    ) {
+        self.aggregator(instrument, descriptor, labels)
+            .update(number)
+    }
+
+    /// Checkpoints all aggregators, returning a record for every instrument
+    /// and label set with a value to report.
+    pub(crate) fn checkpoint(&self) -> Vec<Record> {
+        // Callbacks record into `entries`, so they run without holding a lock.
+        let observers = self
+            .observers
+            .lock()
+            .expect("Mutex poisoned")
+            .iter()
+            .map(|(_, callback)| callback.clone())
+            .collect::<Vec<_>>();
+        for callback in observers {
+            callback(self);
+        }
+
+        let mut entries = self.entries.lock().expect("Mutex poisoned");
+        let mut records = Vec::with_capacity(entries.len());
+        for entry in entries.values_mut() {
+            let delta = entry.aggregator.checkpoint();
+            let aggregation = if self.stateful {
+                match (entry.cumulative.as_mut(), delta) {
+                    (Some(cumulative), Some(delta)) => cumulative.merge(&delta),
+                    (None, delta) => entry.cumulative = delta,
+                    (Some(_), None) => {}
+                }
+                entry.cumulative.clone()
+            } else {
+                delta
+            };
+            if let Some(aggregation) = aggregation {
+                records.push(Record {
+                    descriptor: entry.descriptor.clone(),
+                    labels: entry.labels.clone(),
+                    aggregation,
+                });
+            }
+        }
+        records
+    }
+}
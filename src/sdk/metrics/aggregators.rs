@@ -0,0 +1,252 @@
+//! # Metric Aggregators
+//!
+//! Aggregators combine the measurements of one instrument and label set
+//! between two checkpoints. Which aggregator an instrument uses is decided
+//! by an `AggregatorSelector`.
+use crate::exporter::metrics::{Aggregation, Descriptor, InstrumentKind, Number};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+/// Aggregates the measurements of one instrument and label set.
+pub trait Aggregator: fmt::Debug + Send + Sync {
+    /// Records a single measurement.
+    fn update(&self, number: Number);
+
+    /// Returns the aggregation of all measurements recorded since the last
+    /// checkpoint and resets the aggregator, or `None` if nothing was
+    /// recorded.
+    fn checkpoint(&self) -> Option<Aggregation>;
+}
+
+/// Chooses the `Aggregator` used for each instrument.
+pub trait AggregatorSelector: fmt::Debug + Send + Sync {
+    /// Creates a new aggregator for the given instrument.
+    fn aggregator_for(&self, descriptor: &Descriptor) -> Arc<dyn Aggregator>;
+}
+
+/// The built-in aggregator selectors.
+///
+/// Counters are always summed, gauges and observers report their last
+/// value, the variants only differ in how measures are aggregated.
+#[derive(Clone, Debug)]
+pub enum Selector {
+    /// Measures are summarized by their min, max, sum and count.
+    Inexpensive,
+    /// Measures are aggregated into histograms with the given bucket
+    /// boundaries.
+    Histogram(Vec<f64>),
+}
+
+impl AggregatorSelector for Selector {
+    fn aggregator_for(&self, descriptor: &Descriptor) -> Arc<dyn Aggregator> {
+        match (descriptor.instrument_kind, self) {
+            (InstrumentKind::Counter, _) => Arc::new(SumAggregator::default()),
+            (InstrumentKind::Gauge, _) | (InstrumentKind::Observer, _) => {
+                Arc::new(LastValueAggregator::default())
+            }
+            (InstrumentKind::Measure, Selector::Inexpensive) => {
+                Arc::new(MinMaxSumCountAggregator::default())
+            }
+            (InstrumentKind::Measure, Selector::Histogram(boundaries)) => {
+                Arc::new(HistogramAggregator::new(boundaries.clone()))
+            }
+        }
+    }
+}
+
+/// Sums all measurements.
+#[derive(Debug, Default)]
+pub struct SumAggregator {
+    sum: Mutex<Option<Number>>,
+}
+
+impl Aggregator for SumAggregator {
+    fn update(&self, number: Number) {
+        if let Ok(mut sum) = self.sum.lock() {
+            *sum = Some(sum.map_or(number, |sum| sum + number));
+        }
+    }
+
+    fn checkpoint(&self) -> Option<Aggregation> {
+        self.sum
+            .lock()
+            .ok()
+            .and_then(|mut sum| sum.take())
+            .map(Aggregation::Sum)
+    }
+}
+
+/// Keeps the most recent measurement.
+#[derive(Debug, Default)]
+pub struct LastValueAggregator {
+    last: Mutex<Option<(Number, SystemTime)>>,
+}
+
+impl Aggregator for LastValueAggregator {
+    fn update(&self, number: Number) {
+        if let Ok(mut last) = self.last.lock() {
+            *last = Some((number, SystemTime::now()));
+        }
+    }
+
+    fn checkpoint(&self) -> Option<Aggregation> {
+        self.last
+            .lock()
+            .ok()
+            .and_then(|mut last| last.take())
+            .map(|(value, timestamp)| Aggregation::LastValue { value, timestamp })
+    }
+}
+
+/// Tracks the min, max, sum and count of all measurements.
+#[derive(Debug, Default)]
+pub struct MinMaxSumCountAggregator {
+    state: Mutex<Option<Aggregation>>,
+}
+
+impl Aggregator for MinMaxSumCountAggregator {
+    fn update(&self, number: Number) {
+        if let Ok(mut state) = self.state.lock() {
+            let update = Aggregation::MinMaxSumCount {
+                min: number,
+                max: number,
+                sum: number,
+                count: 1,
+            };
+            match state.as_mut() {
+                Some(aggregation) => aggregation.merge(&update),
+                None => *state = Some(update),
+            }
+        }
+    }
+
+    fn checkpoint(&self) -> Option<Aggregation> {
+        self.state.lock().ok().and_then(|mut state| state.take())
+    }
+}
+
+/// Counts measurements in buckets with fixed boundaries.
+#[derive(Debug)]
+pub struct HistogramAggregator {
+    boundaries: Vec<f64>,
+    state: Mutex<Option<Aggregation>>,
+}
+
+impl HistogramAggregator {
+    /// Creates a histogram aggregator with the given bucket boundaries. The
+    /// boundaries are sorted, a bucket for values above the last boundary is
+    /// always added.
+    pub fn new(mut boundaries: Vec<f64>) -> Self {
+        boundaries.retain(|boundary| !boundary.is_nan());
+        boundaries.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        boundaries.dedup();
+        HistogramAggregator {
+            boundaries,
+            state: Mutex::new(None),
+        }
+    }
+}
+
+impl Aggregator for HistogramAggregator {
+    fn update(&self, number: Number) {
+        if let Ok(mut state) = self.state.lock() {
+            let value = number.to_f64();
+            let bucket = self
+                .boundaries
+                .iter()
+                .position(|boundary| value <= *boundary)
+                .unwrap_or(self.boundaries.len());
+            match state.as_mut() {
+                Some(Aggregation::Histogram {
+                    counts, sum, count, ..
+                }) => {
+                    counts[bucket] += 1;
+                    *sum = *sum + number;
+                    *count += 1;
+                }
+                _ => {
+                    let mut counts = vec![0; self.boundaries.len() + 1];
+                    counts[bucket] = 1;
+                    *state = Some(Aggregation::Histogram {
+                        boundaries: self.boundaries.clone(),
+                        counts,
+                        sum: number,
+                        count: 1,
+                    });
+                }
+            }
+        }
+    }
+
+    fn checkpoint(&self) -> Option<Aggregation> {
+        self.state.lock().ok().and_then(|mut state| state.take())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sum_resets_on_checkpoint() {
+        let aggregator = SumAggregator::default();
+        assert_eq!(aggregator.checkpoint(), None);
+
+        aggregator.update(Number::I64(2));
+        aggregator.update(Number::I64(3));
+        assert_eq!(
+            aggregator.checkpoint(),
+            Some(Aggregation::Sum(Number::I64(5)))
+        );
+        assert_eq!(aggregator.checkpoint(), None);
+    }
+
+    #[test]
+    fn last_value_keeps_latest() {
+        let aggregator = LastValueAggregator::default();
+        aggregator.update(Number::F64(1.0));
+        aggregator.update(Number::F64(-2.0));
+
+        match aggregator.checkpoint() {
+            Some(Aggregation::LastValue { value, .. }) => assert_eq!(value, Number::F64(-2.0)),
+            other => panic!("unexpected aggregation {:?}", other),
+        }
+    }
+
+    #[test]
+    fn min_max_sum_count_summarizes() {
+        let aggregator = MinMaxSumCountAggregator::default();
+        for value in &[3, -1, 7] {
+            aggregator.update(Number::I64(*value));
+        }
+
+        assert_eq!(
+            aggregator.checkpoint(),
+            Some(Aggregation::MinMaxSumCount {
+                min: Number::I64(-1),
+                max: Number::I64(7),
+                sum: Number::I64(9),
+                count: 3,
+            })
+        );
+    }
+
+    #[test]
+    fn histogram_buckets_are_upper_inclusive() {
+        let aggregator = HistogramAggregator::new(vec![5.0, 1.0]);
+        for value in &[0.5, 1.0, 2.0, 5.0, 10.0] {
+            aggregator.update(Number::F64(*value));
+        }
+
+        assert_eq!(
+            aggregator.checkpoint(),
+            Some(Aggregation::Histogram {
+                boundaries: vec![1.0, 5.0],
+                counts: vec![2, 2, 1],
+                sum: Number::F64(18.5),
+                count: 5,
+            })
+        );
+    }
+}
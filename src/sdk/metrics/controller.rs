@@ -0,0 +1,347 @@
+//! # Metrics Controller
+//!
+//! The `Controller` ties the metrics pipeline together. It owns the
+//! accumulator that all of its meters record into, and on every collection
+//! checkpoints the aggregated state and passes it to a `MetricsExporter`.
+//! Collections are triggered explicitly via `collect`, or periodically by
+//! spawning the future returned by `push` on an executor:
+//!
+//! ```ignore
+//! let controller = sdk::metrics::Controller::builder(exporter).build();
+//! tokio::spawn(controller.push(tokio::time::interval(Duration::from_secs(10))));
+//! ```
+use crate::api;
+use crate::exporter::metrics::MetricsExporter;
+use crate::exporter::ExportResult;
+use crate::sdk::metrics::accumulator::Accumulator;
+use crate::sdk::metrics::aggregators::{AggregatorSelector, Selector};
+use crate::sdk::metrics::Meter;
+use futures::{Future, Stream, StreamExt};
+use std::sync::{Arc, Mutex};
+
+/// Creates meters and exports their aggregated measurements.
+#[derive(Clone, Debug)]
+pub struct Controller {
+    inner: Arc<ControllerInner>,
+}
+
+#[derive(Debug)]
+struct ControllerInner {
+    accumulator: Arc<Accumulator>,
+    exporter: Mutex<Box<dyn MetricsExporter>>,
+}
+
+impl Controller {
+    /// Create a new controller builder exporting to the given exporter.
+    pub fn builder<E: MetricsExporter + 'static>(exporter: E) -> ControllerBuilder {
+        ControllerBuilder {
+            exporter: Box::new(exporter),
+            selector: Box::new(Selector::Inexpensive),
+            stateful: true,
+        }
+    }
+
+    /// Creates a `Meter` for the given component. All meters of a controller
+    /// share its aggregation state.
+    pub fn meter(&self, component: &'static str) -> Meter {
+        Meter::new(component, self.inner.accumulator.clone())
+    }
+
+    /// Checkpoints all instruments and exports the resulting records.
+    pub fn collect(&self) -> ExportResult {
+        let exporter = self.inner.exporter.lock().expect("Mutex poisoned");
+        exporter.export(self.inner.accumulator.checkpoint())
+    }
+
+    /// Returns a function collecting from this controller for as long as it
+    /// exists, for exporters that collect on demand.
+    pub(crate) fn collector(&self) -> impl Fn() + Send + Sync + 'static {
+        let inner = Arc::downgrade(&self.inner);
+        move || {
+            if let Some(inner) = inner.upgrade() {
+                Controller { inner }.collect();
+            }
+        }
+    }
+
+    /// Returns a future collecting once per item of `ticks`, finishing when
+    /// the stream ends.
+    pub fn push<T>(&self, ticks: T) -> impl Future<Output = ()> + Send
+    where
+        T: Stream + Send + 'static,
+    {
+        let controller = self.clone();
+        ticks.for_each(move |_| {
+            controller.collect();
+            futures::future::ready(())
+        })
+    }
+}
+
+impl api::MeterProvider for Controller {
+    type Meter = Meter;
+
+    /// Creates a `Meter` for the given component.
+    fn meter(&self, name: &'static str) -> Self::Meter {
+        Controller::meter(self, name)
+    }
+}
+
+/// Builder for `Controller`s.
+#[derive(Debug)]
+pub struct ControllerBuilder {
+    exporter: Box<dyn MetricsExporter>,
+    selector: Box<dyn AggregatorSelector>,
+    stateful: bool,
+}
+
+impl ControllerBuilder {
+    /// Set the selector choosing the aggregator for each instrument. Defaults
+    /// to `Selector::Inexpensive`.
+    pub fn with_aggregator_selector<S: AggregatorSelector + 'static>(self, selector: S) -> Self {
+        ControllerBuilder {
+            selector: Box::new(selector),
+            ..self
+        }
+    }
+
+    /// Set whether exported values are cumulative since the controller was
+    /// created (the default), or only cover the measurements since the
+    /// previous collection.
+    pub fn with_stateful(self, stateful: bool) -> Self {
+        ControllerBuilder { stateful, ..self }
+    }
+
+    /// Build a new controller from the current builder configuration.
+    pub fn build(self) -> Controller {
+        Controller {
+            inner: Arc::new(ControllerInner {
+                accumulator: Arc::new(Accumulator::new(self.selector, self.stateful)),
+                exporter: Mutex::new(self.exporter),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{
+        self, Counter, CounterHandle, Gauge, Measure, Meter as _, MetricOptions, Observer as _,
+    };
+    use crate::exporter::metrics::{Aggregation, Number, Record};
+    use crate::sdk::metrics::LabelSet;
+
+    #[derive(Debug, Default, Clone)]
+    struct TestExporter {
+        exports: Arc<Mutex<Vec<Vec<Record>>>>,
+    }
+
+    impl TestExporter {
+        fn last(&self) -> Vec<(String, String, Aggregation)> {
+            let mut records = self
+                .exports
+                .lock()
+                .unwrap()
+                .last()
+                .cloned()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|record| {
+                    (
+                        record.descriptor.name.clone(),
+                        record.labels.encoded().to_string(),
+                        record.aggregation,
+                    )
+                })
+                .collect::<Vec<_>>();
+            records.sort_by(|a, b| (&a.0, &a.1).cmp(&(&b.0, &b.1)));
+            records
+        }
+    }
+
+    impl MetricsExporter for TestExporter {
+        fn export(&self, records: Vec<Record>) -> ExportResult {
+            self.exports.lock().unwrap().push(records);
+            ExportResult::Success
+        }
+    }
+
+    #[test]
+    fn aggregates_per_instrument_and_label_set() {
+        let exporter = TestExporter::default();
+        let controller = Controller::builder(exporter.clone()).build();
+        let meter = controller.meter("test");
+
+        let counter = meter.new_i64_counter("requests", MetricOptions::default());
+        let measure = meter.new_f64_measure("latency", MetricOptions::default());
+        let get = meter.labels(vec![api::Key::new("method").string("GET")]);
+        let post = meter.labels(vec![api::Key::new("method").string("POST")]);
+
+        counter.add(1, &get);
+        counter.acquire_handle(&get).add(2);
+        counter.add(5, &post);
+        meter.record_batch(&get, vec![measure.measurement(0.5), counter.measurement(1)]);
+        measure.record(1.5, &get);
+
+        assert_eq!(controller.collect(), ExportResult::Success);
+        assert_eq!(
+            exporter.last(),
+            vec![
+                (
+                    "latency".to_string(),
+                    "method=GET".to_string(),
+                    Aggregation::MinMaxSumCount {
+                        min: Number::F64(0.5),
+                        max: Number::F64(1.5),
+                        sum: Number::F64(2.0),
+                        count: 2,
+                    }
+                ),
+                (
+                    "requests".to_string(),
+                    "method=GET".to_string(),
+                    Aggregation::Sum(Number::I64(4))
+                ),
+                (
+                    "requests".to_string(),
+                    "method=POST".to_string(),
+                    Aggregation::Sum(Number::I64(5))
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn stateful_controller_reports_cumulative_values() {
+        let exporter = TestExporter::default();
+        let controller = Controller::builder(exporter.clone()).build();
+        let meter = controller.meter("test");
+        let counter = meter.new_i64_counter("requests", MetricOptions::default());
+        let labels = meter.labels(vec![]);
+
+        counter.add(2, &labels);
+        controller.collect();
+        controller.collect();
+        assert_eq!(
+            exporter.last(),
+            vec![(
+                "requests".to_string(),
+                String::new(),
+                Aggregation::Sum(Number::I64(2))
+            )]
+        );
+
+        counter.add(3, &labels);
+        controller.collect();
+        assert_eq!(exporter.last()[0].2, Aggregation::Sum(Number::I64(5)));
+    }
+
+    #[test]
+    fn stateless_controller_reports_deltas() {
+        let exporter = TestExporter::default();
+        let controller = Controller::builder(exporter.clone())
+            .with_stateful(false)
+            .build();
+        let meter = controller.meter("test");
+        let gauge = meter.new_i64_gauge("queue", MetricOptions::default());
+        let labels = meter.labels(vec![]);
+
+        gauge.set(7, &labels);
+        controller.collect();
+        match &exporter.last()[0].2 {
+            Aggregation::LastValue { value, .. } => assert_eq!(*value, Number::I64(7)),
+            other => panic!("unexpected aggregation {:?}", other),
+        }
+
+        controller.collect();
+        assert!(exporter.last().is_empty());
+    }
+
+    #[test]
+    fn observers_report_on_every_collection() {
+        let exporter = TestExporter::default();
+        let controller = Controller::builder(exporter.clone())
+            .with_stateful(false)
+            .build();
+        let meter = controller.meter("test");
+        let calls = Arc::new(Mutex::new(0));
+        let counted = calls.clone();
+        let labels = meter.labels(vec![api::Key::new("queue").string("default")]);
+
+        let observer =
+            meter.register_f64_observer("depth", MetricOptions::default(), move |result| {
+                let mut calls = counted.lock().unwrap();
+                *calls += 1;
+                result.observe(f64::from(*calls), &labels);
+            });
+
+        for expected in 1..=2 {
+            controller.collect();
+            match &exporter.last()[..] {
+                [(name, labels, Aggregation::LastValue { value, .. })] => {
+                    assert_eq!(name, "depth");
+                    assert_eq!(labels, "queue=default");
+                    assert_eq!(*value, Number::F64(f64::from(expected)));
+                }
+                other => panic!("unexpected records {:?}", other),
+            }
+        }
+
+        observer.unregister();
+        controller.collect();
+        assert!(exporter.last().is_empty());
+        assert_eq!(*calls.lock().unwrap(), 2);
+    }
+
+    #[test]
+    fn unregistered_observers_are_not_reported_cumulatively() {
+        let exporter = TestExporter::default();
+        let controller = Controller::builder(exporter.clone()).build();
+        let meter = controller.meter("test");
+        let observer =
+            meter.register_i64_observer("open_files", MetricOptions::default(), |result| {
+                result.observe(3, &LabelSet::default())
+            });
+
+        controller.collect();
+        assert_eq!(exporter.last().len(), 1);
+
+        observer.unregister();
+        controller.collect();
+        assert!(exporter.last().is_empty());
+    }
+
+    #[test]
+    fn selector_chooses_measure_aggregation() {
+        let exporter = TestExporter::default();
+        let controller = Controller::builder(exporter.clone())
+            .with_aggregator_selector(Selector::Histogram(vec![10.0]))
+            .build();
+        let meter = controller.meter("test");
+        let measure = meter.new_i64_measure("size", MetricOptions::default());
+        let labels = meter.labels(vec![]);
+
+        measure.record(3, &labels);
+        measure.record(30, &labels);
+        controller.collect();
+        assert_eq!(
+            exporter.last()[0].2,
+            Aggregation::Histogram {
+                boundaries: vec![10.0],
+                counts: vec![1, 1],
+                sum: Number::I64(33),
+                count: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn push_collects_on_every_tick() {
+        let exporter = TestExporter::default();
+        let controller = Controller::builder(exporter.clone()).build();
+
+        futures::executor::block_on(controller.push(futures::stream::iter(vec![(), (), ()])));
+        assert_eq!(exporter.exports.lock().unwrap().len(), 3);
+    }
+}
@@ -9,77 +9,227 @@
 //! `Meter` struct, used for recording metrics. There are three distinct
 //! instruments in the Metrics API, commonly known as `Counter`s, `Gauge`s,
 //! and `Measure`s.
+//!
+//! Measurements are aggregated per instrument and label set by an
+//! accumulator shared by all meters of a `Controller`. The aggregation used
+//! for each instrument is chosen by an `AggregatorSelector`. When the
+//! controller collects, the aggregators are checkpointed and the resulting
+//! records are handed to a `MetricsExporter`. Callbacks of observer
+//! instruments are invoked at the start of every collection:
+//!
+//! ```
+//! use opentelemetry::api::{Counter, Meter, MetricOptions};
+//! use opentelemetry::exporter::metrics::prometheus::PrometheusExporter;
+//! use opentelemetry::sdk;
+//!
+//! let exporter = PrometheusExporter::new(Default::default()).unwrap();
+//! let controller = sdk::metrics::Controller::builder(exporter.clone()).build();
+//!
+//! let meter = controller.meter("my_component");
+//! let requests = meter.new_i64_counter("requests", MetricOptions::default());
+//! requests.add(1, &meter.labels(vec![]));
+//!
+//! controller.collect();
+//! assert_eq!(exporter.registry().gather().len(), 1);
+//! ```
 use crate::api;
-use crate::exporter::metrics::prometheus;
-use std::borrow::Cow;
-use std::collections::HashMap;
+use crate::exporter::metrics::{Descriptor, InstrumentKind, Number, NumberKind};
+use std::collections::{btree_map, BTreeMap};
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+mod accumulator;
+pub mod aggregators;
+pub mod controller;
+
+use accumulator::Accumulator;
+pub use aggregators::{Aggregator, AggregatorSelector, Selector};
+pub use controller::{Controller, ControllerBuilder};
+
+/// A sorted set of labels, identifying one aggregation of an instrument.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LabelSet {
+    labels: BTreeMap<api::Key, api::Value>,
+    encoded: String,
+}
+
+impl LabelSet {
+    /// Builds a `LabelSet` from `KeyValue`s. If a key occurs more than once
+    /// the last value is used.
+    pub fn from_key_values(key_values: Vec<api::KeyValue>) -> Self {
+        let labels = key_values
+            .into_iter()
+            .map(|api::KeyValue { key, value }| (key, value))
+            .collect::<BTreeMap<_, _>>();
+        let encoded = labels
+            .iter()
+            .map(|(key, value)| {
+                format!(
+                    "{}={}",
+                    escape_label(key.as_str()),
+                    escape_label(&String::from(value.clone()))
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+
+        LabelSet { labels, encoded }
+    }
+
+    /// Iterates over the labels, sorted by key.
+    pub fn iter(&self) -> btree_map::Iter<'_, api::Key, api::Value> {
+        self.labels.iter()
+    }
+
+    /// Number of labels in this set.
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    /// Returns `true` if this set has no labels.
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    /// A canonical `key=value,key=value` encoding of the labels, equal only for
+    /// equal sets. Backslashes, commas and equals signs are escaped with a backslash.
+    pub fn encoded(&self) -> &str {
+        &self.encoded
+    }
+}
 
-/// Collection of label key and value types.
-pub type LabelSet = HashMap<Cow<'static, str>, Cow<'static, str>>;
 impl api::LabelSet for LabelSet {}
 
+/// Escapes the separators used by `LabelSet::encoded` so that distinct label
+/// sets never share an encoding.
+fn escape_label(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        if c == '\\' || c == ',' || c == '=' {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
 /// `Meter` implementation to create manage metric instruments and record
-/// batch measurements
-#[allow(missing_debug_implementations)]
+/// batch measurements. Meters are created by a `Controller`.
+#[derive(Clone, Debug)]
 pub struct Meter {
-    registry: &'static prometheus::Registry,
     component: &'static str,
+    accumulator: Arc<Accumulator>,
 }
 
 impl Meter {
-    /// Create a new `Meter` instance with a component name and empty registry.
-    pub fn new(component: &'static str) -> Self {
+    pub(crate) fn new(component: &'static str, accumulator: Arc<Accumulator>) -> Self {
         Meter {
-            registry: prometheus::default_registry(),
             component,
+            accumulator,
         }
     }
 
-    /// Build prometheus `Opts` from `name` and `description`.
-    fn build_opts(
+    /// Describes a new instrument of this meter.
+    fn descriptor(
         &self,
-        mut name: String,
-        unit: api::Unit,
-        description: String,
-    ) -> prometheus::Opts {
-        if !unit.as_str().is_empty() {
-            name.push_str(&format!("_{}", unit.as_str()));
-        }
-        // Prometheus cannot have empty help strings
-        let help = if !description.is_empty() {
-            description
-        } else {
-            format!("{} metric", name)
+        name: String,
+        instrument_kind: InstrumentKind,
+        number_kind: NumberKind,
+        opts: api::MetricOptions,
+    ) -> Arc<Descriptor> {
+        let api::MetricOptions {
+            description,
+            unit,
+            keys: _keys,
+            alternate,
+        } = opts;
+        let descriptor = Descriptor {
+            name,
+            component: self.component,
+            instrument_kind,
+            number_kind,
+            description,
+            unit: unit.as_str().to_string(),
+            alternate,
         };
-        prometheus::Opts::new(name, help).namespace(self.component)
+
+        Arc::new(descriptor)
+    }
+
+    /// Registers a new instrument with the accumulator.
+    fn instrument<T>(
+        &self,
+        name: String,
+        instrument_kind: InstrumentKind,
+        number_kind: NumberKind,
+        opts: api::MetricOptions,
+    ) -> Instrument<T> {
+        Instrument {
+            core: Arc::new(InstrumentCore {
+                id: self.accumulator.register_instrument(),
+                descriptor: self.descriptor(name, instrument_kind, number_kind, opts),
+                accumulator: self.accumulator.clone(),
+            }),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Registers a new observer and its callback with the accumulator.
+    fn observer<T, F>(
+        &self,
+        name: String,
+        number_kind: NumberKind,
+        opts: api::MetricOptions,
+        callback: F,
+    ) -> Observer
+    where
+        T: Into<api::MeasurementValue>,
+        F: Fn(&dyn api::ObserverResult<T, LabelSet>) + Send + Sync + 'static,
+    {
+        let id = self.accumulator.register_instrument();
+        let descriptor = self.descriptor(name, InstrumentKind::Observer, number_kind, opts);
+        self.accumulator.register_observer(
+            id,
+            Arc::new(move |accumulator: &Accumulator| {
+                callback(&ObserverResult {
+                    id,
+                    descriptor: &descriptor,
+                    accumulator,
+                    _marker: PhantomData,
+                })
+            }),
+        );
+
+        Observer {
+            id,
+            accumulator: self.accumulator.clone(),
+        }
     }
 }
 
 impl api::Meter for Meter {
     /// The label set used by this `Meter`.
     type LabelSet = LabelSet;
-    /// This implementation of `api::Meter` produces `prometheus::IntCounterVec;` instances.
-    type I64Counter = prometheus::IntCounterVec;
-    /// This implementation of `api::Meter` produces `prometheus::CounterVec;` instances.
-    type F64Counter = prometheus::CounterVec;
-    /// This implementation of `api::Meter` produces `prometheus::IntGaugeVec;` instances.
-    type I64Gauge = prometheus::IntGaugeVec;
-    /// This implementation of `api::Meter` produces `prometheus::GaugeVec;` instances.
-    type F64Gauge = prometheus::GaugeVec;
-    /// This implementation of `api::Meter` produces `prometheus::IntMeasure;` instances.
-    type I64Measure = prometheus::IntMeasure;
-    /// This implementation of `api::Meter` produces `prometheus::HistogramVec;` instances.
-    type F64Measure = prometheus::HistogramVec;
+    /// This implementation of `api::Meter` produces `sdk::metrics::Counter<i64>` instances.
+    type I64Counter = Counter<i64>;
+    /// This implementation of `api::Meter` produces `sdk::metrics::Counter<f64>` instances.
+    type F64Counter = Counter<f64>;
+    /// This implementation of `api::Meter` produces `sdk::metrics::Gauge<i64>` instances.
+    type I64Gauge = Gauge<i64>;
+    /// This implementation of `api::Meter` produces `sdk::metrics::Gauge<f64>` instances.
+    type F64Gauge = Gauge<f64>;
+    /// This implementation of `api::Meter` produces `sdk::metrics::Measure<i64>` instances.
+    type I64Measure = Measure<i64>;
+    /// This implementation of `api::Meter` produces `sdk::metrics::Measure<f64>` instances.
+    type F64Measure = Measure<f64>;
+    /// This implementation of `api::Meter` produces `sdk::metrics::Observer` instances.
+    type I64Observer = Observer;
+    /// This implementation of `api::Meter` produces `sdk::metrics::Observer` instances.
+    type F64Observer = Observer;
 
     /// Builds a `LabelSet` from `KeyValue`s.
     fn labels(&self, key_values: Vec<api::KeyValue>) -> Self::LabelSet {
-        let mut label_set: Self::LabelSet = Default::default();
-
-        for api::KeyValue { key, value } in key_values.into_iter() {
-            label_set.insert(Cow::Owned(key.into()), Cow::Owned(value.into()));
-        }
-
-        label_set
+        LabelSet::from_key_values(key_values)
     }
 
     /// Creates a new `i64` counter with a given name and customized with passed options.
@@ -88,18 +238,7 @@ impl api::Meter for Meter {
         name: S,
         opts: api::MetricOptions,
     ) -> Self::I64Counter {
-        let api::MetricOptions {
-            description,
-            unit,
-            keys,
-            alternate: _alternative,
-        } = opts;
-        let counter_opts = self.build_opts(name.into(), unit, description);
-        let labels = prometheus::convert_labels(&keys);
-        let counter = prometheus::IntCounterVec::new(counter_opts, &labels).unwrap();
-        self.registry.register(Box::new(counter.clone())).unwrap();
-
-        counter
+        Counter(self.instrument(name.into(), InstrumentKind::Counter, NumberKind::I64, opts))
     }
 
     /// Creates a new `f64` counter with a given name and customized with passed options.
@@ -108,50 +247,17 @@ impl api::Meter for Meter {
         name: S,
         opts: api::MetricOptions,
     ) -> Self::F64Counter {
-        let api::MetricOptions {
-            description,
-            unit,
-            keys,
-            alternate: _alternative,
-        } = opts;
-        let counter_opts = self.build_opts(name.into(), unit, description);
-        let labels = prometheus::convert_labels(&keys);
-        let counter = prometheus::CounterVec::new(counter_opts, &labels).unwrap();
-        self.registry.register(Box::new(counter.clone())).unwrap();
-
-        counter
+        Counter(self.instrument(name.into(), InstrumentKind::Counter, NumberKind::F64, opts))
     }
 
     /// Creates a new `i64` gauge with a given name and customized with passed options.
     fn new_i64_gauge<S: Into<String>>(&self, name: S, opts: api::MetricOptions) -> Self::I64Gauge {
-        let api::MetricOptions {
-            description,
-            unit,
-            keys,
-            alternate: _alternative,
-        } = opts;
-        let gauge_opts = self.build_opts(name.into(), unit, description);
-        let labels = prometheus::convert_labels(&keys);
-        let gauge = prometheus::IntGaugeVec::new(gauge_opts, &labels).unwrap();
-        self.registry.register(Box::new(gauge.clone())).unwrap();
-
-        gauge
+        Gauge(self.instrument(name.into(), InstrumentKind::Gauge, NumberKind::I64, opts))
     }
 
     /// Creates a new `f64` gauge with a given name and customized with passed options.
     fn new_f64_gauge<S: Into<String>>(&self, name: S, opts: api::MetricOptions) -> Self::F64Gauge {
-        let api::MetricOptions {
-            description,
-            unit,
-            keys,
-            alternate: _alternative,
-        } = opts;
-        let gauge_opts = self.build_opts(name.into(), unit, description);
-        let labels = prometheus::convert_labels(&keys);
-        let gauge = prometheus::GaugeVec::new(gauge_opts, &labels).unwrap();
-        self.registry.register(Box::new(gauge.clone())).unwrap();
-
-        gauge
+        Gauge(self.instrument(name.into(), InstrumentKind::Gauge, NumberKind::F64, opts))
     }
 
     /// Creates a new `i64` measure with a given name and customized with passed options.
@@ -160,19 +266,7 @@ impl api::Meter for Meter {
         name: S,
         opts: api::MetricOptions,
     ) -> Self::I64Measure {
-        let api::MetricOptions {
-            description,
-            unit,
-            keys,
-            alternate: _alternative,
-        } = opts;
-        let common_opts = self.build_opts(name.into(), unit, description);
-        let labels = prometheus::convert_labels(&keys);
-        let histogram_opts = prometheus::HistogramOpts::from(common_opts);
-        let histogram = prometheus::HistogramVec::new(histogram_opts, &labels).unwrap();
-        self.registry.register(Box::new(histogram.clone())).unwrap();
-
-        prometheus::IntMeasure::new(histogram)
+        Measure(self.instrument(name.into(), InstrumentKind::Measure, NumberKind::I64, opts))
     }
 
     /// Creates a new `f64` measure with a given name and customized with passed options.
@@ -181,19 +275,35 @@ impl api::Meter for Meter {
         name: S,
         opts: api::MetricOptions,
     ) -> Self::F64Measure {
-        let api::MetricOptions {
-            description,
-            unit,
-            keys,
-            alternate: _alternative,
-        } = opts;
-        let common_opts = self.build_opts(name.into(), unit, description);
-        let labels = prometheus::convert_labels(&keys);
-        let histogram_opts = prometheus::HistogramOpts::from(common_opts);
-        let histogram = prometheus::HistogramVec::new(histogram_opts, &labels).unwrap();
-        self.registry.register(Box::new(histogram.clone())).unwrap();
+        Measure(self.instrument(name.into(), InstrumentKind::Measure, NumberKind::F64, opts))
+    }
+
+    /// Registers a new `i64` observer with a given name and customized with passed options.
+    fn register_i64_observer<S, F>(
+        &self,
+        name: S,
+        opts: api::MetricOptions,
+        callback: F,
+    ) -> Self::I64Observer
+    where
+        S: Into<String>,
+        F: Fn(&dyn api::ObserverResult<i64, Self::LabelSet>) + Send + Sync + 'static,
+    {
+        self.observer(name.into(), NumberKind::I64, opts, callback)
+    }
 
-        histogram
+    /// Registers a new `f64` observer with a given name and customized with passed options.
+    fn register_f64_observer<S, F>(
+        &self,
+        name: S,
+        opts: api::MetricOptions,
+        callback: F,
+    ) -> Self::F64Observer
+    where
+        S: Into<String>,
+        F: Fn(&dyn api::ObserverResult<f64, Self::LabelSet>) + Send + Sync + 'static,
+    {
+        self.observer(name.into(), NumberKind::F64, opts, callback)
     }
 
     /// Records a batch of measurements.
@@ -204,7 +314,258 @@ impl api::Meter for Meter {
     ) {
         for measure in measurements.into_iter() {
             let instrument = measure.instrument();
-            instrument.record_one(measure.into_value(), &label_set);
+            instrument.record_one(measure.into_value(), label_set);
         }
     }
 }
+
+/// Converts a measurement to a `Number` of the given kind.
+fn to_number(kind: NumberKind, value: api::MeasurementValue) -> Number {
+    match kind {
+        NumberKind::I64 => Number::I64(value.into_i64()),
+        NumberKind::F64 => Number::F64(value.into_f64()),
+    }
+}
+
+/// State shared by an instrument and its handles.
+#[derive(Debug)]
+struct InstrumentCore {
+    id: usize,
+    descriptor: Arc<Descriptor>,
+    accumulator: Arc<Accumulator>,
+}
+
+impl InstrumentCore {
+    fn record(&self, value: api::MeasurementValue, labels: &LabelSet) {
+        let number = to_number(self.descriptor.number_kind, value);
+        self.accumulator
+            .record(self.id, &self.descriptor, labels, number)
+    }
+
+    fn handle<T>(&self, labels: &LabelSet) -> Handle<T> {
+        Handle {
+            number_kind: self.descriptor.number_kind,
+            aggregator: self
+                .accumulator
+                .aggregator(self.id, &self.descriptor, labels),
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// An instrument registered with a `Meter`.
+#[derive(Debug)]
+struct Instrument<T> {
+    core: Arc<InstrumentCore>,
+    _marker: PhantomData<T>,
+}
+
+impl<T> Clone for Instrument<T> {
+    fn clone(&self) -> Self {
+        Instrument {
+            core: self.core.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// A handle to the aggregator of an instrument and a fixed label set.
+#[derive(Debug)]
+pub struct Handle<T> {
+    number_kind: NumberKind,
+    aggregator: Arc<dyn Aggregator>,
+    _marker: PhantomData<T>,
+}
+
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        Handle {
+            number_kind: self.number_kind,
+            aggregator: self.aggregator.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> api::InstrumentHandle for Handle<T> {
+    /// Record a single measurement value for the preset labels.
+    fn record_one(&self, value: api::MeasurementValue) {
+        self.aggregator.update(to_number(self.number_kind, value))
+    }
+}
+
+impl<T: Into<api::MeasurementValue>> api::CounterHandle<T> for Handle<T> {}
+impl<T: Into<api::MeasurementValue>> api::GaugeHandle<T> for Handle<T> {}
+impl<T: Into<api::MeasurementValue>> api::MeasureHandle<T> for Handle<T> {}
+
+/// A counter instrument, aggregated by summing its measurements.
+#[derive(Debug)]
+pub struct Counter<T>(Instrument<T>);
+
+impl<T> Clone for Counter<T> {
+    fn clone(&self) -> Self {
+        Counter(self.0.clone())
+    }
+}
+
+impl<T> api::Instrument<LabelSet> for Counter<T> {
+    /// Record a single counter measurement value.
+    fn record_one(&self, value: api::MeasurementValue, label_set: &LabelSet) {
+        self.0.core.record(value, label_set)
+    }
+}
+
+impl<T> api::Counter<T, LabelSet> for Counter<T>
+where
+    T: Into<api::MeasurementValue> + 'static,
+{
+    /// Handles for counters bound to a label set.
+    type Handle = Handle<T>;
+
+    /// Creates a `Measurement` object to be used by a `Meter` when batch recording.
+    fn measurement(&self, value: T) -> api::Measurement<LabelSet> {
+        api::Measurement::new(Arc::new(self.clone()), value.into())
+    }
+
+    /// Creates a handle for this instrument.
+    fn acquire_handle(&self, labels: &LabelSet) -> Self::Handle {
+        self.0.core.handle(labels)
+    }
+}
+
+/// A gauge instrument, reporting its last measurement.
+#[derive(Debug)]
+pub struct Gauge<T>(Instrument<T>);
+
+impl<T> Clone for Gauge<T> {
+    fn clone(&self) -> Self {
+        Gauge(self.0.clone())
+    }
+}
+
+impl<T> api::Instrument<LabelSet> for Gauge<T> {
+    /// Record a single gauge measurement value.
+    fn record_one(&self, value: api::MeasurementValue, label_set: &LabelSet) {
+        self.0.core.record(value, label_set)
+    }
+}
+
+impl<T> api::Gauge<T, LabelSet> for Gauge<T>
+where
+    T: Into<api::MeasurementValue> + 'static,
+{
+    /// Handles for gauges bound to a label set.
+    type Handle = Handle<T>;
+
+    /// Creates a `Measurement` object to be used by a `Meter` when batch recording.
+    fn measurement(&self, value: T) -> api::Measurement<LabelSet> {
+        api::Measurement::new(Arc::new(self.clone()), value.into())
+    }
+
+    /// Creates a handle for this instrument.
+    fn acquire_handle(&self, labels: &LabelSet) -> Self::Handle {
+        self.0.core.handle(labels)
+    }
+}
+
+/// A measure instrument, aggregated according to the controller's
+/// `AggregatorSelector`.
+#[derive(Debug)]
+pub struct Measure<T>(Instrument<T>);
+
+impl<T> Clone for Measure<T> {
+    fn clone(&self) -> Self {
+        Measure(self.0.clone())
+    }
+}
+
+impl<T> api::Instrument<LabelSet> for Measure<T> {
+    /// Record a single measure measurement value.
+    fn record_one(&self, value: api::MeasurementValue, label_set: &LabelSet) {
+        self.0.core.record(value, label_set)
+    }
+}
+
+impl<T> api::Measure<T, LabelSet> for Measure<T>
+where
+    T: Into<api::MeasurementValue> + 'static,
+{
+    /// Handles for measures bound to a label set.
+    type Handle = Handle<T>;
+
+    /// Creates a `Measurement` object to be used by a `Meter` when batch recording.
+    fn measurement(&self, value: T) -> api::Measurement<LabelSet> {
+        api::Measurement::new(Arc::new(self.clone()), value.into())
+    }
+
+    /// Creates a handle for this instrument.
+    fn acquire_handle(&self, labels: &LabelSet) -> Self::Handle {
+        self.0.core.handle(labels)
+    }
+}
+
+/// Reports the values of an observer while its callback runs.
+struct ObserverResult<'a, T> {
+    id: usize,
+    descriptor: &'a Arc<Descriptor>,
+    accumulator: &'a Accumulator,
+    _marker: PhantomData<T>,
+}
+
+impl<'a, T: Into<api::MeasurementValue>> api::ObserverResult<T, LabelSet>
+    for ObserverResult<'a, T>
+{
+    /// Records the observed value for the given labels.
+    fn observe(&self, value: T, label_set: &LabelSet) {
+        let number = to_number(self.descriptor.number_kind, value.into());
+        self.accumulator
+            .record(self.id, self.descriptor, label_set, number)
+    }
+}
+
+/// An observer instrument, reporting the values of its callback on every
+/// collection.
+#[derive(Clone, Debug)]
+pub struct Observer {
+    id: usize,
+    accumulator: Arc<Accumulator>,
+}
+
+impl api::Observer for Observer {
+    /// Removes the observer's callback and its reported values.
+    fn unregister(&self) {
+        self.accumulator.unregister_observer(self.id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn label_sets_are_order_independent() {
+        let a = LabelSet::from_key_values(vec![
+            api::Key::new("b").i64(2),
+            api::Key::new("a").string("x"),
+        ]);
+        let b = LabelSet::from_key_values(vec![
+            api::Key::new("a").string("x"),
+            api::Key::new("b").i64(2),
+        ]);
+
+        assert_eq!(a, b);
+        assert_eq!(a.encoded(), "a=x,b=2");
+    }
+
+    #[test]
+    fn label_set_encoding_escapes_separators() {
+        let a = LabelSet::from_key_values(vec![api::Key::new("a").string("1,b=2")]);
+        let b = LabelSet::from_key_values(vec![
+            api::Key::new("a").string("1"),
+            api::Key::new("b").string("2"),
+        ]);
+
+        assert_ne!(a.encoded(), b.encoded());
+        assert_eq!(a.encoded(), "a=1\\,b\\=2");
+    }
+}
@@ -6,6 +6,7 @@
 //! facilitates the delivery of telemetry data to storage systems
 //! through `Exporter`s. These can be configured on `Tracer` and
 //! `Meter` creation.
+pub mod env;
 #[cfg(feature = "metrics")]
 pub mod metrics;
 pub mod resource;
@@ -13,17 +14,20 @@ pub mod resource;
 pub mod trace;
 
 #[cfg(feature = "metrics")]
-pub use metrics::{LabelSet, Meter};
+pub use metrics::{Controller, LabelSet, Meter};
 pub use resource::Resource;
 #[cfg(feature = "trace")]
 pub use trace::{
     config::Config,
     evicted_hash_map::EvictedHashMap,
     evicted_queue::EvictedQueue,
-    id_generator::IdGenerator,
+    id_generator::{IdGenerator, XrayIdGenerator},
     provider::Provider,
-    sampler::Sampler,
+    sampler::{RateLimiter, Sampler, SamplingRule},
     span::Span,
-    span_processor::{BatchSpanProcessor, SimpleSpanProcessor},
+    span_processor::{
+        BatchSpanProcessor, BatchSpanProcessorStats, SimpleSpanProcessor, SpanDropReason,
+    },
     tracer::Tracer,
+    zpages::ZPagesSpanProcessor,
 };
@@ -12,11 +12,40 @@
 //! That association cannot be changed later. When associated with a `Provider`, all `Span`s
 //! produced by any `Tracer` from the provider are associated with this `Resource`.
 //!
+//! Resources can also be discovered at startup by [`ResourceDetector`]s, see
+//! [`Resource::from_detectors`]. The SDK provides detectors for the
+//! `OTEL_RESOURCE_ATTRIBUTES` environment variable, the running process, the
+//! host and the container the process runs in.
+//!
 //! [`Provider`]: ../../api/trace/provider/trait.Provider.html
+//! [`ResourceDetector`]: trait.ResourceDetector.html
+//! [`Resource::from_detectors`]: struct.Resource.html#method.from_detectors
 use crate::api;
+use crate::sdk::{self, env::EnvError};
+use crate::semantic_conventions as semcov;
+use percent_encoding::percent_decode_str;
 #[cfg(feature = "serialize")]
 use serde::{Deserialize, Serialize};
 use std::collections::{btree_map, btree_map::Entry, BTreeMap};
+use std::fmt;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+mod container;
+mod env;
+mod host;
+mod process;
+
+pub use container::ContainerResourceDetector;
+pub use env::EnvResourceDetector;
+pub use host::HostResourceDetector;
+pub use process::ProcessResourceDetector;
+
+/// Environment variable with resource attributes.
+const OTEL_RESOURCE_ATTRIBUTES: &str = "OTEL_RESOURCE_ATTRIBUTES";
+/// Environment variable with the service name.
+const OTEL_SERVICE_NAME: &str = "OTEL_SERVICE_NAME";
 
 /// Describes an entity about which identifying information and metadata is exposed.
 ///
@@ -42,6 +71,77 @@ impl Resource {
         resource
     }
 
+    /// Create a new `Resource` from the `OTEL_RESOURCE_ATTRIBUTES` and
+    /// `OTEL_SERVICE_NAME` environment variables.
+    ///
+    /// Resource attributes are a comma separated list of `key=value` pairs
+    /// with percent-encoded values. `OTEL_SERVICE_NAME` takes precedence over
+    /// a `service.name` attribute.
+    pub fn from_env() -> Result<Self, EnvError> {
+        let mut resource = Resource::default();
+
+        if let Some(service_name) = sdk::env::var(OTEL_SERVICE_NAME)? {
+            resource.insert(semcov::resource::SERVICE_NAME.string(service_name));
+        }
+        if let Some(attributes) = sdk::env::var(OTEL_RESOURCE_ATTRIBUTES)? {
+            for pair in attributes.split(',').map(str::trim) {
+                if pair.is_empty() {
+                    continue;
+                }
+                let mut parts = pair.splitn(2, '=');
+                let (key, value) = match (parts.next().map(str::trim), parts.next()) {
+                    (Some(key), Some(value)) if !key.is_empty() => (key, value.trim()),
+                    _ => {
+                        return Err(EnvError::invalid_value(
+                            OTEL_RESOURCE_ATTRIBUTES,
+                            &attributes,
+                            format!("{:?} is not a key=value pair", pair),
+                        ))
+                    }
+                };
+                let value = percent_decode_str(value).decode_utf8().map_err(|err| {
+                    EnvError::invalid_value(OTEL_RESOURCE_ATTRIBUTES, &attributes, err)
+                })?;
+                resource.insert(api::Key::new(key.to_string()).string(value));
+            }
+        }
+
+        Ok(resource)
+    }
+
+    /// Create a new `Resource` by running the given detectors and merging
+    /// the detected resources. Detectors earlier in the list have priority.
+    ///
+    /// Detectors run concurrently on their own threads. Resources that are
+    /// not detected within `timeout` are left out, so a slow detector never
+    /// blocks for longer than the timeout.
+    pub fn from_detectors(timeout: Duration, detectors: Vec<Box<dyn ResourceDetector>>) -> Self {
+        let deadline = Instant::now() + timeout;
+        let receivers = detectors
+            .into_iter()
+            .filter_map(|detector| {
+                let (sender, receiver) = mpsc::channel();
+                thread::Builder::new()
+                    .name("opentelemetry-resource-detector".to_string())
+                    .spawn(move || {
+                        let _ = sender.send(detector.detect(timeout));
+                    })
+                    .ok()
+                    .map(|_| receiver)
+            })
+            .collect::<Vec<_>>();
+
+        receivers
+            .into_iter()
+            .fold(Resource::default(), |resource, receiver| {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                match receiver.recv_timeout(remaining) {
+                    Ok(detected) => resource.merge(&detected),
+                    Err(_) => resource,
+                }
+            })
+    }
+
     /// Create a new `Resource` by combining two resources.
     ///
     /// Keys from this resource have priority over keys from the merged resource.
@@ -104,6 +204,14 @@ impl Resource {
     }
 }
 
+/// Detects information about the entity producing telemetry.
+///
+/// Detectors that fail to detect anything return an empty `Resource`.
+pub trait ResourceDetector: fmt::Debug + Send + Sync {
+    /// Detect the resource, giving up once `timeout` has elapsed.
+    fn detect(&self, timeout: Duration) -> Resource;
+}
+
 /// An owned iterator over the entries of a `Resource`.
 #[derive(Debug)]
 pub struct IntoIter(btree_map::IntoIter<api::Key, api::Value>);
@@ -144,9 +252,9 @@ impl<'a> IntoIterator for &'a Resource {
     }
 }
 
-#[cfg(Test)]
+#[cfg(test)]
 mod tests {
-    use super::Resource;
+    use super::*;
 
     #[test]
     fn new_resource() {
@@ -184,10 +292,72 @@ mod tests {
         expected_attrs.insert(api::Key::new("c"), api::Value::from("c-value"));
 
         assert_eq!(
-            resource_a.merge(resource_b)
+            resource_a.merge(&resource_b),
             Resource {
                 attrs: expected_attrs
             }
         );
     }
+
+    #[derive(Debug)]
+    struct StaticDetector(Duration, Vec<api::KeyValue>);
+
+    impl ResourceDetector for StaticDetector {
+        fn detect(&self, _timeout: Duration) -> Resource {
+            thread::sleep(self.0);
+            Resource::new(self.1.clone())
+        }
+    }
+
+    #[test]
+    fn from_detectors_merges_in_order() {
+        let resource = Resource::from_detectors(
+            Duration::from_secs(5),
+            vec![
+                Box::new(StaticDetector(
+                    Duration::from_millis(20),
+                    vec![api::KeyValue::new("a", "first")],
+                )),
+                Box::new(StaticDetector(
+                    Duration::from_millis(0),
+                    vec![
+                        api::KeyValue::new("a", "second"),
+                        api::KeyValue::new("b", "second"),
+                    ],
+                )),
+            ],
+        );
+
+        assert_eq!(
+            resource,
+            Resource::new(vec![
+                api::KeyValue::new("a", "first"),
+                api::KeyValue::new("b", "second"),
+            ])
+        );
+    }
+
+    #[test]
+    fn from_detectors_skips_slow_detectors() {
+        let started = Instant::now();
+        let resource = Resource::from_detectors(
+            Duration::from_millis(50),
+            vec![
+                Box::new(StaticDetector(
+                    Duration::from_secs(10),
+                    vec![api::KeyValue::new("slow", "true")],
+                )),
+                Box::new(StaticDetector(
+                    Duration::from_millis(0),
+                    vec![api::KeyValue::new("fast", "true")],
+                )),
+            ],
+        );
+
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(
+            resource,
+            Resource::new(vec![api::KeyValue::new("fast", "true")])
+        );
+    }
 }
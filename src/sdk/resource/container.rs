@@ -0,0 +1,84 @@
+//! Detects the container the process runs in.
+use super::{Resource, ResourceDetector};
+use crate::semantic_conventions as semcov;
+use std::fs;
+use std::time::Duration;
+
+/// Detects the id of the container the process runs in, parsed from
+/// `/proc/self/cgroup`. Detects nothing outside of a container or on
+/// platforms without cgroups.
+#[derive(Clone, Debug, Default)]
+pub struct ContainerResourceDetector {
+    _private: (),
+}
+
+impl ContainerResourceDetector {
+    /// Create a new container detector.
+    pub fn new() -> Self {
+        ContainerResourceDetector::default()
+    }
+}
+
+impl ResourceDetector for ContainerResourceDetector {
+    fn detect(&self, _timeout: Duration) -> Resource {
+        fs::read_to_string("/proc/self/cgroup")
+            .ok()
+            .and_then(|cgroup| container_id(&cgroup))
+            .map(|id| Resource::new(vec![semcov::resource::CONTAINER_ID.string(id)]))
+            .unwrap_or_default()
+    }
+}
+
+/// Finds a container id in the contents of a cgroup file, supporting the
+/// path formats of the common container runtimes, e.g.
+/// `/docker/<id>`, `/kubepods/.../<id>` or `/system.slice/docker-<id>.scope`.
+fn container_id(cgroup: &str) -> Option<String> {
+    cgroup.lines().find_map(|line| {
+        let path = line.rsplit(':').next()?;
+        let segment = path.rsplit('/').next()?;
+        let segment = segment.trim_end_matches(".scope");
+        let id = segment.rsplit('-').next()?;
+
+        if id.len() == 64 && id.chars().all(|c| c.is_ascii_hexdigit()) {
+            Some(id.to_string())
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID: &str = "7be92808767a667f35c8505cbf40d14e931ef6db5b0210329cf193b15ba9d605";
+
+    #[test]
+    fn parses_container_ids() {
+        let cases = vec![
+            format!("12:devices:/docker/{}\n1:name=systemd:/docker/{}", ID, ID),
+            format!("0::/system.slice/docker-{}.scope", ID),
+            format!(
+                "11:cpu:/kubepods/besteffort/pod2c48913c-b29f-11e7-9350-020968147796/{}",
+                ID
+            ),
+            format!(
+                "1:name=systemd:/kubepods.slice/kubepods-pod1.slice/cri-containerd-{}.scope",
+                ID
+            ),
+        ];
+
+        for cgroup in cases {
+            assert_eq!(container_id(&cgroup), Some(ID.to_string()), "{}", cgroup);
+        }
+    }
+
+    #[test]
+    fn ignores_hosts_without_containers() {
+        assert_eq!(
+            container_id("0::/user.slice/user-1000.slice/session-2.scope"),
+            None
+        );
+        assert_eq!(container_id(""), None);
+    }
+}
@@ -0,0 +1,28 @@
+//! Detects resource attributes configured through environment variables.
+use super::{Resource, ResourceDetector};
+use std::time::Duration;
+
+/// Detects the resource configured by the `OTEL_RESOURCE_ATTRIBUTES` and
+/// `OTEL_SERVICE_NAME` environment variables.
+///
+/// Malformed variables are ignored, use [`Resource::from_env`] to report
+/// them.
+///
+/// [`Resource::from_env`]: struct.Resource.html#method.from_env
+#[derive(Clone, Debug, Default)]
+pub struct EnvResourceDetector {
+    _private: (),
+}
+
+impl EnvResourceDetector {
+    /// Create a new environment detector.
+    pub fn new() -> Self {
+        EnvResourceDetector::default()
+    }
+}
+
+impl ResourceDetector for EnvResourceDetector {
+    fn detect(&self, _timeout: Duration) -> Resource {
+        Resource::from_env().unwrap_or_default()
+    }
+}
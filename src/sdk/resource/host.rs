@@ -0,0 +1,79 @@
+//! Detects information about the host the process runs on.
+use super::{Resource, ResourceDetector};
+use crate::semantic_conventions as semcov;
+use std::env;
+use std::fs;
+use std::time::Duration;
+
+/// Detects the host name, operating system and CPU architecture.
+///
+/// The host name is read from `/proc/sys/kernel/hostname` where available,
+/// falling back to the `HOSTNAME` and `COMPUTERNAME` environment variables.
+#[derive(Clone, Debug, Default)]
+pub struct HostResourceDetector {
+    _private: (),
+}
+
+impl HostResourceDetector {
+    /// Create a new host detector.
+    pub fn new() -> Self {
+        HostResourceDetector::default()
+    }
+}
+
+impl ResourceDetector for HostResourceDetector {
+    fn detect(&self, _timeout: Duration) -> Resource {
+        let mut attributes = vec![
+            semcov::resource::OS_TYPE.string(os_type(env::consts::OS)),
+            semcov::resource::HOST_ARCH.string(host_arch(env::consts::ARCH)),
+        ];
+        if let Some(name) = host_name() {
+            attributes.push(semcov::resource::HOST_NAME.string(name));
+        }
+
+        Resource::new(attributes)
+    }
+}
+
+fn host_name() -> Option<String> {
+    fs::read_to_string("/proc/sys/kernel/hostname")
+        .ok()
+        .or_else(|| env::var("HOSTNAME").ok())
+        .or_else(|| env::var("COMPUTERNAME").ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+}
+
+/// Converts Rust's OS name to the semantic convention value.
+fn os_type(os: &str) -> &str {
+    match os {
+        "macos" | "ios" => "darwin",
+        other => other,
+    }
+}
+
+/// Converts Rust's architecture name to the semantic convention value.
+fn host_arch(arch: &str) -> &str {
+    match arch {
+        "x86_64" => "amd64",
+        "aarch64" => "arm64",
+        "arm" => "arm32",
+        "powerpc" => "ppc32",
+        "powerpc64" => "ppc64",
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uses_semantic_convention_values() {
+        assert_eq!(os_type("macos"), "darwin");
+        assert_eq!(os_type("linux"), "linux");
+        assert_eq!(host_arch("x86_64"), "amd64");
+        assert_eq!(host_arch("aarch64"), "arm64");
+        assert_eq!(host_arch("s390x"), "s390x");
+    }
+}
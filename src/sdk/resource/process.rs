@@ -0,0 +1,67 @@
+//! Detects information about the running process.
+use super::{Resource, ResourceDetector};
+use crate::semantic_conventions as semcov;
+use std::env;
+use std::process;
+use std::time::Duration;
+
+/// Detects the process id, executable, command line and runtime of the
+/// running process.
+#[derive(Clone, Debug, Default)]
+pub struct ProcessResourceDetector {
+    _private: (),
+}
+
+impl ProcessResourceDetector {
+    /// Create a new process detector.
+    pub fn new() -> Self {
+        ProcessResourceDetector::default()
+    }
+}
+
+impl ResourceDetector for ProcessResourceDetector {
+    fn detect(&self, _timeout: Duration) -> Resource {
+        let mut attributes = vec![
+            semcov::resource::PROCESS_PID.i64(i64::from(process::id())),
+            semcov::resource::PROCESS_COMMAND_LINE.string(
+                env::args_os()
+                    .map(|arg| arg.to_string_lossy().into_owned())
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            ),
+            semcov::resource::PROCESS_RUNTIME_NAME.string("rustc"),
+        ];
+
+        if let Ok(executable) = env::current_exe() {
+            if let Some(name) = executable.file_name() {
+                attributes
+                    .push(semcov::resource::PROCESS_EXECUTABLE_NAME.string(name.to_string_lossy()));
+            }
+            attributes.push(
+                semcov::resource::PROCESS_EXECUTABLE_PATH.string(executable.to_string_lossy()),
+            );
+        }
+
+        Resource::new(attributes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api;
+
+    #[test]
+    fn detects_current_process() {
+        let resource = ProcessResourceDetector::new().detect(Duration::from_secs(0));
+        let pid = resource
+            .iter()
+            .find(|(key, _)| **key == semcov::resource::PROCESS_PID)
+            .map(|(_, value)| value.clone());
+
+        assert_eq!(pid, Some(api::Value::I64(i64::from(process::id()))));
+        assert!(resource
+            .iter()
+            .any(|(key, _)| *key == semcov::resource::PROCESS_EXECUTABLE_PATH));
+    }
+}
@@ -2,9 +2,21 @@
 //!
 //! Configuration represents the global tracing configuration, overrides
 //! can be set for the default OpenTelemetry limits and Sampler.
+use crate::sdk::env::{self, EnvError};
 use crate::{api, sdk};
 use std::sync::Arc;
 
+/// Environment variable selecting the sampler.
+const OTEL_TRACES_SAMPLER: &str = "OTEL_TRACES_SAMPLER";
+/// Environment variable with the argument of the sampler.
+const OTEL_TRACES_SAMPLER_ARG: &str = "OTEL_TRACES_SAMPLER_ARG";
+/// Environment variable with the max attributes per span.
+const OTEL_SPAN_ATTRIBUTE_COUNT_LIMIT: &str = "OTEL_SPAN_ATTRIBUTE_COUNT_LIMIT";
+/// Environment variable with the max events per span.
+const OTEL_SPAN_EVENT_COUNT_LIMIT: &str = "OTEL_SPAN_EVENT_COUNT_LIMIT";
+/// Environment variable with the max links per span.
+const OTEL_SPAN_LINK_COUNT_LIMIT: &str = "OTEL_SPAN_LINK_COUNT_LIMIT";
+
 /// Tracer configuration
 #[derive(Debug)]
 pub struct Config {
@@ -35,3 +47,174 @@ impl Default for Config {
         }
     }
 }
+
+impl Config {
+    /// Create a configuration from the standard environment variables, using
+    /// the defaults for variables that are not set.
+    ///
+    /// The following variables are supported:
+    ///
+    /// - `OTEL_TRACES_SAMPLER`: one of `always_on`, `always_off`,
+    ///   `traceidratio`, `parentbased_always_on`, `parentbased_always_off` or
+    ///   `parentbased_traceidratio`.
+    /// - `OTEL_TRACES_SAMPLER_ARG`: the sampling probability of the ratio
+    ///   samplers, between 0 and 1. Defaults to 1.
+    /// - `OTEL_SPAN_ATTRIBUTE_COUNT_LIMIT`, `OTEL_SPAN_EVENT_COUNT_LIMIT` and
+    ///   `OTEL_SPAN_LINK_COUNT_LIMIT`: the span limits.
+    /// - `OTEL_RESOURCE_ATTRIBUTES` and `OTEL_SERVICE_NAME`: see
+    ///   [`Resource::from_env`].
+    ///
+    /// [`Resource::from_env`]: ../../resource/struct.Resource.html#method.from_env
+    pub fn from_env() -> Result<Self, EnvError> {
+        let mut config = Config::default();
+
+        if let Some(sampler) = sampler_from_env()? {
+            config.default_sampler = Box::new(sampler);
+        }
+        if let Some(limit) = env::parse(OTEL_SPAN_ATTRIBUTE_COUNT_LIMIT)? {
+            config.max_attributes_per_span = limit;
+        }
+        if let Some(limit) = env::parse(OTEL_SPAN_EVENT_COUNT_LIMIT)? {
+            config.max_events_per_span = limit;
+        }
+        if let Some(limit) = env::parse(OTEL_SPAN_LINK_COUNT_LIMIT)? {
+            config.max_links_per_span = limit;
+        }
+        config.resource = Arc::new(sdk::Resource::from_env()?);
+
+        Ok(config)
+    }
+}
+
+/// The sampler configured by `OTEL_TRACES_SAMPLER`, if any.
+fn sampler_from_env() -> Result<Option<sdk::Sampler>, EnvError> {
+    let name = match env::var(OTEL_TRACES_SAMPLER)? {
+        Some(name) => name,
+        None => return Ok(None),
+    };
+    let ratio = || -> Result<f64, EnvError> {
+        match env::parse::<f64>(OTEL_TRACES_SAMPLER_ARG)? {
+            Some(ratio) if (0.0..=1.0).contains(&ratio) => Ok(ratio),
+            Some(ratio) => Err(EnvError::invalid_value(
+                OTEL_TRACES_SAMPLER_ARG,
+                &ratio.to_string(),
+                "sampling ratio must be between 0 and 1",
+            )),
+            None => Ok(1.0),
+        }
+    };
+
+    let sampler = match name.as_str() {
+        "always_on" => sdk::Sampler::AlwaysOn,
+        "always_off" => sdk::Sampler::AlwaysOff,
+        "traceidratio" => sdk::Sampler::Probability(ratio()?),
+        "parentbased_always_on" => sdk::Sampler::ParentOrElse(Box::new(sdk::Sampler::AlwaysOn)),
+        "parentbased_always_off" => sdk::Sampler::ParentOrElse(Box::new(sdk::Sampler::AlwaysOff)),
+        "parentbased_traceidratio" => {
+            sdk::Sampler::ParentOrElse(Box::new(sdk::Sampler::Probability(ratio()?)))
+        }
+        _ => {
+            return Err(EnvError::invalid_value(
+                OTEL_TRACES_SAMPLER,
+                &name,
+                "unsupported sampler",
+            ))
+        }
+    };
+
+    Ok(Some(sampler))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env::{remove_var, set_var};
+
+    const VARS: &[&str] = &[
+        OTEL_TRACES_SAMPLER,
+        OTEL_TRACES_SAMPLER_ARG,
+        OTEL_SPAN_ATTRIBUTE_COUNT_LIMIT,
+        OTEL_SPAN_EVENT_COUNT_LIMIT,
+        OTEL_SPAN_LINK_COUNT_LIMIT,
+        "OTEL_RESOURCE_ATTRIBUTES",
+        "OTEL_SERVICE_NAME",
+    ];
+
+    fn clear_env() {
+        for var in VARS {
+            remove_var(var);
+        }
+    }
+
+    #[test]
+    fn config_from_env() {
+        let _guard = env::lock_for_test();
+        clear_env();
+        set_var(OTEL_TRACES_SAMPLER, "parentbased_traceidratio");
+        set_var(OTEL_TRACES_SAMPLER_ARG, "0.25");
+        set_var(OTEL_SPAN_ATTRIBUTE_COUNT_LIMIT, "64");
+        set_var(OTEL_SPAN_LINK_COUNT_LIMIT, "");
+        set_var(
+            "OTEL_RESOURCE_ATTRIBUTES",
+            "service.name=ignored, host.name=web%201,k8s.pod.name=",
+        );
+        set_var("OTEL_SERVICE_NAME", "checkout");
+
+        let config = Config::from_env().unwrap();
+        clear_env();
+
+        assert_eq!(
+            format!("{:?}", config.default_sampler),
+            "ParentOrElse(Probability(0.25))"
+        );
+        assert_eq!(config.max_attributes_per_span, 64);
+        assert_eq!(config.max_events_per_span, 128);
+        assert_eq!(config.max_links_per_span, 32);
+        assert_eq!(
+            *config.resource,
+            sdk::Resource::new(vec![
+                api::Key::new("service.name").string("checkout"),
+                api::Key::new("host.name").string("web 1"),
+                api::Key::new("k8s.pod.name").string(""),
+            ])
+        );
+    }
+
+    #[test]
+    fn reports_malformed_values() {
+        let _guard = env::lock_for_test();
+        let cases = vec![
+            (
+                OTEL_TRACES_SAMPLER,
+                "sometimes",
+                "invalid value \"sometimes\" for OTEL_TRACES_SAMPLER: unsupported sampler",
+            ),
+            (
+                OTEL_SPAN_EVENT_COUNT_LIMIT,
+                "-1",
+                "invalid value \"-1\" for OTEL_SPAN_EVENT_COUNT_LIMIT: invalid digit found in string",
+            ),
+            (
+                "OTEL_RESOURCE_ATTRIBUTES",
+                "a=b,c",
+                "invalid value \"a=b,c\" for OTEL_RESOURCE_ATTRIBUTES: \"c\" is not a key=value pair",
+            ),
+        ];
+
+        for (var, value, message) in cases {
+            clear_env();
+            set_var(var, value);
+            let err = Config::from_env().unwrap_err();
+            assert_eq!(err.to_string(), message);
+        }
+
+        clear_env();
+        set_var(OTEL_TRACES_SAMPLER, "traceidratio");
+        set_var(OTEL_TRACES_SAMPLER_ARG, "1.5");
+        assert_eq!(
+            Config::from_env().unwrap_err().to_string(),
+            "invalid value \"1.5\" for OTEL_TRACES_SAMPLER_ARG: sampling ratio must be between 0 and 1"
+        );
+        clear_env();
+    }
+}
@@ -47,6 +47,11 @@ impl EvictedHashMap {
         }
     }
 
+    /// Returns the number of attributes dropped because the map was at capacity.
+    pub fn dropped_count(&self) -> u32 {
+        self.dropped_count
+    }
+
     /// Returns the number of elements in the map.
     pub fn len(&self) -> usize {
         self.map.len()
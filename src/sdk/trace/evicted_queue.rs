@@ -50,6 +50,11 @@ impl<T> EvictedQueue<T> {
         self.queue.iter()
     }
 
+    /// Returns the number of elements dropped because the queue was at capacity.
+    pub fn dropped_count(&self) -> u32 {
+        self.dropped_count
+    }
+
     /// Returns the number of elements in the `EvictedQueue`.
     pub fn len(&self) -> usize {
         self.queue.len()
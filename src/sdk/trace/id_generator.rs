@@ -2,6 +2,7 @@
 use crate::api;
 use rand::{rngs, Rng};
 use std::cell::RefCell;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Generates Trace and Span ids
 #[derive(Clone, Debug, Default)]
@@ -21,7 +22,66 @@ impl api::IdGenerator for IdGenerator {
     }
 }
 
+/// Generates AWS X-Ray compatible Trace and Span ids
+///
+/// X-Ray rejects trace ids that don't start with the trace start time, so the
+/// high 32 bits of the trace ids are the current epoch seconds and the
+/// remaining 96 bits are random. Span ids are random. See the
+/// [`XrayPropagator`] to propagate these ids in the X-Ray header format.
+///
+/// [`XrayPropagator`]: ../../../api/trace/xray_propagator/struct.XrayPropagator.html
+#[derive(Clone, Debug, Default)]
+pub struct XrayIdGenerator {
+    _private: (),
+}
+
+impl api::IdGenerator for XrayIdGenerator {
+    /// Generate new `TraceId` from the current time and thread local rng
+    fn new_trace_id(&self) -> api::TraceId {
+        let epoch_seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default() as u32;
+        let random = CURRENT_RNG.with(|rng| rng.borrow_mut().gen::<u128>());
+
+        api::TraceId::from_u128(u128::from(epoch_seconds) << 96 | random >> 32)
+    }
+
+    /// Generate new `SpanId` using thread local rng
+    fn new_span_id(&self) -> api::SpanId {
+        CURRENT_RNG.with(|rng| api::SpanId::from_u64(rng.borrow_mut().gen()))
+    }
+}
+
 thread_local! {
     /// Store random number generator for each thread
     static CURRENT_RNG: RefCell<rngs::ThreadRng> = RefCell::new(rngs::ThreadRng::default());
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::IdGenerator as _;
+
+    #[test]
+    fn xray_trace_ids_start_with_epoch_seconds() {
+        let before = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let generator = XrayIdGenerator::default();
+        let first = generator.new_trace_id().to_u128();
+        let second = generator.new_trace_id().to_u128();
+        let after = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        for trace_id in &[first, second] {
+            let epoch_seconds = u64::from((trace_id >> 96) as u32);
+            assert!(before <= epoch_seconds && epoch_seconds <= after);
+        }
+        assert_ne!(first, second);
+        assert_ne!(generator.new_span_id(), api::SpanId::invalid());
+    }
+}
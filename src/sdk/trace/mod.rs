@@ -15,3 +15,4 @@ pub mod sampler;
 pub mod span;
 pub mod span_processor;
 pub mod tracer;
+pub mod zpages;
@@ -92,6 +92,15 @@ impl api::Provider for Provider {
 
         new_tracer
     }
+
+    /// Flush all span processors, in the order they were registered. Each
+    /// processor blocks until its buffered spans are exported or its flush
+    /// timeout elapses.
+    fn force_flush(&self) {
+        for processor in self.span_processors() {
+            processor.force_flush();
+        }
+    }
 }
 
 /// Builder for provider attributes.
@@ -118,6 +127,15 @@ impl Builder {
         Builder { processors, ..self }
     }
 
+    /// Add a custom `SpanProcessor`. Processors receive all recording spans,
+    /// including spans that are recorded but not sampled.
+    pub fn with_span_processor<T: api::SpanProcessor + 'static>(self, processor: T) -> Self {
+        let mut processors = self.processors;
+        processors.push(Box::new(processor));
+
+        Builder { processors, ..self }
+    }
+
     /// The sdk `Config` that this provider will use.
     pub fn with_config(self, config: sdk::Config) -> Self {
         Builder { config, ..self }
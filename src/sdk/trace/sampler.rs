@@ -1,5 +1,9 @@
 //! # Sampler
 use crate::api;
+use crate::semantic_conventions as semcov;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
 
 /// Sampling options
 #[derive(Clone, Debug)]
@@ -14,6 +18,19 @@ pub enum Sampler {
     /// sampled, then it's child spans will automatically be sampled. Fractions < 0 are treated as
     /// zero, but spans may still be sampled if their parent is.
     Probability(f64),
+    /// Delegates to the sampler of the first rule matching the span, or to
+    /// the fallback sampler if no rule matches. See [`SamplingRule`].
+    ///
+    /// [`SamplingRule`]: struct.SamplingRule.html
+    RuleBased(Vec<SamplingRule>, Box<Sampler>),
+    /// Sample at most a given number of traces per second, see
+    /// [`RateLimiter`]. Spans with a sampled parent are always sampled and
+    /// do not count towards the limit. Spans sampled by the limiter get the
+    /// `sampler.type` attribute set to `ratelimiting` and `sampler.param` set
+    /// to the traces per second.
+    ///
+    /// [`RateLimiter`]: struct.RateLimiter.html
+    RateLimiting(RateLimiter),
 }
 
 impl api::Sampler for Sampler {
@@ -60,6 +77,37 @@ impl api::Sampler for Sampler {
                     }
                 }
             }
+            // The decision of the first matching rule, or the fallback sampler
+            Sampler::RuleBased(rules, fallback) => {
+                let sampler = rules
+                    .iter()
+                    .find(|rule| rule.matches(name, span_kind, attributes))
+                    .map_or(fallback.as_ref(), |rule| &rule.sampler);
+
+                return sampler.should_sample(
+                    parent_context,
+                    trace_id,
+                    name,
+                    span_kind,
+                    attributes,
+                    links,
+                );
+            }
+            // Follow a sampled parent, otherwise sample while the rate limiter has
+            // capacity left
+            Sampler::RateLimiting(limiter) => match parent_context {
+                Some(ctx) if ctx.is_sampled() => api::SamplingDecision::RecordAndSampled,
+                _ if limiter.try_acquire() => {
+                    return api::SamplingResult {
+                        decision: api::SamplingDecision::RecordAndSampled,
+                        attributes: vec![
+                            semcov::trace::SAMPLER_TYPE.string("ratelimiting"),
+                            semcov::trace::SAMPLER_PARAM.f64(limiter.traces_per_second),
+                        ],
+                    }
+                }
+                _ => api::SamplingDecision::NotRecord,
+            },
         };
 
         api::SamplingResult {
@@ -70,10 +118,217 @@ impl api::Sampler for Sampler {
     }
 }
 
+/// A rule of a `Sampler::RuleBased` sampler, selecting the sampler for the
+/// spans it matches.
+///
+/// A rule matches spans that satisfy all of its conditions, a rule without
+/// conditions matches all spans.
+///
+/// ```
+/// use opentelemetry::api::SpanKind;
+/// use opentelemetry::sdk::{Sampler, SamplingRule};
+/// use opentelemetry::semantic_conventions as semcov;
+///
+/// // Never sample health checks, sample all checkout requests and a tenth
+/// // of the remaining spans.
+/// let sampler = Sampler::RuleBased(
+///     vec![
+///         SamplingRule::new(Sampler::AlwaysOff).with_name_glob("*/health*"),
+///         SamplingRule::new(Sampler::AlwaysOn)
+///             .with_span_kind(SpanKind::Server)
+///             .with_attribute(semcov::trace::HTTP_ROUTE.string("/checkout")),
+///     ],
+///     Box::new(Sampler::Probability(0.1)),
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct SamplingRule {
+    name: Option<NameMatcher>,
+    span_kind: Option<api::SpanKind>,
+    attributes: Vec<api::KeyValue>,
+    sampler: Sampler,
+}
+
+/// Matches span names.
+#[derive(Clone, Debug)]
+enum NameMatcher {
+    Glob(String),
+    #[cfg(feature = "regex")]
+    Regex(regex::Regex),
+}
+
+impl SamplingRule {
+    /// Create a rule delegating to the given sampler.
+    pub fn new(sampler: Sampler) -> Self {
+        SamplingRule {
+            name: None,
+            span_kind: None,
+            attributes: Vec::new(),
+            sampler,
+        }
+    }
+
+    /// Only match spans whose name matches the glob pattern. `*` matches any
+    /// sequence of characters and `?` matches any single character.
+    pub fn with_name_glob<T: Into<String>>(self, pattern: T) -> Self {
+        SamplingRule {
+            name: Some(NameMatcher::Glob(pattern.into())),
+            ..self
+        }
+    }
+
+    /// Only match spans whose name matches the regular expression.
+    #[cfg(feature = "regex")]
+    pub fn with_name_regex(self, regex: regex::Regex) -> Self {
+        SamplingRule {
+            name: Some(NameMatcher::Regex(regex)),
+            ..self
+        }
+    }
+
+    /// Only match spans of the given kind.
+    pub fn with_span_kind(self, span_kind: api::SpanKind) -> Self {
+        SamplingRule {
+            span_kind: Some(span_kind),
+            ..self
+        }
+    }
+
+    /// Only match spans created with the given attribute. Can be called
+    /// multiple times to require several attributes.
+    pub fn with_attribute(mut self, attribute: api::KeyValue) -> Self {
+        self.attributes.push(attribute);
+        self
+    }
+
+    fn matches(&self, name: &str, span_kind: &api::SpanKind, attributes: &[api::KeyValue]) -> bool {
+        let name_matches = match &self.name {
+            Some(NameMatcher::Glob(pattern)) => glob_matches(pattern, name),
+            #[cfg(feature = "regex")]
+            Some(NameMatcher::Regex(regex)) => regex.is_match(name),
+            None => true,
+        };
+
+        name_matches
+            && self
+                .span_kind
+                .as_ref()
+                .map_or(true, |kind| kind == span_kind)
+            && self
+                .attributes
+                .iter()
+                .all(|attribute| attributes.contains(attribute))
+    }
+}
+
+/// Matches `text` against a glob pattern supporting `*` and `?` wildcards.
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let text = text.chars().collect::<Vec<_>>();
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` in the pattern and the text position it was
+    // matched at, to backtrack to when the rest of the pattern fails.
+    let mut star = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// A token bucket allowing a given number of traces per second on average,
+/// and bursts of up to `burst` traces at once.
+///
+/// The bucket state is a single atomic, so checking it is lock free. Clones
+/// share the same bucket.
+///
+/// ```
+/// use opentelemetry::sdk::{RateLimiter, Sampler};
+///
+/// // Sample at most 100 traces per second, up to 10 at once.
+/// let sampler = Sampler::RateLimiting(RateLimiter::new(100.0, 10));
+/// ```
+#[derive(Clone, Debug)]
+pub struct RateLimiter {
+    traces_per_second: f64,
+    /// Nanoseconds between two traces at the configured rate.
+    interval: u64,
+    /// Nanoseconds of traces that may be sampled ahead of time.
+    burst_interval: u64,
+    start: Instant,
+    /// Nanoseconds since `start` at which the bucket will be full again.
+    full_at: Arc<AtomicU64>,
+}
+
+impl RateLimiter {
+    /// Create a rate limiter allowing `traces_per_second` traces per second
+    /// and bursts of up to `burst` traces. A burst of zero is treated as one,
+    /// a rate of zero or less never samples.
+    pub fn new(traces_per_second: f64, burst: u32) -> Self {
+        let interval = if traces_per_second > 0.0 {
+            (1_000_000_000.0 / traces_per_second).max(1.0) as u64
+        } else {
+            u64::MAX
+        };
+
+        RateLimiter {
+            traces_per_second: traces_per_second.max(0.0),
+            interval,
+            burst_interval: interval.saturating_mul(u64::from(burst.max(1))),
+            start: Instant::now(),
+            full_at: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// The configured number of traces per second.
+    pub fn traces_per_second(&self) -> f64 {
+        self.traces_per_second
+    }
+
+    /// Takes a token from the bucket, returning `false` if it is empty.
+    fn try_acquire(&self) -> bool {
+        if self.interval == u64::MAX {
+            return false;
+        }
+
+        let now = self.start.elapsed().as_nanos() as u64;
+        let mut full_at = self.full_at.load(Ordering::Relaxed);
+        loop {
+            let next = full_at.max(now).saturating_add(self.interval);
+            if next - now > self.burst_interval {
+                return false;
+            }
+            match self.full_at.compare_exchange_weak(
+                full_at,
+                next,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return true,
+                Err(current) => full_at = current,
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::glob_matches;
     use crate::api::{self, Sampler as _};
-    use crate::sdk::Sampler;
+    use crate::sdk::{RateLimiter, Sampler, SamplingRule};
     use rand::Rng;
 
     #[rustfmt::skip]
@@ -140,6 +395,7 @@ mod tests {
                         api::SpanId::from_u64(1),
                         trace_flags,
                         false,
+                        api::TraceState::default(),
                     ))
                 } else {
                     None
@@ -181,4 +437,141 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn glob_patterns() {
+        assert!(glob_matches("*", ""));
+        assert!(glob_matches("GET /health*", "GET /healthz"));
+        assert!(glob_matches("*/health", "GET /health"));
+        assert!(glob_matches("a*b*c", "aXbYbZc"));
+        assert!(glob_matches("span-?", "span-1"));
+        assert!(!glob_matches("span-?", "span-10"));
+        assert!(!glob_matches("*/health", "GET /healthz"));
+        assert!(!glob_matches("", "span"));
+    }
+
+    #[test]
+    fn rule_based_sampling() {
+        let sampler = Sampler::RuleBased(
+            vec![
+                SamplingRule::new(Sampler::AlwaysOff).with_name_glob("*/health"),
+                SamplingRule::new(Sampler::AlwaysOn)
+                    .with_span_kind(api::SpanKind::Server)
+                    .with_attribute(api::Key::new("http.route").string("/checkout")),
+            ],
+            Box::new(Sampler::AlwaysOff),
+        );
+        let sampled = |name: &str, kind: api::SpanKind, attributes: &[api::KeyValue]| {
+            sampler
+                .should_sample(
+                    None,
+                    api::TraceId::from_u128(1),
+                    name,
+                    &kind,
+                    attributes,
+                    &[],
+                )
+                .decision
+                == api::SamplingDecision::RecordAndSampled
+        };
+        let checkout = vec![
+            api::Key::new("http.method").string("POST"),
+            api::Key::new("http.route").string("/checkout"),
+        ];
+
+        assert!(sampled("POST /checkout", api::SpanKind::Server, &checkout));
+        assert!(!sampled("GET /health", api::SpanKind::Server, &checkout));
+        assert!(!sampled("POST /checkout", api::SpanKind::Client, &checkout));
+        assert!(!sampled("POST /checkout", api::SpanKind::Server, &[]));
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn rules_match_regex() {
+        let rule = SamplingRule::new(Sampler::AlwaysOn)
+            .with_name_regex(regex::Regex::new("^(GET|HEAD) /static/").unwrap());
+
+        assert!(rule.matches("GET /static/app.js", &api::SpanKind::Server, &[]));
+        assert!(!rule.matches("POST /static/app.js", &api::SpanKind::Server, &[]));
+    }
+
+    #[test]
+    fn rate_limiting_allows_bursts() {
+        // A rate low enough that no tokens are refilled during the test.
+        let sampler = Sampler::RateLimiting(RateLimiter::new(0.001, 3));
+        let results = (0..5)
+            .map(|i| {
+                sampler.should_sample(
+                    None,
+                    api::TraceId::from_u128(i),
+                    "span",
+                    &api::SpanKind::Internal,
+                    &[],
+                    &[],
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let sampled = results
+            .iter()
+            .filter(|result| result.decision == api::SamplingDecision::RecordAndSampled)
+            .count();
+        assert_eq!(sampled, 3);
+        assert_eq!(
+            results[0].attributes,
+            vec![
+                api::Key::new("sampler.type").string("ratelimiting"),
+                api::Key::new("sampler.param").f64(0.001),
+            ]
+        );
+        assert!(results[4].attributes.is_empty());
+    }
+
+    #[test]
+    fn rate_limiting_follows_sampled_parents() {
+        let sampler = Sampler::RateLimiting(RateLimiter::new(0.001, 1));
+        let parent = api::SpanContext::new(
+            api::TraceId::from_u128(1),
+            api::SpanId::from_u64(1),
+            api::TRACE_FLAG_SAMPLED,
+            true,
+            api::TraceState::default(),
+        );
+        let sample = |parent_context| {
+            sampler
+                .should_sample(
+                    parent_context,
+                    api::TraceId::from_u128(1),
+                    "span",
+                    &api::SpanKind::Server,
+                    &[],
+                    &[],
+                )
+                .decision
+        };
+
+        for _ in 0..3 {
+            assert_eq!(
+                sample(Some(&parent)),
+                api::SamplingDecision::RecordAndSampled
+            );
+        }
+        assert_eq!(sample(None), api::SamplingDecision::RecordAndSampled);
+        assert_eq!(sample(None), api::SamplingDecision::NotRecord);
+    }
+
+    #[test]
+    fn rate_limiting_refills() {
+        let limiter = RateLimiter::new(1.0, 2);
+        assert!(limiter.try_acquire());
+        assert!(limiter.try_acquire());
+        assert!(!limiter.try_acquire());
+
+        // One trace every 50ms, so a token is back well before the sleep ends.
+        let limiter = RateLimiter::new(20.0, 1);
+        assert!(limiter.try_acquire());
+        std::thread::sleep(std::time::Duration::from_millis(500));
+        assert!(limiter.try_acquire());
+        assert!(!RateLimiter::new(0.0, 10).try_acquire());
+    }
 }
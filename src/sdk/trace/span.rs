@@ -86,7 +86,13 @@ impl api::Span for Span {
     fn span_context(&self) -> api::SpanContext {
         self.with_data(|data| data.span_context.clone())
             .unwrap_or_else(|| {
-                api::SpanContext::new(api::TraceId::invalid(), api::SpanId::invalid(), 0, false)
+                api::SpanContext::new(
+                    api::TraceId::invalid(),
+                    api::SpanId::invalid(),
+                    0,
+                    false,
+                    api::TraceState::default(),
+                )
             })
     }
 
@@ -22,7 +22,7 @@
 //! |     | Span.end()   |
 //! |     |              |   +---------------------+
 //! |     |              |   |                     |
-//! |     |              +---> ZPagesProcessor     |
+//! |     |              +---> ZPagesSpanProcessor |
 //! |     |              |   |                     |
 //! +-----+--------------+   +---------------------+
 //! ```
@@ -81,26 +81,73 @@
 //! }
 //! ```
 //!
+//! #### Batching with blocking exporters:
+//!
+//! [`SpanExporter::export`] is synchronous, so exporters that send spans over
+//! the network block the worker until the request completes or times out. On a
+//! tokio runtime, run the worker on the blocking thread pool so exports do not
+//! stall the executor threads. The worker is a future, so it has to be driven
+//! by a local executor inside the blocking task:
+//!
+//! ```
+//! use opentelemetry::{api, sdk, global};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     // Configure an exporter that blocks on export
+//!     let exporter = api::NoopSpanExporter {};
+//!
+//!     let batch = sdk::BatchSpanProcessor::builder(
+//!         exporter,
+//!         |worker| tokio::task::spawn_blocking(move || futures::executor::block_on(worker)),
+//!         tokio::time::interval,
+//!     )
+//!     .build();
+//!
+//!     let provider = sdk::Provider::builder()
+//!         .with_batch_exporter(batch)
+//!         .build();
+//!
+//!     global::set_provider(provider);
+//! }
+//! ```
+//!
 //! [`is_recording`]: ../../../api/trace/span/trait.Span.html#tymethod.is_recording
 //! [`Provider`]: ../../../api/trace/provider/trait.Provider.html
 //! [`Tracer`]: ../../../api/trace/tracer/trait.Tracer.html
 //! [`SpanProcessor`]: ../../../api/trace/span_processor/trait.SpanProcessor.html
+//! [`SpanExporter::export`]: ../../../exporter/trace/trait.SpanExporter.html#tymethod.export
 //! [`SimpleSpanProcessor`]: struct.SimpleSpanProcessor.html
 //! [`BatchSpanProcessor`]: struct.BatchSpanProcessor.html
+//! [`ZPagesSpanProcessor`]: ../zpages/struct.ZPagesSpanProcessor.html
 //! [`executor`]: https://docs.rs/futures/0.3.4/futures/executor/index.html
 //! [`tokio`]: https://tokio.rs
 //! [`async-std`]: https://async.rs
+use crate::sdk::env::{self, EnvError};
 use crate::{api, exporter};
 use futures::{
-    channel::mpsc,
-    task::{Context, Poll},
-    Future, Stream, StreamExt,
+    channel::{mpsc, oneshot},
+    task::{ArcWake, Context, Poll},
+    Future, FutureExt, Stream, StreamExt,
 };
+use std::collections::VecDeque;
+use std::fmt;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time;
 
+/// Environment variable with the max queue size.
+const OTEL_BSP_MAX_QUEUE_SIZE: &str = "OTEL_BSP_MAX_QUEUE_SIZE";
+/// Environment variable with the scheduled delay in milliseconds.
+const OTEL_BSP_SCHEDULE_DELAY: &str = "OTEL_BSP_SCHEDULE_DELAY";
+/// Environment variable with the max export batch size.
+const OTEL_BSP_MAX_EXPORT_BATCH_SIZE: &str = "OTEL_BSP_MAX_EXPORT_BATCH_SIZE";
+/// Shortest delay used for retry timers, interval streams reject a zero period.
+const MIN_RETRY_DELAY: time::Duration = time::Duration::from_millis(1);
+
 /// A [`SpanProcessor`] that exports synchronously when spans are finished.
+/// Spans that are recorded but not sampled are not exported.
 ///
 /// [`SpanProcessor`]: ../../../api/trace/span_processor/trait.SpanProcessor.html
 #[derive(Debug)]
@@ -128,15 +175,30 @@ impl api::SpanProcessor for SimpleSpanProcessor {
     fn shutdown(&self) {
         self.exporter.shutdown();
     }
+
+    fn force_flush(&self) {
+        // Spans are exported as they end, only the exporter may still buffer.
+        self.exporter.force_flush();
+    }
 }
 
 /// A [`SpanProcessor`] that asynchronously buffers finished spans and reports
-/// them at a preconfigured interval.
+/// them at a preconfigured interval. Spans that are recorded but not sampled
+/// are not exported.
+///
+/// On shutdown all buffered spans are exported before the exporter is shut
+/// down. `shutdown` blocks the calling thread until the worker finishes, so if
+/// the worker runs on a single threaded executor shared with the caller, use
+/// [`shutdown_async`] instead.
 ///
+/// [`shutdown_async`]: struct.BatchSpanProcessor.html#method.shutdown_async
 /// [`SpanProcessor`]: ../../../api/trace/span_processor/trait.SpanProcessor.html
 #[derive(Debug)]
 pub struct BatchSpanProcessor {
     message_sender: Mutex<mpsc::Sender<BatchMessage>>,
+    counters: Arc<SpanCounters>,
+    flush_timeout: time::Duration,
+    shutdown_timeout: time::Duration,
 }
 
 impl api::SpanProcessor for BatchSpanProcessor {
@@ -145,14 +207,34 @@ impl api::SpanProcessor for BatchSpanProcessor {
     }
 
     fn on_end(&self, span: Arc<exporter::trace::SpanData>) {
-        if let Ok(mut sender) = self.message_sender.try_lock() {
-            let _ = sender.try_send(BatchMessage::ExportSpan(span));
+        // Spans that are recorded but not sampled are not exported
+        if !span.span_context.is_sampled() {
+            return;
+        }
+
+        let mut sender = match self.message_sender.try_lock() {
+            Ok(sender) => sender,
+            Err(_) => return self.counters.dropped(SpanDropReason::LockContended, 1),
+        };
+
+        match sender.try_send(BatchMessage::ExportSpan(span)) {
+            Ok(()) => self.counters.enqueued(1),
+            Err(err) if err.is_full() => self.counters.dropped(SpanDropReason::ChannelFull, 1),
+            Err(_) => self.counters.dropped(SpanDropReason::Shutdown, 1),
         }
     }
 
+    /// Exports all buffered spans and shuts down the exporter, blocking until
+    /// the worker has finished or the shutdown timeout elapses.
     fn shutdown(&self) {
-        if let Ok(mut sender) = self.message_sender.try_lock() {
-            let _ = sender.try_send(BatchMessage::Shutdown);
+        if let Some(finished) = self.send_and_notify(BatchMessage::Shutdown) {
+            wait_timeout(finished, self.shutdown_timeout);
+        }
+    }
+
+    fn force_flush(&self) {
+        if let Some(flushed) = self.send_and_notify(BatchMessage::Flush) {
+            wait_timeout(flushed, self.flush_timeout);
         }
     }
 }
@@ -162,12 +244,157 @@ impl api::SpanProcessor for BatchSpanProcessor {
 /// This process is implemented as a [`Future`] that returns when the accompanying
 /// [`BatchSpanProcessor`] is shut down, and allows systems like [`tokio`] and [`async-std`] to
 /// process the work in the background without requiring dedicated system threads.
+///
+/// Batches rejected with `ExportResult::FailedRetryable` are kept and exported
+/// again with exponential backoff until they succeed, the maximum number of
+/// attempts is reached, or the maximum elapsed time has passed. Spans waiting
+/// to be retried count towards the maximum queue size. On shutdown every
+/// pending batch gets one final export attempt.
 #[allow(missing_debug_implementations)]
 pub struct BatchSpanProcessorWorker {
     exporter: Box<dyn exporter::trace::SpanExporter>,
     messages: Pin<Box<dyn Stream<Item = BatchMessage> + Send>>,
+    interval: Box<dyn Fn(time::Duration) -> RetryTimer + Send>,
     config: BatchConfig,
     buffer: Vec<Arc<exporter::trace::SpanData>>,
+    retries: VecDeque<RetryBatch>,
+    retry_timer: Option<RetryTimer>,
+    counters: Arc<SpanCounters>,
+}
+
+/// Stream used to wake the worker once the next retry is due.
+type RetryTimer = Pin<Box<dyn Stream<Item = ()> + Send>>;
+
+/// A batch that failed with a retryable error and is waiting to be exported again.
+#[derive(Debug)]
+struct RetryBatch {
+    spans: Vec<Arc<exporter::trace::SpanData>>,
+    attempts: usize,
+    first_attempt: time::Instant,
+    next_attempt: time::Instant,
+}
+
+impl BatchSpanProcessorWorker {
+    /// Number of spans currently held by the worker, including pending retries.
+    fn queued_spans(&self) -> usize {
+        self.buffer.len()
+            + self
+                .retries
+                .iter()
+                .map(|batch| batch.spans.len())
+                .sum::<usize>()
+    }
+
+    /// Export all buffered spans in batches of at most `max_export_batch_size`.
+    fn export_buffer(&mut self) {
+        let mut spans = std::mem::take(&mut self.buffer);
+        while !spans.is_empty() {
+            let batch_idx = spans
+                .len()
+                .saturating_sub(self.config.max_export_batch_size);
+            let batch = spans.split_off(batch_idx);
+            self.export_batch(batch, 0, time::Instant::now());
+        }
+    }
+
+    /// Export a batch, scheduling it for another attempt if the failure is retryable.
+    fn export_batch(
+        &mut self,
+        spans: Vec<Arc<exporter::trace::SpanData>>,
+        attempts: usize,
+        first_attempt: time::Instant,
+    ) {
+        let span_count = spans.len() as u64;
+        match self.exporter.export(spans.clone()) {
+            exporter::trace::ExportResult::Success => return self.counters.exported(span_count),
+            exporter::trace::ExportResult::FailedNotRetryable => {
+                self.counters.export_failed(span_count);
+                return self
+                    .counters
+                    .dropped(SpanDropReason::ExportFailed, span_count);
+            }
+            exporter::trace::ExportResult::FailedRetryable => {
+                self.counters.export_failed(span_count)
+            }
+        }
+
+        let attempts = attempts + 1;
+        let now = time::Instant::now();
+        if attempts >= self.config.max_export_attempts
+            || now.duration_since(first_attempt) >= self.config.max_elapsed_time
+        {
+            return self
+                .counters
+                .dropped(SpanDropReason::ExportFailed, span_count);
+        }
+
+        self.retries.push_back(RetryBatch {
+            spans,
+            attempts,
+            first_attempt,
+            next_attempt: now + self.config.backoff(attempts),
+        });
+    }
+
+    /// Export all batches whose backoff has elapsed and schedule the next wake up.
+    fn export_due_retries(&mut self) {
+        let now = time::Instant::now();
+        let (due, waiting) = std::mem::take(&mut self.retries)
+            .into_iter()
+            .partition::<VecDeque<_>, _>(|batch| batch.next_attempt <= now);
+        self.retries = waiting;
+
+        for batch in due {
+            self.export_batch(batch.spans, batch.attempts, batch.first_attempt);
+        }
+
+        self.retry_timer = self
+            .retries
+            .iter()
+            .map(|batch| batch.next_attempt)
+            .min()
+            .map(|next_attempt| {
+                let delay = next_attempt.saturating_duration_since(time::Instant::now());
+                (self.interval)(delay.max(MIN_RETRY_DELAY))
+            });
+    }
+
+    /// Give every pending retry and buffered span a final export attempt, then
+    /// shut down the exporter.
+    fn export_remaining(&mut self) {
+        let mut batches = std::mem::take(&mut self.retries)
+            .into_iter()
+            .map(|batch| batch.spans)
+            .collect::<Vec<_>>();
+        self.retry_timer = None;
+
+        let mut spans = std::mem::take(&mut self.buffer);
+        while !spans.is_empty() {
+            let batch_idx = spans
+                .len()
+                .saturating_sub(self.config.max_export_batch_size);
+            batches.push(spans.split_off(batch_idx));
+        }
+
+        for batch in batches {
+            let span_count = batch.len() as u64;
+            if self.exporter.export(batch) == exporter::trace::ExportResult::Success {
+                self.counters.exported(span_count);
+            } else {
+                self.counters.export_failed(span_count);
+                self.counters
+                    .dropped(SpanDropReason::ExportFailed, span_count);
+            }
+        }
+
+        self.exporter.shutdown();
+    }
+
+    /// Returns `true` if the earliest pending retry is due.
+    fn retry_due(&self) -> bool {
+        let now = time::Instant::now();
+        self.retries.iter().any(|batch| batch.next_attempt <= now)
+    }
 }
 
 impl Future for BatchSpanProcessorWorker {
@@ -175,29 +402,56 @@ impl Future for BatchSpanProcessorWorker {
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         loop {
+            // Retry timer fired, export any batches whose backoff has elapsed. Intervals
+            // may fire immediately when created, so only act once a retry is actually due.
+            if let Some(timer) = self.retry_timer.as_mut() {
+                match timer.poll_next_unpin(cx) {
+                    Poll::Ready(Some(())) if self.retry_due() => {
+                        self.export_due_retries();
+                        continue;
+                    }
+                    Poll::Ready(Some(())) => continue,
+                    Poll::Ready(None) => self.retry_timer = None,
+                    Poll::Pending => (),
+                }
+            }
+
             match futures::ready!(self.messages.poll_next_unpin(cx)) {
                 // Span has finished, add to buffer of pending spans.
                 Some(BatchMessage::ExportSpan(span)) => {
-                    if self.buffer.len() < self.config.max_queue_size {
+                    if self.queued_spans() < self.config.max_queue_size {
                         self.buffer.push(span);
+                    } else {
+                        self.counters.dropped(SpanDropReason::QueueFull, 1);
                     }
                 }
                 // Span batch interval time reached, export current spans.
                 Some(BatchMessage::Tick) => {
-                    if !self.buffer.is_empty() {
-                        let mut spans = std::mem::replace(&mut self.buffer, Vec::new());
-                        while !spans.is_empty() {
-                            let batch_idx = spans
-                                .len()
-                                .saturating_sub(self.config.max_export_batch_size);
-                            let batch = spans.split_off(batch_idx);
-                            self.exporter.export(batch);
-                        }
+                    self.export_buffer();
+                    if self.retry_timer.is_none() || self.retry_due() {
+                        self.export_due_retries();
                     }
                 }
-                // Stream has terminated or processor is shutdown, return to finish execution.
-                None | Some(BatchMessage::Shutdown) => {
-                    self.exporter.shutdown();
+                // Flush requested, export current spans and pending retries right away.
+                Some(BatchMessage::Flush(flushed)) => {
+                    self.export_buffer();
+                    let now = time::Instant::now();
+                    for batch in self.retries.iter_mut() {
+                        batch.next_attempt = now;
+                    }
+                    self.export_due_retries();
+                    self.exporter.force_flush();
+                    let _ = flushed.send(());
+                }
+                // Stream has terminated or processor is shutdown, export everything still
+                // buffered and return to finish execution.
+                None => {
+                    self.export_remaining();
+                    return Poll::Ready(());
+                }
+                Some(BatchMessage::Shutdown(finished)) => {
+                    self.export_remaining();
+                    let _ = finished.send(());
                     return Poll::Ready(());
                 }
             }
@@ -209,7 +463,8 @@ impl Future for BatchSpanProcessorWorker {
 enum BatchMessage {
     ExportSpan(Arc<exporter::trace::SpanData>),
     Tick,
-    Shutdown,
+    Flush(oneshot::Sender<()>),
+    Shutdown(oneshot::Sender<()>),
 }
 
 impl BatchSpanProcessor {
@@ -218,29 +473,79 @@ impl BatchSpanProcessor {
         spawn: S,
         interval: I,
         config: BatchConfig,
+        counters: SpanCounters,
     ) -> Self
     where
         S: Fn(BatchSpanProcessorWorker) -> SO,
-        I: Fn(time::Duration) -> IS,
+        I: Fn(time::Duration) -> IS + Send + 'static,
         IS: Stream<Item = ISI> + Send + 'static,
     {
         let (message_sender, message_receiver) = mpsc::channel(config.max_queue_size);
+        let flush_timeout = config.flush_timeout;
+        let shutdown_timeout = config.shutdown_timeout;
+        let counters = Arc::new(counters);
         let ticker = interval(config.scheduled_delay).map(|_| BatchMessage::Tick);
 
         // Spawn worker process via user-defined spawn function.
         spawn(BatchSpanProcessorWorker {
             exporter,
             messages: Box::pin(futures::stream::select(message_receiver, ticker)),
+            interval: Box::new(move |delay| Box::pin(interval(delay).map(|_| ()))),
             config,
             buffer: Vec::new(),
+            retries: VecDeque::new(),
+            retry_timer: None,
+            counters: counters.clone(),
         });
 
         // Return batch processor with link to worker
         BatchSpanProcessor {
             message_sender: Mutex::new(message_sender),
+            counters,
+            flush_timeout,
+            shutdown_timeout,
         }
     }
 
+    /// Returns a snapshot of the counters describing the spans handled by this
+    /// processor and its exporter.
+    pub fn stats(&self) -> BatchSpanProcessorStats {
+        self.counters.snapshot()
+    }
+
+    /// Shuts down the processor, resolving once all buffered spans have been
+    /// exported and the exporter has been shut down.
+    ///
+    /// Unlike `SpanProcessor::shutdown`, this does not block the current thread,
+    /// so it can be awaited from the same executor that runs the worker. Use
+    /// your runtime's timeout utilities to bound how long to wait. Resolves to
+    /// `false` if the worker had already stopped.
+    pub fn shutdown_async(&self) -> impl Future<Output = bool> + Send {
+        let finished = self.send_and_notify(BatchMessage::Shutdown);
+        async move {
+            match finished {
+                Some(finished) => finished.await.is_ok(),
+                None => false,
+            }
+        }
+    }
+
+    /// Send a message to the worker, returning a receiver that resolves once it
+    /// has been handled, or `None` if the worker is no longer running.
+    fn send_and_notify<F>(&self, message: F) -> Option<oneshot::Receiver<()>>
+    where
+        F: FnOnce(oneshot::Sender<()>) -> BatchMessage,
+    {
+        let (done_sender, done_receiver) = oneshot::channel();
+        // A fresh sender is guaranteed a slot, so the message is queued even if
+        // the channel is full of pending spans.
+        let mut sender = self.message_sender.lock().ok()?.clone();
+        sender
+            .try_send(message(done_sender))
+            .ok()
+            .map(|_| done_receiver)
+    }
+
     /// Create a new batch processor builder
     pub fn builder<E, S, SO, I, IO>(
         exporter: E,
@@ -257,10 +562,216 @@ impl BatchSpanProcessor {
             spawn,
             interval,
             config: Default::default(),
+            counters: Default::default(),
+        }
+    }
+}
+
+/// Reasons for a `BatchSpanProcessor` to drop spans.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SpanDropReason {
+    /// The span ended while another thread held the lock on the worker channel.
+    LockContended,
+    /// The channel to the worker was full.
+    ChannelFull,
+    /// The worker already held `max_queue_size` spans.
+    QueueFull,
+    /// The span's batch failed to export and will not be retried.
+    ExportFailed,
+    /// The span ended after the processor was shut down.
+    Shutdown,
+}
+
+impl SpanDropReason {
+    /// All drop reasons, in the order they are stored in the counters.
+    const ALL: [SpanDropReason; 5] = [
+        SpanDropReason::LockContended,
+        SpanDropReason::ChannelFull,
+        SpanDropReason::QueueFull,
+        SpanDropReason::ExportFailed,
+        SpanDropReason::Shutdown,
+    ];
+
+    /// Label value used when reporting this reason through a `Meter`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SpanDropReason::LockContended => "lock_contended",
+            SpanDropReason::ChannelFull => "channel_full",
+            SpanDropReason::QueueFull => "queue_full",
+            SpanDropReason::ExportFailed => "export_failed",
+            SpanDropReason::Shutdown => "shutdown",
         }
     }
 }
 
+/// Snapshot of the counters kept by a `BatchSpanProcessor`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BatchSpanProcessorStats {
+    /// Spans handed to the worker.
+    pub enqueued: u64,
+    /// Spans successfully exported.
+    pub exported: u64,
+    /// Spans in failed export attempts. Spans in a batch that is retried are
+    /// counted once for every failed attempt.
+    pub export_failed: u64,
+    dropped: [u64; 5],
+}
+
+impl BatchSpanProcessorStats {
+    /// Spans dropped for the given reason.
+    pub fn dropped(&self, reason: SpanDropReason) -> u64 {
+        self.dropped[reason as usize]
+    }
+
+    /// Spans dropped for any reason.
+    pub fn total_dropped(&self) -> u64 {
+        self.dropped.iter().sum()
+    }
+}
+
+/// Span counters shared by a `BatchSpanProcessor` and its worker.
+#[derive(Default)]
+pub(crate) struct SpanCounters {
+    enqueued: AtomicU64,
+    exported: AtomicU64,
+    export_failed: AtomicU64,
+    dropped: [AtomicU64; 5],
+    meter: Option<MeterCounters>,
+}
+
+impl fmt::Debug for SpanCounters {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpanCounters")
+            .field("stats", &self.snapshot())
+            .field("meter", &self.meter.is_some())
+            .finish()
+    }
+}
+
+impl SpanCounters {
+    fn enqueued(&self, count: u64) {
+        self.enqueued.fetch_add(count, Ordering::Relaxed);
+        if let Some(meter) = &self.meter {
+            meter.enqueued.add(count as i64);
+        }
+    }
+
+    fn exported(&self, count: u64) {
+        self.exported.fetch_add(count, Ordering::Relaxed);
+        if let Some(meter) = &self.meter {
+            meter.exported.add(count as i64);
+        }
+    }
+
+    fn export_failed(&self, count: u64) {
+        self.export_failed.fetch_add(count, Ordering::Relaxed);
+        if let Some(meter) = &self.meter {
+            meter.export_failed.add(count as i64);
+        }
+    }
+
+    fn dropped(&self, reason: SpanDropReason, count: u64) {
+        self.dropped[reason as usize].fetch_add(count, Ordering::Relaxed);
+        if let Some(meter) = &self.meter {
+            meter.dropped[reason as usize].add(count as i64);
+        }
+    }
+
+    fn snapshot(&self) -> BatchSpanProcessorStats {
+        let mut dropped = [0; 5];
+        for (snapshot, counter) in dropped.iter_mut().zip(self.dropped.iter()) {
+            *snapshot = counter.load(Ordering::Relaxed);
+        }
+
+        BatchSpanProcessorStats {
+            enqueued: self.enqueued.load(Ordering::Relaxed),
+            exported: self.exported.load(Ordering::Relaxed),
+            export_failed: self.export_failed.load(Ordering::Relaxed),
+            dropped,
+        }
+    }
+}
+
+type CounterHandle = Box<dyn api::CounterHandle<i64> + Send + Sync>;
+
+/// Metric instruments mirroring the `SpanCounters`.
+struct MeterCounters {
+    enqueued: CounterHandle,
+    exported: CounterHandle,
+    export_failed: CounterHandle,
+    dropped: Vec<CounterHandle>,
+}
+
+impl MeterCounters {
+    fn new<M>(meter: &M) -> Self
+    where
+        M: api::Meter,
+        <M::I64Counter as api::Counter<i64, M::LabelSet>>::Handle: Send + Sync + 'static,
+    {
+        use api::Counter;
+
+        let counter = |name: &str, description: &str| -> CounterHandle {
+            let counter = meter.new_i64_counter(
+                name,
+                api::MetricOptions::default().with_description(description),
+            );
+            Box::new(counter.acquire_handle(&meter.labels(Vec::new())))
+        };
+        let dropped = meter.new_i64_counter(
+            "spans_dropped",
+            api::MetricOptions::default()
+                .with_description("Spans dropped by the batch span processor")
+                .with_keys(vec![api::Key::new("reason")]),
+        );
+
+        MeterCounters {
+            enqueued: counter(
+                "spans_enqueued",
+                "Spans handed to the batch span processor worker",
+            ),
+            exported: counter("spans_exported", "Spans successfully exported"),
+            export_failed: counter(
+                "spans_export_failed",
+                "Spans in failed export attempts, including retries",
+            ),
+            dropped: SpanDropReason::ALL
+                .iter()
+                .map(|reason| -> CounterHandle {
+                    let labels = meter.labels(vec![api::KeyValue::new("reason", reason.as_str())]);
+                    Box::new(dropped.acquire_handle(&labels))
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Block the current thread until `done` resolves or `timeout` elapses.
+///
+/// Returns `true` if `done` resolved successfully in time.
+fn wait_timeout(mut done: oneshot::Receiver<()>, timeout: time::Duration) -> bool {
+    struct ThreadWaker(std::thread::Thread);
+
+    impl ArcWake for ThreadWaker {
+        fn wake_by_ref(arc_self: &Arc<Self>) {
+            arc_self.0.unpark();
+        }
+    }
+
+    let waker = futures::task::waker(Arc::new(ThreadWaker(std::thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let deadline = time::Instant::now() + timeout;
+    loop {
+        if let Poll::Ready(result) = done.poll_unpin(&mut cx) {
+            return result.is_ok();
+        }
+        let now = time::Instant::now();
+        if now >= deadline {
+            return false;
+        }
+        std::thread::park_timeout(deadline - now);
+    }
+}
+
 /// Batch span processor configuration
 #[derive(Debug)]
 pub struct BatchConfig {
@@ -277,6 +788,78 @@ pub struct BatchConfig {
     /// of spans one batch after the other without any delay. The default value
     /// is 512.
     max_export_batch_size: usize,
+
+    /// The maximum number of times a batch is exported, including the first
+    /// attempt, when the exporter reports a retryable failure. A value of 1
+    /// disables retries. The default value is 5.
+    max_export_attempts: usize,
+
+    /// The delay before the first retry of a failed batch. Each subsequent
+    /// retry doubles the delay. The default value is 1 second.
+    initial_backoff: time::Duration,
+
+    /// The upper bound for the delay between two retries of the same batch.
+    /// The default value is 30 seconds.
+    max_backoff: time::Duration,
+
+    /// The maximum time spent retrying a batch, measured from its first export
+    /// attempt. The default value is 2 minutes.
+    max_elapsed_time: time::Duration,
+
+    /// The maximum time `force_flush` blocks waiting for buffered spans to be
+    /// exported. The default value is 30 seconds.
+    flush_timeout: time::Duration,
+
+    /// The maximum time `shutdown` blocks waiting for buffered spans to be
+    /// exported and the exporter to shut down. The default value is 30 seconds.
+    shutdown_timeout: time::Duration,
+}
+
+impl BatchConfig {
+    /// Create a configuration from the standard environment variables, using
+    /// the defaults for variables that are not set.
+    ///
+    /// The following variables are supported:
+    ///
+    /// - `OTEL_BSP_MAX_QUEUE_SIZE`
+    /// - `OTEL_BSP_SCHEDULE_DELAY`, in milliseconds
+    /// - `OTEL_BSP_MAX_EXPORT_BATCH_SIZE`, which must not exceed the queue size
+    ///
+    /// `OTEL_BSP_EXPORT_TIMEOUT` is not supported, exports are not interrupted
+    /// by the processor.
+    pub fn from_env() -> Result<Self, EnvError> {
+        let mut config = BatchConfig::default();
+
+        if let Some(size) = env::parse(OTEL_BSP_MAX_QUEUE_SIZE)? {
+            config.max_queue_size = size;
+        }
+        if let Some(delay) = env::parse(OTEL_BSP_SCHEDULE_DELAY)? {
+            config.scheduled_delay = time::Duration::from_millis(delay);
+        }
+        if let Some(size) = env::parse::<usize>(OTEL_BSP_MAX_EXPORT_BATCH_SIZE)? {
+            if size > config.max_queue_size {
+                return Err(EnvError::invalid_value(
+                    OTEL_BSP_MAX_EXPORT_BATCH_SIZE,
+                    &size.to_string(),
+                    format!(
+                        "must not exceed the max queue size {}",
+                        config.max_queue_size
+                    ),
+                ));
+            }
+            config.max_export_batch_size = size;
+        }
+
+        Ok(config)
+    }
+
+    /// Delay before the next export of a batch that has been tried `attempts` times.
+    fn backoff(&self, attempts: usize) -> time::Duration {
+        let exponent = attempts.saturating_sub(1).min(31) as u32;
+        self.initial_backoff
+            .checked_mul(1 << exponent)
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff))
+    }
 }
 
 impl Default for BatchConfig {
@@ -285,6 +868,12 @@ impl Default for BatchConfig {
             max_queue_size: 2048,
             scheduled_delay: time::Duration::from_secs(5),
             max_export_batch_size: 512,
+            max_export_attempts: 5,
+            initial_backoff: time::Duration::from_secs(1),
+            max_backoff: time::Duration::from_secs(30),
+            max_elapsed_time: time::Duration::from_secs(120),
+            flush_timeout: time::Duration::from_secs(30),
+            shutdown_timeout: time::Duration::from_secs(30),
         }
     }
 }
@@ -298,15 +887,22 @@ pub struct BatchSpanProcessorBuilder<E, S, I> {
     interval: I,
     spawn: S,
     config: BatchConfig,
+    counters: SpanCounters,
 }
 
 impl<E, S, SO, I, IS, ISI> BatchSpanProcessorBuilder<E, S, I>
 where
     E: exporter::trace::SpanExporter + 'static,
     S: Fn(BatchSpanProcessorWorker) -> SO,
-    I: Fn(time::Duration) -> IS,
+    I: Fn(time::Duration) -> IS + Send + 'static,
     IS: Stream<Item = ISI> + Send + 'static,
 {
+    /// Replace the whole batch configuration, e.g. with one created by
+    /// `BatchConfig::from_env`
+    pub fn with_batch_config(self, config: BatchConfig) -> Self {
+        BatchSpanProcessorBuilder { config, ..self }
+    }
+
     /// Set max queue size for batches
     pub fn with_max_queue_size(self, size: usize) -> Self {
         let mut config = self.config;
@@ -331,6 +927,77 @@ where
         BatchSpanProcessorBuilder { config, ..self }
     }
 
+    /// Set the maximum number of export attempts for batches that fail with a
+    /// retryable error
+    pub fn with_max_export_attempts(self, attempts: usize) -> Self {
+        let mut config = self.config;
+        config.max_export_attempts = attempts;
+
+        BatchSpanProcessorBuilder { config, ..self }
+    }
+
+    /// Set the delay before the first retry of a failed batch, values below one
+    /// millisecond are rounded up to one millisecond
+    pub fn with_initial_backoff(self, backoff: time::Duration) -> Self {
+        let mut config = self.config;
+        config.initial_backoff = backoff.max(MIN_RETRY_DELAY);
+
+        BatchSpanProcessorBuilder { config, ..self }
+    }
+
+    /// Set the maximum delay between retries of a failed batch
+    pub fn with_max_backoff(self, backoff: time::Duration) -> Self {
+        let mut config = self.config;
+        config.max_backoff = backoff;
+
+        BatchSpanProcessorBuilder { config, ..self }
+    }
+
+    /// Set the maximum time spent retrying a failed batch
+    pub fn with_max_elapsed_time(self, elapsed: time::Duration) -> Self {
+        let mut config = self.config;
+        config.max_elapsed_time = elapsed;
+
+        BatchSpanProcessorBuilder { config, ..self }
+    }
+
+    /// Set the maximum time `force_flush` waits for buffered spans to be exported
+    pub fn with_flush_timeout(self, timeout: time::Duration) -> Self {
+        let mut config = self.config;
+        config.flush_timeout = timeout;
+
+        BatchSpanProcessorBuilder { config, ..self }
+    }
+
+    /// Set the maximum time `shutdown` waits for buffered spans to be exported
+    pub fn with_shutdown_timeout(self, timeout: time::Duration) -> Self {
+        let mut config = self.config;
+        config.shutdown_timeout = timeout;
+
+        BatchSpanProcessorBuilder { config, ..self }
+    }
+
+    /// Also report the processor's span counters through metric instruments
+    /// created with the given `Meter`.
+    ///
+    /// The following `i64` counters are created, so a meter can only be passed
+    /// to one processor:
+    ///
+    /// - `spans_enqueued`: spans handed to the worker
+    /// - `spans_dropped`: spans dropped, labeled by `reason`
+    /// - `spans_exported`: spans successfully exported
+    /// - `spans_export_failed`: spans in failed export attempts, including retries
+    pub fn with_meter<M>(self, meter: &M) -> Self
+    where
+        M: api::Meter,
+        <M::I64Counter as api::Counter<i64, M::LabelSet>>::Handle: Send + Sync + 'static,
+    {
+        let mut counters = self.counters;
+        counters.meter = Some(MeterCounters::new(meter));
+
+        BatchSpanProcessorBuilder { counters, ..self }
+    }
+
     /// Build a batch processor
     pub fn build(self) -> BatchSpanProcessor {
         BatchSpanProcessor::new(
@@ -338,6 +1005,557 @@ where
             self.spawn,
             self.interval,
             self.config,
+            self.counters,
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exporter::trace::{ExportResult, SpanData, SpanExporter};
+    use crate::sdk;
+    use futures::FutureExt;
+    use std::time::{Duration, SystemTime};
+
+    #[derive(Debug, Default)]
+    struct TestExporter {
+        results: Mutex<VecDeque<ExportResult>>,
+        batch_sizes: Mutex<Vec<usize>>,
+        flushes: Mutex<usize>,
+        shutdowns: Mutex<usize>,
+        export_delay: Duration,
+    }
+
+    impl TestExporter {
+        fn exports(&self) -> Vec<usize> {
+            self.batch_sizes.lock().unwrap().clone()
+        }
+    }
+
+    impl SpanExporter for Arc<TestExporter> {
+        fn export(&self, batch: Vec<Arc<SpanData>>) -> ExportResult {
+            self.batch_sizes.lock().unwrap().push(batch.len());
+            std::thread::sleep(self.export_delay);
+            self.results
+                .lock()
+                .unwrap()
+                .pop_front()
+                .unwrap_or(ExportResult::FailedRetryable)
+        }
+
+        fn shutdown(&self) {
+            *self.shutdowns.lock().unwrap() += 1;
+        }
+
+        fn force_flush(&self) {
+            *self.flushes.lock().unwrap() += 1;
+        }
+    }
+
+    fn span() -> Arc<SpanData> {
+        Arc::new(SpanData {
+            span_context: api::SpanContext::new(
+                api::TraceId::from_u128(1),
+                api::SpanId::from_u64(1),
+                api::TRACE_FLAG_SAMPLED,
+                false,
+                api::TraceState::default(),
+            ),
+            parent_span_id: api::SpanId::invalid(),
+            span_kind: api::SpanKind::Internal,
+            name: "span".to_string(),
+            start_time: SystemTime::now(),
+            end_time: SystemTime::now(),
+            attributes: sdk::EvictedHashMap::new(0),
+            message_events: sdk::EvictedQueue::new(0),
+            links: sdk::EvictedQueue::new(0),
+            status_code: api::StatusCode::OK,
+            status_message: String::new(),
+            resource: Arc::new(sdk::Resource::default()),
+        })
+    }
+
+    /// Build a worker driven by manually sent messages, with retry timers that never fire
+    /// so retries only happen on ticks.
+    fn worker(
+        results: Vec<ExportResult>,
+        config: BatchConfig,
+    ) -> (
+        BatchSpanProcessorWorker,
+        mpsc::UnboundedSender<BatchMessage>,
+        Arc<TestExporter>,
+    ) {
+        let exporter = Arc::new(TestExporter {
+            results: Mutex::new(results.into()),
+            ..Default::default()
+        });
+        let (sender, receiver) = mpsc::unbounded();
+        let worker = BatchSpanProcessorWorker {
+            exporter: Box::new(exporter.clone()),
+            messages: Box::pin(receiver),
+            interval: Box::new(|_| Box::pin(futures::stream::pending())),
+            config,
+            buffer: Vec::new(),
+            retries: VecDeque::new(),
+            retry_timer: None,
+            counters: Default::default(),
+        };
+
+        (worker, sender, exporter)
+    }
+
+    fn send(
+        worker: &mut BatchSpanProcessorWorker,
+        sender: &mpsc::UnboundedSender<BatchMessage>,
+        message: BatchMessage,
+    ) {
+        sender.unbounded_send(message).unwrap();
+        let mut cx = Context::from_waker(futures::task::noop_waker_ref());
+        assert_eq!(worker.poll_unpin(&mut cx), Poll::Pending);
+    }
+
+    fn retry_config() -> BatchConfig {
+        BatchConfig {
+            initial_backoff: Duration::from_secs(0),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn retries_retryable_batches() {
+        let results = vec![
+            ExportResult::FailedRetryable,
+            ExportResult::FailedRetryable,
+            ExportResult::Success,
+        ];
+        let (mut worker, sender, exporter) = worker(results, retry_config());
+
+        send(&mut worker, &sender, BatchMessage::ExportSpan(span()));
+        send(&mut worker, &sender, BatchMessage::Tick);
+        assert_eq!(exporter.exports(), vec![1, 1]);
+        send(&mut worker, &sender, BatchMessage::Tick);
+
+        assert_eq!(exporter.exports(), vec![1, 1, 1]);
+        assert_eq!(worker.queued_spans(), 0);
+    }
+
+    #[test]
+    fn stops_after_max_export_attempts() {
+        let config = BatchConfig {
+            max_export_attempts: 3,
+            ..retry_config()
+        };
+        let (mut worker, sender, exporter) = worker(Vec::new(), config);
+
+        send(&mut worker, &sender, BatchMessage::ExportSpan(span()));
+        send(&mut worker, &sender, BatchMessage::Tick);
+        send(&mut worker, &sender, BatchMessage::Tick);
+
+        assert_eq!(exporter.exports().len(), 3);
+        assert_eq!(worker.queued_spans(), 0);
+    }
+
+    #[test]
+    fn does_not_retry_non_retryable_failures() {
+        let (mut worker, sender, exporter) =
+            worker(vec![ExportResult::FailedNotRetryable], retry_config());
+
+        send(&mut worker, &sender, BatchMessage::ExportSpan(span()));
+        send(&mut worker, &sender, BatchMessage::Tick);
+        send(&mut worker, &sender, BatchMessage::Tick);
+
+        assert_eq!(exporter.exports(), vec![1]);
+    }
+
+    #[test]
+    fn pending_retries_count_towards_max_queue_size() {
+        let config = BatchConfig {
+            max_queue_size: 2,
+            initial_backoff: Duration::from_secs(3600),
+            ..Default::default()
+        };
+        let (mut worker, sender, exporter) = worker(Vec::new(), config);
+
+        send(&mut worker, &sender, BatchMessage::ExportSpan(span()));
+        send(&mut worker, &sender, BatchMessage::ExportSpan(span()));
+        send(&mut worker, &sender, BatchMessage::Tick);
+        send(&mut worker, &sender, BatchMessage::ExportSpan(span()));
+        send(&mut worker, &sender, BatchMessage::Tick);
+
+        assert_eq!(exporter.exports(), vec![2]);
+        assert_eq!(worker.queued_spans(), 2);
+    }
+
+    #[test]
+    fn retries_are_scheduled_between_ticks() {
+        let mut runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            let exporter = Arc::new(TestExporter {
+                results: Mutex::new(
+                    vec![
+                        ExportResult::FailedRetryable,
+                        ExportResult::FailedRetryable,
+                        ExportResult::Success,
+                    ]
+                    .into(),
+                ),
+                ..Default::default()
+            });
+            let processor =
+                BatchSpanProcessor::builder(exporter.clone(), tokio::spawn, tokio::time::interval)
+                    .with_scheduled_delay(Duration::from_millis(100))
+                    .with_initial_backoff(Duration::from_millis(1))
+                    .build();
+
+            api::SpanProcessor::on_end(&processor, span());
+            // first tick exports at ~100ms, both retries follow within a few milliseconds
+            tokio::time::delay_for(Duration::from_millis(150)).await;
+
+            assert_eq!(exporter.exports(), vec![1, 1, 1]);
+        });
+    }
+
+    #[test]
+    fn retry_timer_is_never_zero() {
+        let mut runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            let exporter = Arc::new(TestExporter {
+                results: Mutex::new(
+                    vec![
+                        ExportResult::FailedRetryable,
+                        ExportResult::FailedRetryable,
+                        ExportResult::FailedRetryable,
+                        ExportResult::Success,
+                        ExportResult::Success,
+                    ]
+                    .into(),
+                ),
+                export_delay: Duration::from_millis(5),
+                ..Default::default()
+            });
+            let processor =
+                BatchSpanProcessor::builder(exporter.clone(), tokio::spawn, tokio::time::interval)
+                    .with_scheduled_delay(Duration::from_millis(100))
+                    .with_max_export_batch_size(1)
+                    .with_initial_backoff(Duration::from_millis(1))
+                    .build();
+
+            api::SpanProcessor::on_end(&processor, span());
+            api::SpanProcessor::on_end(&processor, span());
+            // The second batch becomes due while the first one is being retried, so the
+            // next retry timer is computed from an instant that has already passed.
+            tokio::time::delay_for(Duration::from_millis(150)).await;
+
+            assert_eq!(exporter.exports(), vec![1, 1, 1, 1, 1]);
+        });
+    }
+
+    #[test]
+    fn builder_rounds_up_zero_initial_backoff() {
+        let builder = BatchSpanProcessor::builder(
+            Arc::new(TestExporter::default()),
+            tokio::spawn,
+            tokio::time::interval,
+        )
+        .with_initial_backoff(Duration::from_secs(0));
+
+        assert_eq!(builder.config.initial_backoff, MIN_RETRY_DELAY);
+    }
+
+    #[test]
+    fn flush_exports_buffer_and_pending_retries() {
+        let config = BatchConfig {
+            initial_backoff: Duration::from_secs(3600),
+            ..Default::default()
+        };
+        let results = vec![
+            ExportResult::FailedRetryable,
+            ExportResult::Success,
+            ExportResult::Success,
+        ];
+        let (mut worker, sender, exporter) = worker(results, config);
+
+        send(&mut worker, &sender, BatchMessage::ExportSpan(span()));
+        send(&mut worker, &sender, BatchMessage::Tick);
+        send(&mut worker, &sender, BatchMessage::ExportSpan(span()));
+        let (flushed_sender, mut flushed_receiver) = oneshot::channel();
+        send(&mut worker, &sender, BatchMessage::Flush(flushed_sender));
+
+        assert_eq!(flushed_receiver.try_recv(), Ok(Some(())));
+        assert_eq!(exporter.exports(), vec![1, 1, 1]);
+        assert_eq!(*exporter.flushes.lock().unwrap(), 1);
+        assert_eq!(worker.queued_spans(), 0);
+    }
+
+    #[test]
+    fn provider_force_flush_exports_without_waiting_for_tick() {
+        let mut runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            let exporter = Arc::new(TestExporter {
+                results: Mutex::new(vec![ExportResult::Success].into()),
+                ..Default::default()
+            });
+            let processor =
+                BatchSpanProcessor::builder(exporter.clone(), tokio::spawn, tokio::time::interval)
+                    .with_scheduled_delay(Duration::from_secs(3600))
+                    .build();
+            let provider = sdk::Provider::builder()
+                .with_batch_exporter(processor)
+                .build();
+
+            // Let the interval's immediate first tick pass before recording spans
+            tokio::task::yield_now().await;
+            api::Span::end(&api::Tracer::start(
+                &api::Provider::get_tracer(&provider, "test"),
+                "span",
+            ));
+            api::Provider::force_flush(&provider);
+
+            assert_eq!(exporter.exports(), vec![1]);
+            assert_eq!(*exporter.flushes.lock().unwrap(), 1);
+        });
+    }
+
+    #[test]
+    fn does_not_export_unsampled_spans() {
+        let mut runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            let exporter = Arc::new(TestExporter {
+                results: Mutex::new(vec![ExportResult::Success].into()),
+                ..Default::default()
+            });
+            let processor =
+                BatchSpanProcessor::builder(exporter.clone(), tokio::spawn, tokio::time::interval)
+                    .with_scheduled_delay(Duration::from_secs(3600))
+                    .build();
+            let unsampled = SpanData {
+                span_context: api::SpanContext::new(
+                    api::TraceId::from_u128(1),
+                    api::SpanId::from_u64(2),
+                    0,
+                    false,
+                    api::TraceState::default(),
+                ),
+                ..(*span()).clone()
+            };
+
+            tokio::task::yield_now().await;
+            api::SpanProcessor::on_end(&processor, Arc::new(unsampled));
+            api::SpanProcessor::on_end(&processor, span());
+            api::SpanProcessor::force_flush(&processor);
+
+            assert_eq!(exporter.exports(), vec![1]);
+            assert_eq!(processor.stats().enqueued, 1);
+        });
+    }
+
+    #[test]
+    fn shutdown_exports_remaining_spans_in_batches() {
+        let config = BatchConfig {
+            max_export_batch_size: 2,
+            initial_backoff: Duration::from_secs(3600),
+            ..Default::default()
+        };
+        let (mut worker, sender, exporter) = worker(Vec::new(), config);
+
+        send(&mut worker, &sender, BatchMessage::ExportSpan(span()));
+        send(&mut worker, &sender, BatchMessage::Tick);
+        for _ in 0..3 {
+            send(&mut worker, &sender, BatchMessage::ExportSpan(span()));
+        }
+        let (finished_sender, mut finished_receiver) = oneshot::channel();
+        sender
+            .unbounded_send(BatchMessage::Shutdown(finished_sender))
+            .unwrap();
+        let mut cx = Context::from_waker(futures::task::noop_waker_ref());
+
+        assert_eq!(worker.poll_unpin(&mut cx), Poll::Ready(()));
+        assert_eq!(finished_receiver.try_recv(), Ok(Some(())));
+        // the failed batch from the first tick gets a final attempt as well
+        assert_eq!(exporter.exports(), vec![1, 1, 2, 1]);
+        assert_eq!(*exporter.shutdowns.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn shutdown_is_delivered_when_queue_is_full() {
+        let exporter = Arc::new(TestExporter {
+            results: Mutex::new(vec![ExportResult::Success; 8].into()),
+            ..Default::default()
+        });
+        // Start the worker late so that the channel fills up first
+        let spawn = |worker: BatchSpanProcessorWorker| {
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(50));
+                futures::executor::block_on(worker)
+            })
+        };
+        let processor = BatchSpanProcessor::builder(exporter.clone(), spawn, |_| {
+            futures::stream::pending::<()>()
+        })
+        .with_max_queue_size(2)
+        .build();
+
+        for _ in 0..5 {
+            api::SpanProcessor::on_end(&processor, span());
+        }
+        api::SpanProcessor::shutdown(&processor);
+
+        assert_eq!(exporter.exports().iter().sum::<usize>(), 2);
+        assert_eq!(*exporter.shutdowns.lock().unwrap(), 1);
+
+        // Shutting down again returns immediately
+        api::SpanProcessor::shutdown(&processor);
+        assert_eq!(*exporter.shutdowns.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn shutdown_async_resolves_once_finished() {
+        let mut runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            let exporter = Arc::new(TestExporter {
+                results: Mutex::new(vec![ExportResult::Success].into()),
+                ..Default::default()
+            });
+            let processor =
+                BatchSpanProcessor::builder(exporter.clone(), tokio::spawn, tokio::time::interval)
+                    .with_scheduled_delay(Duration::from_secs(3600))
+                    .build();
+
+            tokio::task::yield_now().await;
+            api::SpanProcessor::on_end(&processor, span());
+
+            assert!(processor.shutdown_async().await);
+            assert_eq!(exporter.exports(), vec![1]);
+            assert_eq!(*exporter.shutdowns.lock().unwrap(), 1);
+            assert!(!processor.shutdown_async().await);
+        });
+    }
+
+    #[test]
+    fn counts_exported_failed_and_dropped_spans() {
+        let config = BatchConfig {
+            max_queue_size: 3,
+            max_export_attempts: 2,
+            initial_backoff: Duration::from_secs(0),
+            ..Default::default()
+        };
+        let results = vec![
+            ExportResult::Success,
+            ExportResult::FailedNotRetryable,
+            ExportResult::FailedRetryable,
+            ExportResult::FailedRetryable,
+        ];
+        let (mut worker, sender, _exporter) = worker(results, config);
+        let counters = worker.counters.clone();
+
+        for _ in 0..4 {
+            send(&mut worker, &sender, BatchMessage::ExportSpan(span()));
+        }
+        send(&mut worker, &sender, BatchMessage::Tick);
+        for _ in 0..2 {
+            send(&mut worker, &sender, BatchMessage::ExportSpan(span()));
+            send(&mut worker, &sender, BatchMessage::Tick);
+        }
+
+        let stats = counters.snapshot();
+        assert_eq!(stats.exported, 3);
+        assert_eq!(stats.export_failed, 3);
+        assert_eq!(stats.dropped(SpanDropReason::QueueFull), 1);
+        assert_eq!(stats.dropped(SpanDropReason::ExportFailed), 2);
+        assert_eq!(stats.total_dropped(), 3);
+    }
+
+    #[test]
+    fn counts_spans_dropped_by_processor() {
+        let workers = Mutex::new(Vec::new());
+        let processor = BatchSpanProcessor::builder(
+            Arc::new(TestExporter::default()),
+            |worker| workers.lock().unwrap().push(worker),
+            |_| futures::stream::pending::<()>(),
+        )
+        .with_max_queue_size(1)
+        .build();
+
+        // The channel holds one span plus one per sender, the worker never runs.
+        for _ in 0..3 {
+            api::SpanProcessor::on_end(&processor, span());
+        }
+        let stats = processor.stats();
+        assert_eq!(stats.enqueued, 2);
+        assert_eq!(stats.dropped(SpanDropReason::ChannelFull), 1);
+
+        // Dropping the worker disconnects the channel
+        let processor =
+            BatchSpanProcessor::builder(Arc::new(TestExporter::default()), drop, |_| {
+                futures::stream::pending::<()>()
+            })
+            .build();
+        api::SpanProcessor::on_end(&processor, span());
+        assert_eq!(processor.stats().dropped(SpanDropReason::Shutdown), 1);
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn reports_counters_through_meter() {
+        use crate::exporter::metrics::prometheus::{PrometheusExporter, Registry};
+
+        let metrics_exporter = PrometheusExporter::new(Registry::new()).unwrap();
+        let controller = sdk::metrics::Controller::builder(metrics_exporter.clone()).build();
+        let exporter = Arc::new(TestExporter::default());
+        let processor =
+            BatchSpanProcessor::builder(exporter, drop, |_| futures::stream::pending::<()>())
+                .with_meter(&controller.meter("batch_span_processor_test"))
+                .build();
+        api::SpanProcessor::on_end(&processor, span());
+        controller.collect();
+
+        let families = metrics_exporter.registry().gather();
+        let dropped = families
+            .iter()
+            .find(|family| family.get_name() == "batch_span_processor_test_spans_dropped")
+            .expect("dropped counter is registered");
+        let shutdown = dropped
+            .get_metric()
+            .iter()
+            .find(|metric| metric.get_label()[0].get_value() == "shutdown")
+            .unwrap();
+        assert_eq!(shutdown.get_counter().get_value() as u64, 1);
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_max() {
+        let config = BatchConfig {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(1),
+            ..Default::default()
+        };
+
+        assert_eq!(config.backoff(1), Duration::from_millis(100));
+        assert_eq!(config.backoff(2), Duration::from_millis(200));
+        assert_eq!(config.backoff(4), Duration::from_millis(800));
+        assert_eq!(config.backoff(5), Duration::from_secs(1));
+        assert_eq!(config.backoff(64), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn batch_config_from_env() {
+        let _guard = env::lock_for_test();
+        std::env::set_var(OTEL_BSP_MAX_QUEUE_SIZE, "100");
+        std::env::set_var(OTEL_BSP_SCHEDULE_DELAY, "250");
+        let config = BatchConfig::from_env().unwrap();
+        assert_eq!(config.max_queue_size, 100);
+        assert_eq!(config.scheduled_delay, Duration::from_millis(250));
+        assert_eq!(config.max_export_batch_size, 512);
+
+        std::env::set_var(OTEL_BSP_MAX_EXPORT_BATCH_SIZE, "200");
+        let err = BatchConfig::from_env().unwrap_err();
+        std::env::remove_var(OTEL_BSP_MAX_QUEUE_SIZE);
+        std::env::remove_var(OTEL_BSP_SCHEDULE_DELAY);
+        std::env::remove_var(OTEL_BSP_MAX_EXPORT_BATCH_SIZE);
+        assert_eq!(
+            err.to_string(),
+            "invalid value \"200\" for OTEL_BSP_MAX_EXPORT_BATCH_SIZE: must not exceed the max queue size 100"
+        );
+    }
+}
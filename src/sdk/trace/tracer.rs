@@ -140,6 +140,11 @@ impl api::Tracer for Tracer {
             .or_else(|| Some(cx.span().span_context()).filter(|cx| cx.is_valid()))
             .or_else(|| cx.remote_span_context().cloned())
             .filter(|cx| cx.is_valid());
+        // Children carry on the vendor state of their parent
+        let trace_state = parent_span_context
+            .as_ref()
+            .map(|ctx| ctx.trace_state().clone())
+            .unwrap_or_default();
         // Build context for sampling decision
         let (no_parent, trace_id, parent_span_id, remote_parent, parent_trace_flags) =
             parent_span_context
@@ -168,6 +173,13 @@ impl api::Tracer for Tracer {
         // * Sampling has occurred elsewhere and is already stored in the builder
         // * There is no parent or a remote parent, in which case make decision now
         // * There is a local parent, in which case defer to the parent's decision
+        //
+        // Children of local spans that are recorded but not sampled are
+        // recorded but not sampled as well.
+        let parent_recording = cx.span().is_recording()
+            && parent_span_context.as_ref().map_or(false, |ctx| {
+                ctx.span_id() == cx.span().span_context().span_id()
+            });
         let sampling_decision = if let Some(sampling_result) = builder.sampling_result.take() {
             self.process_sampling_result(sampling_result, parent_span_context.as_ref())
         } else if no_parent || remote_parent {
@@ -180,9 +192,9 @@ impl api::Tracer for Tracer {
                 &link_options,
             )
         } else {
-            // has parent that is local: use parent if sampled, or don't record.
+            // has parent that is local: use parent if sampled or recording, or don't record.
             parent_span_context
-                .filter(|span_context| span_context.is_sampled())
+                .filter(|span_context| span_context.is_sampled() || parent_recording)
                 .map(|_| (parent_trace_flags, Vec::new()))
         };
 
@@ -206,7 +218,13 @@ impl api::Tracer for Tracer {
             let resource = config.resource.clone();
 
             exporter::trace::SpanData {
-                span_context: api::SpanContext::new(trace_id, span_id, trace_flags, false),
+                span_context: api::SpanContext::new(
+                    trace_id,
+                    span_id,
+                    trace_flags,
+                    false,
+                    trace_state,
+                ),
                 parent_span_id,
                 span_kind,
                 name: builder.name,
@@ -232,3 +250,180 @@ impl api::Tracer for Tracer {
         sdk::Span::new(span_id, inner, self.clone())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::api::{Provider, Span, TraceContextExt, Tracer};
+    use crate::exporter::trace::{ExportResult, SpanData, SpanExporter};
+    use crate::{api, sdk};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Debug)]
+    struct TestSampler;
+
+    impl api::Sampler for TestSampler {
+        fn should_sample(
+            &self,
+            parent_context: Option<&api::SpanContext>,
+            _trace_id: api::TraceId,
+            _name: &str,
+            _span_kind: &api::SpanKind,
+            _attributes: &[api::KeyValue],
+            _links: &[api::Link],
+        ) -> api::SamplingResult {
+            let suppressed = parent_context
+                .and_then(|ctx| ctx.trace_state().get("foo"))
+                .map_or(false, |value| value == "notrace");
+            api::SamplingResult {
+                decision: if suppressed {
+                    api::SamplingDecision::NotRecord
+                } else {
+                    api::SamplingDecision::RecordAndSampled
+                },
+                attributes: Vec::new(),
+            }
+        }
+    }
+
+    fn remote_context(trace_state: &str) -> api::Context {
+        api::Context::new().with_remote_span_context(api::SpanContext::new(
+            api::TraceId::from_u128(1),
+            api::SpanId::from_u64(1),
+            0,
+            true,
+            trace_state.parse().unwrap(),
+        ))
+    }
+
+    #[test]
+    fn inherits_trace_state_from_parent() {
+        let provider = sdk::Provider::builder()
+            .with_config(sdk::Config {
+                default_sampler: Box::new(TestSampler),
+                ..Default::default()
+            })
+            .build();
+        let tracer = provider.get_tracer("test");
+
+        let span = tracer.start_from_context("span", &remote_context("foo=bar"));
+        let span_context = span.span_context();
+        assert!(span_context.is_sampled());
+        assert_eq!(span_context.trace_state().header(), "foo=bar");
+
+        let root = tracer.start_from_context("root", &api::Context::new());
+        assert!(root.span_context().trace_state().is_empty());
+
+        let suppressed = tracer.start_from_context("span", &remote_context("foo=notrace"));
+        assert!(!suppressed.is_recording());
+    }
+
+    #[derive(Debug)]
+    struct DecisionSampler(api::SamplingDecision);
+
+    impl api::Sampler for DecisionSampler {
+        fn should_sample(
+            &self,
+            _parent_context: Option<&api::SpanContext>,
+            _trace_id: api::TraceId,
+            _name: &str,
+            _span_kind: &api::SpanKind,
+            _attributes: &[api::KeyValue],
+            _links: &[api::Link],
+        ) -> api::SamplingResult {
+            api::SamplingResult {
+                decision: self.0.clone(),
+                attributes: Vec::new(),
+            }
+        }
+    }
+
+    /// Records the names of the spans passed to it.
+    #[derive(Clone, Debug, Default)]
+    struct TestProcessor {
+        started: Arc<Mutex<Vec<String>>>,
+        ended: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl api::SpanProcessor for TestProcessor {
+        fn on_start(&self, span: Arc<SpanData>) {
+            self.started.lock().unwrap().push(span.name.clone());
+        }
+
+        fn on_end(&self, span: Arc<SpanData>) {
+            self.ended.lock().unwrap().push(span.name.clone());
+        }
+
+        fn shutdown(&self) {}
+    }
+
+    #[derive(Clone, Debug, Default)]
+    struct TestExporter(Arc<Mutex<Vec<String>>>);
+
+    impl SpanExporter for TestExporter {
+        fn export(&self, batch: Vec<Arc<SpanData>>) -> ExportResult {
+            let mut exported = self.0.lock().unwrap();
+            exported.extend(batch.iter().map(|span| span.name.clone()));
+            ExportResult::Success
+        }
+
+        fn shutdown(&self) {}
+    }
+
+    /// Starts and ends a span and its child with the given sampling decision,
+    /// returning the spans seen by a processor and an exporter.
+    fn record(decision: api::SamplingDecision) -> (TestProcessor, TestExporter) {
+        let processor = TestProcessor::default();
+        let exporter = TestExporter::default();
+        let provider = sdk::Provider::builder()
+            .with_span_processor(processor.clone())
+            .with_simple_exporter(exporter.clone())
+            .with_config(sdk::Config {
+                default_sampler: Box::new(DecisionSampler(decision.clone())),
+                ..Default::default()
+            })
+            .build();
+        let tracer = provider.get_tracer("test");
+
+        let parent = tracer.start("parent");
+        let is_recording = decision != api::SamplingDecision::NotRecord;
+        let is_sampled = decision == api::SamplingDecision::RecordAndSampled;
+        assert_eq!(parent.is_recording(), is_recording);
+        assert_eq!(parent.span_context().is_sampled(), is_sampled);
+
+        let cx = api::Context::current_with_span(parent);
+        let child = tracer.start_from_context("child", &cx);
+        assert_eq!(child.is_recording(), is_recording);
+        assert_eq!(child.span_context().is_sampled(), is_sampled);
+        drop(child);
+        drop(cx);
+
+        (processor, exporter)
+    }
+
+    #[test]
+    fn record_and_sampled_spans_are_processed_and_exported() {
+        let (processor, exporter) = record(api::SamplingDecision::RecordAndSampled);
+
+        assert_eq!(*processor.started.lock().unwrap(), vec!["parent", "child"]);
+        assert_eq!(*processor.ended.lock().unwrap(), vec!["child", "parent"]);
+        assert_eq!(*exporter.0.lock().unwrap(), vec!["child", "parent"]);
+    }
+
+    #[test]
+    fn record_only_spans_are_processed_but_not_exported() {
+        let (processor, exporter) = record(api::SamplingDecision::Record);
+
+        assert_eq!(*processor.started.lock().unwrap(), vec!["parent", "child"]);
+        assert_eq!(*processor.ended.lock().unwrap(), vec!["child", "parent"]);
+        assert!(exporter.0.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn not_recorded_spans_are_neither_processed_nor_exported() {
+        let (processor, exporter) = record(api::SamplingDecision::NotRecord);
+
+        assert!(processor.started.lock().unwrap().is_empty());
+        assert!(processor.ended.lock().unwrap().is_empty());
+        assert!(exporter.0.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,422 @@
+//! # zPages Span Processor
+//!
+//! zPages are in-process pages showing live tracing data, useful for
+//! diagnosing a service without any backend. The [`ZPagesSpanProcessor`]
+//! tracks the spans of a provider:
+//!
+//! * the currently running spans,
+//! * the number of ended spans per name, by latency bucket and for errors,
+//! * a few sample spans for every latency bucket and for errors,
+//! * RPC statistics for `Client` and `Server` spans.
+//!
+//! The processor receives all recording spans, including spans that are
+//! recorded but not sampled. With the `zpages_server` feature, the data can
+//! be served as `/tracez` and `/rpcz` pages by the [`server`] module.
+//!
+//! To bound memory use, at most 1000 span names and 10000 running spans are
+//! tracked by default. Spans with further names are not counted and further
+//! started spans are not shown as running, see
+//! [`ZPagesSpanProcessor::with_max_span_names`] and
+//! [`ZPagesSpanProcessor::with_max_running_spans`].
+//!
+//! ```
+//! use opentelemetry::api::{Provider, Span, Tracer};
+//! use opentelemetry::sdk;
+//!
+//! let zpages = sdk::ZPagesSpanProcessor::new();
+//! let provider = sdk::Provider::builder()
+//!     .with_span_processor(zpages.clone())
+//!     .build();
+//!
+//! provider.get_tracer("my-component").start("GET /users").end();
+//! assert_eq!(zpages.summary()["GET /users"].latency.iter().sum::<u64>(), 1);
+//! ```
+//!
+//! [`ZPagesSpanProcessor`]: struct.ZPagesSpanProcessor.html
+//! [`server`]: server/index.html
+//! [`ZPagesSpanProcessor::with_max_span_names`]: struct.ZPagesSpanProcessor.html#method.with_max_span_names
+//! [`ZPagesSpanProcessor::with_max_running_spans`]: struct.ZPagesSpanProcessor.html#method.with_max_running_spans
+use crate::exporter::trace::SpanData;
+use crate::{api, exporter};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[cfg(feature = "zpages_server")]
+pub mod server;
+
+/// Upper bounds of the latency buckets, the last bucket holds all spans
+/// taking longer than the last bound.
+pub const LATENCY_BUCKET_BOUNDARIES: [Duration; 8] = [
+    Duration::from_micros(10),
+    Duration::from_micros(100),
+    Duration::from_millis(1),
+    Duration::from_millis(10),
+    Duration::from_millis(100),
+    Duration::from_secs(1),
+    Duration::from_secs(10),
+    Duration::from_secs(100),
+];
+/// Number of latency buckets.
+pub const LATENCY_BUCKETS: usize = LATENCY_BUCKET_BOUNDARIES.len() + 1;
+/// Default number of sample spans kept per bucket.
+const DEFAULT_SAMPLES_PER_BUCKET: usize = 5;
+/// Default maximum number of span names tracked.
+const DEFAULT_MAX_SPAN_NAMES: usize = 1000;
+/// Default maximum number of running spans tracked.
+const DEFAULT_MAX_RUNNING_SPANS: usize = 10_000;
+
+/// A [`SpanProcessor`] collecting the data shown on zPages.
+///
+/// Clones share the same data, so a clone can be registered with the
+/// provider while another is used to read the data.
+///
+/// [`SpanProcessor`]: ../../../api/trace/span_processor/trait.SpanProcessor.html
+#[derive(Clone, Debug)]
+pub struct ZPagesSpanProcessor {
+    data: Arc<Mutex<ZPagesData>>,
+    samples_per_bucket: usize,
+    max_span_names: usize,
+    max_running_spans: usize,
+}
+
+impl Default for ZPagesSpanProcessor {
+    fn default() -> Self {
+        ZPagesSpanProcessor {
+            data: Default::default(),
+            samples_per_bucket: DEFAULT_SAMPLES_PER_BUCKET,
+            max_span_names: DEFAULT_MAX_SPAN_NAMES,
+            max_running_spans: DEFAULT_MAX_RUNNING_SPANS,
+        }
+    }
+}
+
+impl ZPagesSpanProcessor {
+    /// Create a new zPages processor.
+    pub fn new() -> Self {
+        ZPagesSpanProcessor::default()
+    }
+
+    /// Assign the number of sample spans kept per latency bucket and for
+    /// errors, 5 by default.
+    pub fn with_samples_per_bucket(self, samples_per_bucket: usize) -> Self {
+        ZPagesSpanProcessor {
+            samples_per_bucket,
+            ..self
+        }
+    }
+
+    /// Assign the maximum number of span names tracked, 1000 by default.
+    /// Once reached, ended spans with other names are ignored.
+    pub fn with_max_span_names(self, max_span_names: usize) -> Self {
+        ZPagesSpanProcessor {
+            max_span_names,
+            ..self
+        }
+    }
+
+    /// Assign the maximum number of running spans tracked, 10000 by default.
+    /// Once reached, started spans are not shown as running until others end.
+    pub fn with_max_running_spans(self, max_running_spans: usize) -> Self {
+        ZPagesSpanProcessor {
+            max_running_spans,
+            ..self
+        }
+    }
+
+    /// Counts of the spans seen per span name.
+    pub fn summary(&self) -> BTreeMap<String, SpanNameSummary> {
+        let data = self.lock();
+        let mut summary = data
+            .names
+            .iter()
+            .map(|(name, name_data)| {
+                let mut latency = [0; LATENCY_BUCKETS];
+                for (count, bucket) in latency.iter_mut().zip(name_data.latency.iter()) {
+                    *count = bucket.count;
+                }
+                let summary = SpanNameSummary {
+                    running: 0,
+                    latency,
+                    errors: name_data.errors.count,
+                    client: name_data.client.clone(),
+                    server: name_data.server.clone(),
+                };
+                (name.clone(), summary)
+            })
+            .collect::<BTreeMap<_, _>>();
+        for span in data.running.values() {
+            summary.entry(span.name.clone()).or_default().running += 1;
+        }
+
+        summary
+    }
+
+    /// The currently running spans with the given name.
+    pub fn running_spans(&self, name: &str) -> Vec<Arc<SpanData>> {
+        self.lock()
+            .running
+            .values()
+            .filter(|span| span.name == name)
+            .cloned()
+            .collect()
+    }
+
+    /// Sample spans with the given name that ended without error, in the
+    /// given latency bucket. See [`LATENCY_BUCKET_BOUNDARIES`].
+    ///
+    /// [`LATENCY_BUCKET_BOUNDARIES`]: constant.LATENCY_BUCKET_BOUNDARIES.html
+    pub fn latency_samples(&self, name: &str, bucket: usize) -> Vec<Arc<SpanData>> {
+        self.lock()
+            .names
+            .get(name)
+            .and_then(|name_data| name_data.latency.get(bucket))
+            .map(|bucket| bucket.samples.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Sample spans with the given name that ended with an error.
+    pub fn error_samples(&self, name: &str) -> Vec<Arc<SpanData>> {
+        self.lock()
+            .names
+            .get(name)
+            .map(|name_data| name_data.errors.samples.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, ZPagesData> {
+        self.data
+            .lock()
+            .expect("ZPagesSpanProcessor Mutex poisoned")
+    }
+}
+
+impl api::SpanProcessor for ZPagesSpanProcessor {
+    fn on_start(&self, span: Arc<exporter::trace::SpanData>) {
+        let key = (span.span_context.trace_id(), span.span_context.span_id());
+        let mut data = self.lock();
+        if data.running.len() < self.max_running_spans {
+            data.running.insert(key, span);
+        }
+    }
+
+    fn on_end(&self, span: Arc<exporter::trace::SpanData>) {
+        let key = (span.span_context.trace_id(), span.span_context.span_id());
+        let latency = span
+            .end_time
+            .duration_since(span.start_time)
+            .unwrap_or_default();
+        let is_error = span.status_code != api::StatusCode::OK;
+
+        let mut data = self.lock();
+        data.running.remove(&key);
+        if !data.names.contains_key(&span.name) && data.names.len() >= self.max_span_names {
+            return;
+        }
+        let name_data = data.names.entry(span.name.clone()).or_default();
+        match span.span_kind {
+            api::SpanKind::Client => name_data.client.record(latency, is_error),
+            api::SpanKind::Server => name_data.server.record(latency, is_error),
+            _ => {}
+        }
+        let bucket = if is_error {
+            &mut name_data.errors
+        } else {
+            &mut name_data.latency[latency_bucket(latency)]
+        };
+        bucket.count += 1;
+        if self.samples_per_bucket > 0 {
+            if bucket.samples.len() == self.samples_per_bucket {
+                bucket.samples.pop_front();
+            }
+            bucket.samples.push_back(span);
+        }
+    }
+
+    fn shutdown(&self) {}
+}
+
+/// The index of the latency bucket holding spans of the given duration.
+pub fn latency_bucket(latency: Duration) -> usize {
+    LATENCY_BUCKET_BOUNDARIES
+        .iter()
+        .position(|bound| latency < *bound)
+        .unwrap_or(LATENCY_BUCKET_BOUNDARIES.len())
+}
+
+/// Counts of the spans with a given name.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SpanNameSummary {
+    /// Spans currently running.
+    pub running: usize,
+    /// Spans that ended without error, per latency bucket.
+    pub latency: [u64; LATENCY_BUCKETS],
+    /// Spans that ended with an error.
+    pub errors: u64,
+    /// Statistics of the ended `Client` spans.
+    pub client: RpcStats,
+    /// Statistics of the ended `Server` spans.
+    pub server: RpcStats,
+}
+
+/// Statistics of the RPCs with a given name.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RpcStats {
+    /// Number of RPCs.
+    pub count: u64,
+    /// Number of RPCs that ended with an error.
+    pub errors: u64,
+    /// Total latency of all RPCs.
+    pub total_latency: Duration,
+}
+
+impl RpcStats {
+    /// The average latency of the RPCs.
+    pub fn average_latency(&self) -> Duration {
+        if self.count == 0 {
+            Duration::default()
+        } else {
+            let nanos = self.total_latency.as_nanos() / u128::from(self.count);
+            Duration::new(
+                (nanos / 1_000_000_000) as u64,
+                (nanos % 1_000_000_000) as u32,
+            )
+        }
+    }
+
+    fn record(&mut self, latency: Duration, is_error: bool) {
+        self.count += 1;
+        if is_error {
+            self.errors += 1;
+        }
+        self.total_latency += latency;
+    }
+}
+
+#[derive(Debug, Default)]
+struct ZPagesData {
+    running: HashMap<(api::TraceId, api::SpanId), Arc<SpanData>>,
+    names: HashMap<String, SpanNameData>,
+}
+
+#[derive(Debug, Default)]
+struct SpanNameData {
+    latency: [Bucket; LATENCY_BUCKETS],
+    errors: Bucket,
+    client: RpcStats,
+    server: RpcStats,
+}
+
+#[derive(Debug, Default)]
+struct Bucket {
+    count: u64,
+    samples: VecDeque<Arc<SpanData>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::SpanProcessor;
+    use crate::sdk;
+    use std::time::SystemTime;
+
+    fn span(
+        span_id: u64,
+        name: &str,
+        latency: Duration,
+        status_code: api::StatusCode,
+    ) -> Arc<SpanData> {
+        let start_time = SystemTime::now();
+        Arc::new(SpanData {
+            span_context: api::SpanContext::new(
+                api::TraceId::from_u128(1),
+                api::SpanId::from_u64(span_id),
+                api::TRACE_FLAG_SAMPLED,
+                false,
+                api::TraceState::default(),
+            ),
+            parent_span_id: api::SpanId::invalid(),
+            span_kind: api::SpanKind::Server,
+            name: name.to_string(),
+            start_time,
+            end_time: start_time + latency,
+            attributes: sdk::EvictedHashMap::new(0),
+            message_events: sdk::EvictedQueue::new(0),
+            links: sdk::EvictedQueue::new(0),
+            status_code,
+            status_message: String::new(),
+            resource: Arc::new(sdk::Resource::default()),
+        })
+    }
+
+    #[test]
+    fn buckets_latencies() {
+        assert_eq!(latency_bucket(Duration::from_micros(1)), 0);
+        assert_eq!(latency_bucket(Duration::from_micros(10)), 1);
+        assert_eq!(latency_bucket(Duration::from_millis(50)), 4);
+        assert_eq!(latency_bucket(Duration::from_secs(1000)), 8);
+    }
+
+    #[test]
+    fn averages_large_rpc_counts() {
+        let stats = RpcStats {
+            count: 1 << 32,
+            errors: 0,
+            total_latency: Duration::from_secs(1 << 32),
+        };
+
+        assert_eq!(stats.average_latency(), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn limits_tracked_names_and_running_spans() {
+        let processor = ZPagesSpanProcessor::new()
+            .with_max_span_names(1)
+            .with_max_running_spans(1);
+        let ended = |span_id, name| span(span_id, name, Duration::default(), api::StatusCode::OK);
+        for (span_id, name) in [(1, "first"), (2, "second")].iter() {
+            processor.on_start(ended(*span_id, name));
+            assert_eq!(processor.running_spans(name).len(), 1);
+            processor.on_end(ended(*span_id, name));
+        }
+        processor.on_start(ended(3, "first"));
+        processor.on_start(ended(4, "first"));
+
+        let summary = processor.summary();
+        assert_eq!(summary.keys().collect::<Vec<_>>(), vec!["first"]);
+        assert_eq!(summary["first"].running, 1);
+    }
+
+    #[test]
+    fn tracks_running_and_ended_spans() {
+        let processor = ZPagesSpanProcessor::new().with_samples_per_bucket(1);
+        let running = span(1, "op", Duration::from_millis(0), api::StatusCode::OK);
+        processor.on_start(running.clone());
+        for span_id in 2..4 {
+            let ended = span(span_id, "op", Duration::from_millis(5), api::StatusCode::OK);
+            processor.on_start(ended.clone());
+            processor.on_end(ended);
+        }
+        processor.on_end(span(
+            4,
+            "op",
+            Duration::from_millis(5),
+            api::StatusCode::Internal,
+        ));
+
+        let summary = &processor.summary()["op"];
+        assert_eq!(summary.running, 1);
+        assert_eq!(summary.latency[3], 2);
+        assert_eq!(summary.errors, 1);
+        assert_eq!(summary.server.count, 3);
+        assert_eq!(summary.server.errors, 1);
+        assert_eq!(summary.server.average_latency(), Duration::from_millis(5));
+
+        assert_eq!(processor.running_spans("op"), vec![running]);
+        let samples = processor.latency_samples("op", 3);
+        assert_eq!(samples.len(), 1);
+        assert_eq!(samples[0].span_context.span_id(), api::SpanId::from_u64(3));
+        assert_eq!(processor.error_samples("op").len(), 1);
+        assert!(processor.latency_samples("other", 3).is_empty());
+    }
+}
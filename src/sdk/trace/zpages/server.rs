@@ -0,0 +1,454 @@
+//! # zPages HTTP endpoint
+//!
+//! Serves the data of a [`ZPagesSpanProcessor`] as HTML pages:
+//!
+//! * `/tracez` lists the span names with their running, latency bucket and
+//!   error counts, linking to the running and sample spans.
+//! * `/rpcz` lists the count, average latency and errors of the `Client` and
+//!   `Server` spans per name.
+//!
+//! The [`Server`] binds `127.0.0.1:55679` by default, as the pages expose
+//! span data. Applications that already run an HTTP server can use
+//! [`response`] from a hyper service, or [`tracez`] and [`rpcz`] with any
+//! other framework.
+//!
+//! ```no_run
+//! use opentelemetry::sdk::{self, trace::zpages::server::Server};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let zpages = sdk::ZPagesSpanProcessor::new();
+//!     let _provider = sdk::Provider::builder()
+//!         .with_span_processor(zpages.clone())
+//!         .build();
+//!
+//!     let server = Server::builder(zpages)
+//!         .bind()
+//!         .expect("failed to bind zPages server");
+//!     server.await.expect("zPages server failed");
+//! }
+//! ```
+//!
+//! [`ZPagesSpanProcessor`]: ../struct.ZPagesSpanProcessor.html
+//! [`Server`]: struct.Server.html
+//! [`response`]: fn.response.html
+//! [`tracez`]: fn.tracez.html
+//! [`rpcz`]: fn.rpcz.html
+use super::{SpanNameSummary, ZPagesSpanProcessor, LATENCY_BUCKETS, LATENCY_BUCKET_BOUNDARIES};
+use crate::exporter::trace::SpanData;
+use hyper::header::{HeaderValue, CONTENT_TYPE};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, StatusCode};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, NON_ALPHANUMERIC};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::fmt::{self, Write};
+use std::future::Future;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Default address the server binds to, the port used for zPages by the
+/// OpenTelemetry collector.
+const DEFAULT_ADDRESS: ([u8; 4], u16) = ([127, 0, 0, 1], 55679);
+/// Path of the trace page.
+const TRACEZ_PATH: &str = "/tracez";
+/// Path of the RPC page.
+const RPCZ_PATH: &str = "/rpcz";
+
+/// Renders the `/tracez` page for the given query string.
+///
+/// Without query the page shows the summary of all span names. The
+/// `zspanname` parameter selects the spans of a name, with `ztype` `0` for the
+/// running spans, `1` for the latency samples of the `zlatencybucket` bucket
+/// and `2` for the error samples.
+pub fn tracez(processor: &ZPagesSpanProcessor, query: Option<&str>) -> String {
+    let query = query.unwrap_or("");
+    let mut page = header("TraceZ");
+    match query_param(query, "zspanname") {
+        Some(name) => {
+            let (title, spans) = match query_param(query, "ztype").as_deref() {
+                Some("0") => (Cow::Borrowed("Running"), processor.running_spans(&name)),
+                Some("2") => (Cow::Borrowed("Errors"), processor.error_samples(&name)),
+                _ => {
+                    let bucket = query_param(query, "zlatencybucket")
+                        .and_then(|bucket| bucket.parse().ok())
+                        .unwrap_or(0);
+                    let title = bucket_label(bucket);
+                    (title, processor.latency_samples(&name, bucket))
+                }
+            };
+            let _ = writeln!(
+                page,
+                "<h2>{}: {}</h2>",
+                escape(&name),
+                escape(title.as_ref())
+            );
+            write_spans(&mut page, &spans);
+        }
+        None => write_summary(&mut page, &processor.summary()),
+    }
+    page.push_str("</body></html>\n");
+    page
+}
+
+/// Renders the `/rpcz` page.
+pub fn rpcz(processor: &ZPagesSpanProcessor) -> String {
+    let summary = processor.summary();
+    let mut page = header("RpcZ");
+    for (title, is_client) in &[("Sent", true), ("Received", false)] {
+        let _ = writeln!(page, "<h2>{}</h2>", title);
+        page.push_str(
+            "<table><tr><th>Method</th><th>Count</th><th>Avg latency</th><th>Errors</th></tr>\n",
+        );
+        for (name, name_summary) in &summary {
+            let stats = if *is_client {
+                &name_summary.client
+            } else {
+                &name_summary.server
+            };
+            if stats.count == 0 {
+                continue;
+            }
+            let _ = writeln!(
+                page,
+                "<tr><td>{}</td><td>{}</td><td>{:?}</td><td>{}</td></tr>",
+                escape(name),
+                stats.count,
+                stats.average_latency(),
+                stats.errors
+            );
+        }
+        page.push_str("</table>\n");
+    }
+    page.push_str("</body></html>\n");
+    page
+}
+
+/// Builds the response to a request for one of the pages, `404 Not Found` for
+/// other paths.
+pub fn response(processor: &ZPagesSpanProcessor, req: &Request<Body>) -> Response<Body> {
+    let page = match req.uri().path() {
+        TRACEZ_PATH => tracez(processor, req.uri().query()),
+        RPCZ_PATH => rpcz(processor),
+        _ => return status_response(StatusCode::NOT_FOUND, "Not Found"),
+    };
+    if req.method() != Method::GET && req.method() != Method::HEAD {
+        return status_response(StatusCode::METHOD_NOT_ALLOWED, "Method Not Allowed");
+    }
+
+    let mut response = Response::new(Body::from(page));
+    response.headers_mut().insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static("text/html; charset=utf-8"),
+    );
+    response
+}
+
+fn status_response(status: StatusCode, body: &'static str) -> Response<Body> {
+    let mut response = Response::new(Body::from(body));
+    *response.status_mut() = status;
+    response
+}
+
+fn header(title: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{0}</title></head><body>\n<h1>{0}</h1>\n",
+        title
+    )
+}
+
+fn write_summary(page: &mut String, summary: &BTreeMap<String, SpanNameSummary>) {
+    page.push_str("<table><tr><th>Span Name</th><th>Running</th>");
+    for bucket in 0..LATENCY_BUCKETS {
+        let _ = write!(page, "<th>{}</th>", escape(&bucket_label(bucket)));
+    }
+    page.push_str("<th>Errors</th></tr>\n");
+
+    for (name, name_summary) in summary {
+        let link = format!("?zspanname={}", utf8_percent_encode(name, NON_ALPHANUMERIC));
+        let _ = write!(
+            page,
+            "<tr><td>{}</td><td><a href=\"{}&amp;ztype=0\">{}</a></td>",
+            escape(name),
+            link,
+            name_summary.running
+        );
+        for (bucket, count) in name_summary.latency.iter().enumerate() {
+            let _ = write!(
+                page,
+                "<td><a href=\"{}&amp;ztype=1&amp;zlatencybucket={}\">{}</a></td>",
+                link, bucket, count
+            );
+        }
+        let _ = writeln!(
+            page,
+            "<td><a href=\"{}&amp;ztype=2\">{}</a></td></tr>",
+            link, name_summary.errors
+        );
+    }
+    page.push_str("</table>\n");
+}
+
+fn write_spans(page: &mut String, spans: &[Arc<SpanData>]) {
+    page.push_str(
+        "<table><tr><th>Start</th><th>Latency</th><th>Trace Id</th><th>Span Id</th>\
+         <th>Parent Span Id</th><th>Status</th><th>Attributes</th><th>Events</th></tr>\n",
+    );
+    for span in spans {
+        let latency = span
+            .end_time
+            .duration_since(span.start_time)
+            .unwrap_or_default();
+        let attributes = span
+            .attributes
+            .iter()
+            .map(|(key, value)| format!("{}={}", key.as_str(), String::from(value.clone())))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let events = span
+            .message_events
+            .iter()
+            .map(|event| format!("{}: {}", timestamp(event.timestamp), event.name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let _ = writeln!(
+            page,
+            "<tr><td>{}</td><td>{:?}</td><td>{:032x}</td><td>{:016x}</td><td>{:016x}</td>\
+             <td>{:?} {}</td><td>{}</td><td>{}</td></tr>",
+            timestamp(span.start_time),
+            latency,
+            span.span_context.trace_id().to_u128(),
+            span.span_context.span_id().to_u64(),
+            span.parent_span_id.to_u64(),
+            span.status_code,
+            escape(&span.status_message),
+            escape(&attributes),
+            escape(&events),
+        );
+    }
+    page.push_str("</table>\n");
+}
+
+/// Label of a latency bucket, e.g. `>=10µs`.
+fn bucket_label(bucket: usize) -> Cow<'static, str> {
+    match bucket {
+        0 => Cow::Borrowed(">=0s"),
+        bucket if bucket < LATENCY_BUCKETS => {
+            Cow::Owned(format!(">={:?}", LATENCY_BUCKET_BOUNDARIES[bucket - 1]))
+        }
+        _ => Cow::Borrowed("unknown bucket"),
+    }
+}
+
+/// Seconds since the Unix epoch with microsecond precision.
+fn timestamp(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    format!(
+        "{}.{:06}",
+        since_epoch.as_secs(),
+        since_epoch.subsec_micros()
+    )
+}
+
+/// Looks up a percent-encoded query parameter.
+fn query_param<'a>(query: &'a str, name: &str) -> Option<Cow<'a, str>> {
+    query.split('&').find_map(|param| {
+        let mut parts = param.splitn(2, '=');
+        if parts.next() != Some(name) {
+            return None;
+        }
+        let value = parts.next().unwrap_or("");
+        if value.contains('+') {
+            let value = value.replace('+', " ");
+            Some(Cow::Owned(
+                percent_decode_str(&value).decode_utf8_lossy().into_owned(),
+            ))
+        } else {
+            Some(percent_decode_str(value).decode_utf8_lossy())
+        }
+    })
+}
+
+/// Escapes text for use in HTML.
+fn escape(text: &str) -> Cow<'_, str> {
+    if !text.contains(|c| "<>&\"'".contains(c)) {
+        return Cow::Borrowed(text);
+    }
+
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
+}
+
+/// Builder for a `Server`.
+#[derive(Debug)]
+pub struct Builder {
+    processor: ZPagesSpanProcessor,
+    address: SocketAddr,
+}
+
+impl Builder {
+    /// Address to listen on, `127.0.0.1:55679` by default.
+    pub fn with_address(self, address: SocketAddr) -> Self {
+        Builder { address, ..self }
+    }
+
+    /// Binds the configured address. The returned server only handles
+    /// requests while it is polled.
+    ///
+    /// This must be called from within a Tokio runtime.
+    pub fn bind(self) -> Result<Server, hyper::Error> {
+        let Builder { processor, address } = self;
+
+        let make_service = make_service_fn(move |_conn| {
+            let processor = processor.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |req| {
+                    let response = response(&processor, &req);
+                    async move { Ok::<_, Infallible>(response) }
+                }))
+            }
+        });
+
+        let server = hyper::Server::try_bind(&address)?.serve(make_service);
+        Ok(Server {
+            local_addr: server.local_addr(),
+            server: Box::pin(server),
+        })
+    }
+}
+
+/// An HTTP server serving zPages, running until an error occurs while it is
+/// awaited.
+pub struct Server {
+    local_addr: SocketAddr,
+    server: Pin<Box<dyn Future<Output = Result<(), hyper::Error>> + Send>>,
+}
+
+impl Server {
+    /// Create a new server builder serving the data of the given processor.
+    pub fn builder(processor: ZPagesSpanProcessor) -> Builder {
+        Builder {
+            processor,
+            address: DEFAULT_ADDRESS.into(),
+        }
+    }
+
+    /// The address the server is listening on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+}
+
+impl fmt::Debug for Server {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Server")
+            .field("local_addr", &self.local_addr)
+            .finish()
+    }
+}
+
+impl Future for Server {
+    type Output = Result<(), hyper::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.server.as_mut().poll(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{Provider, Span, Tracer};
+    use crate::{api, sdk};
+    use hyper::Client;
+    use std::time::Duration;
+
+    fn processor_with_spans() -> (ZPagesSpanProcessor, sdk::Span) {
+        let processor = ZPagesSpanProcessor::new();
+        let provider = sdk::Provider::builder()
+            .with_span_processor(processor.clone())
+            .build();
+        let tracer = provider.get_tracer("zpages");
+        tracer
+            .span_builder("GET /<users>")
+            .with_kind(api::SpanKind::Server)
+            .with_start_time(SystemTime::now() - Duration::from_millis(20))
+            .start(&tracer)
+            .end();
+        let running = tracer.start("running");
+
+        (processor, running)
+    }
+
+    #[test]
+    fn renders_tracez() {
+        let (processor, _running) = processor_with_spans();
+
+        let summary = tracez(&processor, None);
+        assert!(summary.contains("<td>GET /&lt;users&gt;</td>"));
+        assert!(summary.contains(
+            "<a href=\"?zspanname=GET%20%2F%3Cusers%3E&amp;ztype=1&amp;zlatencybucket=4\">1</a>"
+        ));
+        assert!(summary.contains("<a href=\"?zspanname=running&amp;ztype=0\">1</a>"));
+
+        let samples = tracez(
+            &processor,
+            Some("zspanname=GET+%2F%3Cusers%3E&ztype=1&zlatencybucket=4"),
+        );
+        assert!(samples.contains("<h2>GET /&lt;users&gt;: &gt;=10ms</h2>"));
+        assert_eq!(samples.matches("<tr><td>").count(), 1);
+    }
+
+    #[test]
+    fn renders_rpcz() {
+        let (processor, _running) = processor_with_spans();
+
+        let page = rpcz(&processor);
+        let received = &page[page.find("<h2>Received</h2>").unwrap()..];
+        assert!(received.contains("<tr><td>GET /&lt;users&gt;</td><td>1</td>"));
+        assert!(!page.contains("<td>running</td>"));
+    }
+
+    #[tokio::test]
+    async fn serves_pages() {
+        let (processor, _running) = processor_with_spans();
+        let server = Server::builder(processor)
+            .with_address(([127, 0, 0, 1], 0).into())
+            .bind()
+            .unwrap();
+        let address = server.local_addr();
+        tokio::spawn(server);
+
+        let client = Client::new();
+        let response = client
+            .get(format!("http://{}/tracez", address).parse().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[CONTENT_TYPE], "text/html; charset=utf-8");
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert!(String::from_utf8(body.to_vec())
+            .unwrap()
+            .contains("GET /&lt;users&gt;"));
+
+        let response = client
+            .get(format!("http://{}/metrics", address).parse().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
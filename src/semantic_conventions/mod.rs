@@ -0,0 +1,21 @@
+//! # OpenTelemetry Semantic Conventions
+//!
+//! The [semantic conventions] define well-known names for span attributes
+//! and resource attributes, so telemetry from different libraries and
+//! services can be interpreted consistently by backends.
+//!
+//! The conventions are provided as `api::Key` constants:
+//!
+//! ```
+//! use opentelemetry::api::{Span, Tracer};
+//! use opentelemetry::global;
+//! use opentelemetry::semantic_conventions as semcov;
+//!
+//! let span = global::tracer("my-component").start("GET /users");
+//! span.set_attribute(semcov::trace::HTTP_METHOD.string("GET"));
+//! span.set_attribute(semcov::trace::HTTP_STATUS_CODE.i64(200));
+//! ```
+//!
+//! [semantic conventions]: https://github.com/open-telemetry/opentelemetry-specification/tree/master/specification/trace/semantic_conventions
+pub mod resource;
+pub mod trace;
@@ -0,0 +1,83 @@
+//! # Resource Semantic Conventions
+//!
+//! Well-known resource attribute names, see the [specification] for the
+//! expected values.
+//!
+//! [specification]: https://github.com/open-telemetry/opentelemetry-specification/tree/master/specification/resource/semantic_conventions
+use crate::api::Key;
+
+// Service attributes.
+
+/// Logical name of the service, e.g. `shoppingcart`.
+pub const SERVICE_NAME: Key = Key::from_static_str("service.name");
+/// A namespace for `service.name`, e.g. `Shop`.
+pub const SERVICE_NAMESPACE: Key = Key::from_static_str("service.namespace");
+/// The id of the service instance, unique within its namespace and name.
+pub const SERVICE_INSTANCE_ID: Key = Key::from_static_str("service.instance.id");
+/// The version of the service, e.g. `2.0.0`.
+pub const SERVICE_VERSION: Key = Key::from_static_str("service.version");
+
+// Telemetry SDK attributes.
+
+/// The name of the telemetry SDK, e.g. `opentelemetry`.
+pub const TELEMETRY_SDK_NAME: Key = Key::from_static_str("telemetry.sdk.name");
+/// The language of the telemetry SDK, e.g. `rust`.
+pub const TELEMETRY_SDK_LANGUAGE: Key = Key::from_static_str("telemetry.sdk.language");
+/// The version of the telemetry SDK.
+pub const TELEMETRY_SDK_VERSION: Key = Key::from_static_str("telemetry.sdk.version");
+
+// Host attributes.
+
+/// Unique host id, e.g. the instance id of a cloud provider.
+pub const HOST_ID: Key = Key::from_static_str("host.id");
+/// Name of the host, e.g. as returned by the `hostname` command.
+pub const HOST_NAME: Key = Key::from_static_str("host.name");
+/// Type of the host, e.g. the machine type of a cloud provider.
+pub const HOST_TYPE: Key = Key::from_static_str("host.type");
+/// The CPU architecture of the host, e.g. `amd64` or `arm64`.
+pub const HOST_ARCH: Key = Key::from_static_str("host.arch");
+/// Name of the VM image or OS install the host was instantiated from.
+pub const HOST_IMAGE_NAME: Key = Key::from_static_str("host.image.name");
+/// Id of the VM image the host was instantiated from.
+pub const HOST_IMAGE_ID: Key = Key::from_static_str("host.image.id");
+/// The version of the VM image the host was instantiated from.
+pub const HOST_IMAGE_VERSION: Key = Key::from_static_str("host.image.version");
+
+// Operating system attributes.
+
+/// The operating system type, e.g. `linux`, `windows` or `darwin`.
+pub const OS_TYPE: Key = Key::from_static_str("os.type");
+/// Human readable OS version information, e.g. as reported by `uname -srm`.
+pub const OS_DESCRIPTION: Key = Key::from_static_str("os.description");
+
+// Process attributes.
+
+/// Process identifier.
+pub const PROCESS_PID: Key = Key::from_static_str("process.pid");
+/// The name of the process executable.
+pub const PROCESS_EXECUTABLE_NAME: Key = Key::from_static_str("process.executable.name");
+/// The full path to the process executable.
+pub const PROCESS_EXECUTABLE_PATH: Key = Key::from_static_str("process.executable.path");
+/// The command used to launch the process, i.e. the first argument.
+pub const PROCESS_COMMAND: Key = Key::from_static_str("process.command");
+/// The full command used to launch the process as a single string.
+pub const PROCESS_COMMAND_LINE: Key = Key::from_static_str("process.command_line");
+/// The username of the user that owns the process.
+pub const PROCESS_OWNER: Key = Key::from_static_str("process.owner");
+/// The name of the runtime of this process.
+pub const PROCESS_RUNTIME_NAME: Key = Key::from_static_str("process.runtime.name");
+/// The version of the runtime of this process.
+pub const PROCESS_RUNTIME_VERSION: Key = Key::from_static_str("process.runtime.version");
+/// An additional description about the runtime of the process.
+pub const PROCESS_RUNTIME_DESCRIPTION: Key = Key::from_static_str("process.runtime.description");
+
+// Container attributes.
+
+/// Container id, usually a UUID.
+pub const CONTAINER_ID: Key = Key::from_static_str("container.id");
+/// Container name.
+pub const CONTAINER_NAME: Key = Key::from_static_str("container.name");
+/// Name of the image the container was built on.
+pub const CONTAINER_IMAGE_NAME: Key = Key::from_static_str("container.image.name");
+/// Container image tag.
+pub const CONTAINER_IMAGE_TAG: Key = Key::from_static_str("container.image.tag");
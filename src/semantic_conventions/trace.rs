@@ -0,0 +1,147 @@
+//! # Trace Semantic Conventions
+//!
+//! Well-known span attribute names, see the [specification] for the expected
+//! values and when to set them.
+//!
+//! [specification]: https://github.com/open-telemetry/opentelemetry-specification/tree/master/specification/trace/semantic_conventions
+use crate::api::Key;
+
+// General network connection attributes.
+
+/// Transport protocol used, e.g. `IP.TCP`, `IP.UDP` or `Unix`.
+pub const NET_TRANSPORT: Key = Key::from_static_str("net.transport");
+/// Remote address of the peer, e.g. `127.0.0.1` or `::1`.
+pub const NET_PEER_IP: Key = Key::from_static_str("net.peer.ip");
+/// Remote port number.
+pub const NET_PEER_PORT: Key = Key::from_static_str("net.peer.port");
+/// Remote host name or similar.
+pub const NET_PEER_NAME: Key = Key::from_static_str("net.peer.name");
+/// Local address the connection was made on.
+pub const NET_HOST_IP: Key = Key::from_static_str("net.host.ip");
+/// Local port number.
+pub const NET_HOST_PORT: Key = Key::from_static_str("net.host.port");
+/// Local host name.
+pub const NET_HOST_NAME: Key = Key::from_static_str("net.host.name");
+/// The logical name of the remote service, e.g. `AuthTokenCache`.
+pub const PEER_SERVICE: Key = Key::from_static_str("peer.service");
+
+// End user attributes.
+
+/// Username or client id of the user making the request.
+pub const ENDUSER_ID: Key = Key::from_static_str("enduser.id");
+/// Roles the user is assigned.
+pub const ENDUSER_ROLE: Key = Key::from_static_str("enduser.role");
+/// Scopes or granted authorities of the user.
+pub const ENDUSER_SCOPE: Key = Key::from_static_str("enduser.scope");
+
+// HTTP attributes.
+
+/// HTTP request method, e.g. `GET`.
+pub const HTTP_METHOD: Key = Key::from_static_str("http.method");
+/// Full HTTP request URL, e.g. `https://example.com:8080/users?id=1`.
+pub const HTTP_URL: Key = Key::from_static_str("http.url");
+/// The full request target as passed in the request line, e.g. `/users?id=1`.
+pub const HTTP_TARGET: Key = Key::from_static_str("http.target");
+/// The value of the HTTP host header.
+pub const HTTP_HOST: Key = Key::from_static_str("http.host");
+/// The URI scheme, e.g. `http` or `https`.
+pub const HTTP_SCHEME: Key = Key::from_static_str("http.scheme");
+/// HTTP response status code, e.g. `200`.
+pub const HTTP_STATUS_CODE: Key = Key::from_static_str("http.status_code");
+/// HTTP response reason phrase, e.g. `OK`.
+pub const HTTP_STATUS_TEXT: Key = Key::from_static_str("http.status_text");
+/// The HTTP protocol version, e.g. `1.1` or `2`.
+pub const HTTP_FLAVOR: Key = Key::from_static_str("http.flavor");
+/// Value of the HTTP User-Agent header sent by the client.
+pub const HTTP_USER_AGENT: Key = Key::from_static_str("http.user_agent");
+/// The size of the request payload body in bytes.
+pub const HTTP_REQUEST_CONTENT_LENGTH: Key = Key::from_static_str("http.request_content_length");
+/// The size of the response payload body in bytes.
+pub const HTTP_RESPONSE_CONTENT_LENGTH: Key = Key::from_static_str("http.response_content_length");
+/// The primary server name of the matched virtual host.
+pub const HTTP_SERVER_NAME: Key = Key::from_static_str("http.server_name");
+/// The matched route, e.g. `/users/:id`.
+pub const HTTP_ROUTE: Key = Key::from_static_str("http.route");
+/// The IP address of the original client behind all proxies.
+pub const HTTP_CLIENT_IP: Key = Key::from_static_str("http.client_ip");
+
+// RPC attributes.
+
+/// A string identifying the remoting system, e.g. `grpc`.
+pub const RPC_SYSTEM: Key = Key::from_static_str("rpc.system");
+/// The full name of the service being called, e.g. `myservice.EchoService`.
+pub const RPC_SERVICE: Key = Key::from_static_str("rpc.service");
+/// The name of the method being called, e.g. `Echo`.
+pub const RPC_METHOD: Key = Key::from_static_str("rpc.method");
+/// The numeric gRPC status code of the response.
+pub const RPC_GRPC_STATUS_CODE: Key = Key::from_static_str("rpc.grpc.status_code");
+
+// Database attributes.
+
+/// An identifier for the database management system, e.g. `postgresql`.
+pub const DB_SYSTEM: Key = Key::from_static_str("db.system");
+/// The connection string used to connect to the database, without credentials.
+pub const DB_CONNECTION_STRING: Key = Key::from_static_str("db.connection_string");
+/// Username for accessing the database.
+pub const DB_USER: Key = Key::from_static_str("db.user");
+/// The name of the database being accessed.
+pub const DB_NAME: Key = Key::from_static_str("db.name");
+/// The database statement being executed.
+pub const DB_STATEMENT: Key = Key::from_static_str("db.statement");
+/// The name of the operation being executed, e.g. `SELECT` or `findAndModify`.
+pub const DB_OPERATION: Key = Key::from_static_str("db.operation");
+
+// Messaging attributes.
+
+/// A string identifying the messaging system, e.g. `kafka`.
+pub const MESSAGING_SYSTEM: Key = Key::from_static_str("messaging.system");
+/// The message destination name, e.g. a queue or topic name.
+pub const MESSAGING_DESTINATION: Key = Key::from_static_str("messaging.destination");
+/// The kind of message destination, `queue` or `topic`.
+pub const MESSAGING_DESTINATION_KIND: Key = Key::from_static_str("messaging.destination_kind");
+/// Whether the message destination is temporary.
+pub const MESSAGING_TEMP_DESTINATION: Key = Key::from_static_str("messaging.temp_destination");
+/// The name of the transport protocol, e.g. `AMQP`.
+pub const MESSAGING_PROTOCOL: Key = Key::from_static_str("messaging.protocol");
+/// The version of the transport protocol.
+pub const MESSAGING_PROTOCOL_VERSION: Key = Key::from_static_str("messaging.protocol_version");
+/// Connection string of the messaging system.
+pub const MESSAGING_URL: Key = Key::from_static_str("messaging.url");
+/// A value used by the messaging system as an identifier for the message.
+pub const MESSAGING_MESSAGE_ID: Key = Key::from_static_str("messaging.message_id");
+/// The conversation, or correlation, id of the message.
+pub const MESSAGING_CONVERSATION_ID: Key = Key::from_static_str("messaging.conversation_id");
+/// The size of the uncompressed message payload in bytes.
+pub const MESSAGING_MESSAGE_PAYLOAD_SIZE_BYTES: Key =
+    Key::from_static_str("messaging.message_payload_size_bytes");
+/// The kind of message consumption, `receive` or `process`.
+pub const MESSAGING_OPERATION: Key = Key::from_static_str("messaging.operation");
+
+// Exception attributes, recorded on `exception` events.
+
+/// The type of the exception, e.g. the error type name.
+pub const EXCEPTION_TYPE: Key = Key::from_static_str("exception.type");
+/// The exception message.
+pub const EXCEPTION_MESSAGE: Key = Key::from_static_str("exception.message");
+/// The stack trace or backtrace of the exception.
+pub const EXCEPTION_STACKTRACE: Key = Key::from_static_str("exception.stacktrace");
+
+// Attributes used by exporters to represent span data in formats without
+// native support for it.
+
+/// The kind of the span, e.g. `client` or `server`.
+pub const SPAN_KIND: Key = Key::from_static_str("span.kind");
+/// Set to `true` on spans that ended with an error status.
+pub const ERROR: Key = Key::from_static_str("error");
+/// The numeric status code of the span.
+pub const STATUS_CODE: Key = Key::from_static_str("status.code");
+/// The status message of the span.
+pub const STATUS_MESSAGE: Key = Key::from_static_str("status.message");
+
+// Sampler attributes, set by samplers on the spans they sample.
+
+/// The type of the sampler that sampled the span, e.g. `ratelimiting`.
+pub const SAMPLER_TYPE: Key = Key::from_static_str("sampler.type");
+/// The parameter of the sampler that sampled the span, e.g. the traces per
+/// second of a rate limiting sampler.
+pub const SAMPLER_PARAM: Key = Key::from_static_str("sampler.param");